@@ -19,7 +19,61 @@ pub enum Inline {
     Math(MathType, String),
     Link(Attr, Vec<Inline>, Target),
     Image(Attr, Vec<Inline>, Target),
-    Note(Vec<Block>),
+    /// A footnote: its label (`1` for `[^1]`), shared by every reference
+    /// to the same note and empty for an unlabelled one, and its content.
+    Note(String, Vec<Block>),
     Span(Attr, Vec<Inline>),
     RawInline(Format, String),
 }
+
+impl Inline {
+    /// The checkbox that starts a task-list item: a span of class `task`
+    /// holding a ballot box, `☐`, or `☒` with class `checked` as well.
+    pub fn task_checkbox(checked: bool) -> Inline {
+        let mut attr = Attr::empty();
+        attr.classes.push("task".to_string());
+        if checked {
+            attr.classes.push("checked".to_string());
+        }
+        let ballot = if checked { "\u{2612}" } else { "\u{2610}" };
+        Inline::Span(attr, vec![Inline::Str(ballot.to_string())])
+    }
+
+    /// For a task checkbox, whether it is checked; `None` for any other
+    /// inline.
+    pub fn task_state(&self) -> Option<bool> {
+        match self {
+            Inline::Span(attr, _) if attr.classes.iter().any(|c| c == "task") => {
+                Some(attr.classes.iter().any(|c| c == "checked"))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Plain text of inline content, dropping formatting. Quotes keep their
+/// marks, a link without text reads as its URL, and notes and raw inlines
+/// are left out.
+pub(crate) fn inlines_text(inlines: &[Inline]) -> String {
+    inlines
+        .iter()
+        .map(|i| match i {
+            Inline::Str(s) | Inline::Code(_, s) | Inline::Math(_, s) => s.clone(),
+            Inline::Space | Inline::SoftBreak | Inline::LineBreak => " ".to_string(),
+            Inline::Emph(inner)
+            | Inline::Strong(inner)
+            | Inline::Underline(inner)
+            | Inline::Strikeout(inner)
+            | Inline::Superscript(inner)
+            | Inline::Subscript(inner)
+            | Inline::SmallCaps(inner)
+            | Inline::Span(_, inner)
+            | Inline::Image(_, inner, _) => inlines_text(inner),
+            Inline::Quoted(QuoteType::SingleQuote, inner) => format!("\u{2018}{}\u{2019}", inlines_text(inner)),
+            Inline::Quoted(QuoteType::DoubleQuote, inner) => format!("\u{201C}{}\u{201D}", inlines_text(inner)),
+            Inline::Link(_, inner, target) if inner.is_empty() => target.url.clone(),
+            Inline::Link(_, inner, _) => inlines_text(inner),
+            Inline::Note(..) | Inline::RawInline(..) => String::new(),
+        })
+        .collect()
+}
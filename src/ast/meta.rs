@@ -1,7 +1,12 @@
 use std::collections::HashMap;
 
+use crate::utils::date::Date;
+
 use super::block::Block;
-use super::inline::Inline;
+use super::inline::{inlines_text, Inline};
+use super::table::{
+    Alignment, Caption, Cell, ColSpec, ColWidth, Row, Table, TableBody, TableFoot, TableHead,
+};
 
 #[derive(Debug, Clone)]
 pub struct Document {
@@ -9,12 +14,99 @@ pub struct Document {
     pub blocks: Vec<Block>,
 }
 
+impl Document {
+    /// URLs of images with no alt text, in document order.
+    pub fn images_missing_alt(&self) -> Vec<&str> {
+        let mut urls = Vec::new();
+        self.walk_inlines(|inline| {
+            if let Inline::Image(_, alt, target) = inline
+                && inlines_text(alt).trim().is_empty()
+            {
+                urls.push(target.url.as_str());
+            }
+        });
+        urls
+    }
+
+    /// Checked and total task-list items (items starting with a
+    /// [`Inline::task_checkbox`]), in all lists.
+    pub fn task_counts(&self) -> (usize, usize) {
+        let mut counts = (0, 0);
+        self.walk_blocks(|block| {
+            let (Block::BulletList(items) | Block::OrderedList(_, items)) = block else {
+                return;
+            };
+            for item in items {
+                if let Some(Block::Plain(inlines) | Block::Para(inlines)) = item.first()
+                    && let Some(checked) = inlines.first().and_then(Inline::task_state)
+                {
+                    counts.0 += usize::from(checked);
+                    counts.1 += 1;
+                }
+            }
+        });
+        counts
+    }
+
+    /// Replace `{{key}}` placeholders in body text with the values of the
+    /// `substitutions` metadata map. Code is left alone, as are placeholders
+    /// with no mapping.
+    pub fn apply_substitutions(&mut self) {
+        let substitutions = self.meta.substitutions();
+        if substitutions.is_empty() {
+            return;
+        }
+        self.walk_inlines_mut(|inline| {
+            if let Inline::Str(text) = inline
+                && text.contains("{{")
+            {
+                for (placeholder, value) in &substitutions {
+                    if text.contains(placeholder.as_str()) {
+                        *text = text.replace(placeholder.as_str(), value);
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Front-matter keys pandorust reads. `hN-space-before`/`hN-space-after`
+/// and anything under `metadata` are accepted as well.
+pub const KNOWN_META_KEYS: &[&str] = &[
+    "title",
+    "subtitle",
+    "author",
+    "date",
+    "abstract",
+    "fontsize",
+    "mainfont",
+    "monofont",
+    "lang",
+    "heading-space-before",
+    "heading-space-after",
+    "substitutions",
+    "css",
+    "lot",
+    "lof",
+    "linestretch",
+    "line-spacing",
+    "date-format",
+    "coverpage",
+];
+
 #[derive(Debug, Clone, Default)]
 pub struct Meta {
     pub entries: HashMap<String, MetaValue>,
 }
 
 impl Meta {
+    /// Add the entries of `defaults` that this metadata does not set.
+    pub fn merge_defaults(&mut self, defaults: &Meta) {
+        for (key, value) in &defaults.entries {
+            self.entries.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
+
     pub fn title(&self) -> Option<&str> {
         match self.entries.get("title") {
             Some(MetaValue::String(s)) => Some(s),
@@ -43,6 +135,37 @@ impl Meta {
         }
     }
 
+    /// The date as displayed in a title block. `\today` stands for the
+    /// current date. With `date-format` (strftime-style, such as
+    /// `"%B %e, %Y"`) an ISO `YYYY-MM-DD` date is reformatted; any other
+    /// date is shown as written.
+    pub fn display_date(&self) -> Option<String> {
+        let date = self.date()?;
+        let parsed = match date.trim() {
+            "\\today" => Some(Date::today()),
+            other => Date::parse_iso(other),
+        };
+        let Some(parsed) = parsed else {
+            return Some(date.to_string());
+        };
+        Some(match self.get_str("date-format") {
+            Some(format) => parsed.format(format),
+            None if date.trim() == "\\today" => parsed.to_iso(),
+            None => date.to_string(),
+        })
+    }
+
+    /// The document abstract as blocks. A plain string value is wrapped in a
+    /// single paragraph.
+    pub fn abstract_blocks(&self) -> Option<Vec<Block>> {
+        match self.entries.get("abstract") {
+            Some(MetaValue::Blocks(blocks)) => Some(blocks.clone()),
+            Some(MetaValue::Inlines(inlines)) => Some(vec![Block::Para(inlines.clone())]),
+            Some(MetaValue::String(s)) => Some(vec![Block::Para(vec![Inline::Str(s.clone())])]),
+            _ => None,
+        }
+    }
+
     /// Get any string metadata value by key.
     pub fn get_str(&self, key: &str) -> Option<&str> {
         match self.entries.get(key) {
@@ -50,6 +173,118 @@ impl Meta {
             _ => None,
         }
     }
+
+    /// Extra CSS from the `css` key: a string of rules, or a list of them
+    /// one per line.
+    pub fn css(&self) -> Option<String> {
+        let css = match self.entries.get("css")? {
+            MetaValue::List(rules) => rules
+                .iter()
+                .map(MetaValue::to_plain_text)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            other => other.to_plain_text(),
+        };
+        (!css.trim().is_empty()).then_some(css)
+    }
+
+    /// The `substitutions` map as (`{{key}}`, value) pairs. Keys may be
+    /// written bare (`client`) or with their braces (`"{{client}}"`).
+    pub fn substitutions(&self) -> Vec<(String, String)> {
+        let Some(MetaValue::Map(map)) = self.entries.get("substitutions") else {
+            return vec![];
+        };
+        let mut pairs: Vec<(String, String)> = map
+            .iter()
+            .map(|(key, value)| {
+                let name = key.trim();
+                let name = name
+                    .strip_prefix("{{")
+                    .and_then(|n| n.strip_suffix("}}"))
+                    .unwrap_or(name)
+                    .trim();
+                (format!("{{{{{}}}}}", name), value.to_plain_text())
+            })
+            .collect();
+        pairs.sort();
+        pairs
+    }
+
+    /// All entries as a two-column key/value table, sorted by key, with a
+    /// "Key"/"Value" header row. Returns `None` when there is no metadata.
+    pub fn to_table(&self) -> Option<Table> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let text_cell = |text: &str| cell(vec![Block::Plain(vec![Inline::Str(text.to_string())])]);
+
+        let mut keys: Vec<&String> = self.entries.keys().collect();
+        keys.sort();
+        let body = keys
+            .into_iter()
+            .map(|key| {
+                let value = match &self.entries[key] {
+                    MetaValue::Inlines(inlines) => cell(vec![Block::Plain(inlines.clone())]),
+                    MetaValue::Blocks(blocks) => cell(blocks.clone()),
+                    other => text_cell(&other.to_plain_text()),
+                };
+                Row {
+                    attr: Attr::empty(),
+                    cells: vec![text_cell(key), value],
+                }
+            })
+            .collect();
+
+        let col = || ColSpec {
+            align: Alignment::AlignDefault,
+            width: ColWidth::Default,
+        };
+        Some(Table {
+            attr: Attr::empty(),
+            caption: Caption::default(),
+            col_specs: vec![col(), col()],
+            head: TableHead {
+                attr: Attr::empty(),
+                rows: vec![Row {
+                    attr: Attr::empty(),
+                    cells: vec![text_cell("Key"), text_cell("Value")],
+                }],
+            },
+            bodies: vec![TableBody {
+                attr: Attr::empty(),
+                row_head_columns: 1,
+                head: vec![],
+                body,
+            }],
+            foot: TableFoot {
+                attr: Attr::empty(),
+                rows: vec![],
+            },
+        })
+    }
+
+    /// Keys pandorust does not recognize (see [`KNOWN_META_KEYS`]), sorted.
+    /// Useful for catching typos like `titel`.
+    pub fn unknown_keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = self
+            .entries
+            .keys()
+            .map(String::as_str)
+            .filter(|key| !is_known_key(key))
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    /// Get a boolean metadata value by key. The strings `"true"` and
+    /// `"false"` are accepted as well.
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        match self.entries.get(key) {
+            Some(MetaValue::Bool(b)) => Some(*b),
+            Some(MetaValue::String(s)) => s.parse().ok(),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -62,6 +297,62 @@ pub enum MetaValue {
     Blocks(Vec<Block>),
 }
 
+impl MetaValue {
+    /// Flatten the value to plain text. Lists are comma-joined and maps
+    /// become comma-joined `key: value` pairs in key order.
+    pub fn to_plain_text(&self) -> String {
+        match self {
+            MetaValue::String(s) => s.clone(),
+            MetaValue::Bool(b) => b.to_string(),
+            MetaValue::List(items) => items
+                .iter()
+                .map(MetaValue::to_plain_text)
+                .collect::<Vec<_>>()
+                .join(", "),
+            MetaValue::Map(map) => {
+                let mut pairs: Vec<_> = map.iter().collect();
+                pairs.sort_by(|a, b| a.0.cmp(b.0));
+                pairs
+                    .into_iter()
+                    .map(|(k, v)| format!("{}: {}", k, v.to_plain_text()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }
+            MetaValue::Inlines(inlines) => inlines_text(inlines),
+            MetaValue::Blocks(blocks) => blocks
+                .iter()
+                .filter_map(|b| match b {
+                    Block::Para(inlines) | Block::Plain(inlines) => Some(inlines_text(inlines)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+}
+
+fn is_known_key(key: &str) -> bool {
+    if KNOWN_META_KEYS.contains(&key) || key == "metadata" || key.starts_with("metadata.") {
+        return true;
+    }
+    // Per-level heading spacing: h1-space-before .. h6-space-after
+    key.strip_prefix('h')
+        .and_then(|rest| rest.split_once("-space-"))
+        .is_some_and(|(level, side)| {
+            matches!(level, "1" | "2" | "3" | "4" | "5" | "6") && matches!(side, "before" | "after")
+        })
+}
+
+fn cell(content: Vec<Block>) -> Cell {
+    Cell {
+        attr: Attr::empty(),
+        align: Alignment::AlignDefault,
+        row_span: 1,
+        col_span: 1,
+        content,
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct Attr {
     pub id: String,
@@ -2,6 +2,7 @@ pub mod block;
 pub mod inline;
 pub mod meta;
 pub mod table;
+pub mod walk;
 
 pub use block::Block;
 pub use inline::Inline;
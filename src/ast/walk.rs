@@ -0,0 +1,210 @@
+use super::{Block, Document, Inline, Row, Table};
+
+impl Document {
+    /// Call `f` on every block in the body, parents before their children.
+    pub fn walk_blocks<'a>(&'a self, mut f: impl FnMut(&'a Block)) {
+        walk_blocks(&self.blocks, &mut f);
+    }
+
+    /// Call `f` on every inline in the body, including those in notes.
+    pub fn walk_inlines<'a>(&'a self, mut f: impl FnMut(&'a Inline)) {
+        walk_inlines(&self.blocks, &mut f);
+    }
+
+    /// Call `f` on every block in the body, parents before their children.
+    /// Children are visited as `f` left them.
+    pub fn walk_blocks_mut(&mut self, mut f: impl FnMut(&mut Block)) {
+        walk_blocks_mut(&mut self.blocks, &mut f);
+    }
+
+    /// Call `f` on every inline in the body, including those in notes.
+    pub fn walk_inlines_mut(&mut self, mut f: impl FnMut(&mut Inline)) {
+        walk_inlines_mut(&mut self.blocks, &mut f);
+    }
+}
+
+/// Call `f` on every block in `blocks` and below, parents first. The walk
+/// reaches list items, definition lists, table cells and captions, figures,
+/// divs and the contents of notes.
+pub fn walk_blocks<'a>(blocks: &'a [Block], f: &mut dyn FnMut(&'a Block)) {
+    Walk { block: f, inline: &mut |_| {} }.blocks(blocks);
+}
+
+/// Call `f` on every inline in `blocks` and below, parents first.
+pub fn walk_inlines<'a>(blocks: &'a [Block], f: &mut dyn FnMut(&'a Inline)) {
+    Walk { block: &mut |_| {}, inline: f }.blocks(blocks);
+}
+
+/// Mutable [`walk_blocks`].
+pub fn walk_blocks_mut(blocks: &mut [Block], f: &mut dyn FnMut(&mut Block)) {
+    WalkMut { block: f, inline: &mut |_| {} }.blocks(blocks);
+}
+
+/// Mutable [`walk_inlines`].
+pub fn walk_inlines_mut(blocks: &mut [Block], f: &mut dyn FnMut(&mut Inline)) {
+    WalkMut { block: &mut |_| {}, inline: f }.blocks(blocks);
+}
+
+struct Walk<'f, 'a> {
+    block: &'f mut dyn FnMut(&'a Block),
+    inline: &'f mut dyn FnMut(&'a Inline),
+}
+
+impl<'a> Walk<'_, 'a> {
+    fn blocks(&mut self, blocks: &'a [Block]) {
+        for block in blocks {
+            self.block(block);
+        }
+    }
+
+    fn block(&mut self, block: &'a Block) {
+        (self.block)(block);
+        match block {
+            Block::Plain(inlines) | Block::Para(inlines) | Block::Heading(_, _, inlines) => {
+                self.inlines(inlines)
+            }
+            Block::LineBlock(lines) => lines.iter().for_each(|line| self.inlines(line)),
+            Block::BlockQuote(inner) | Block::Div(_, inner) => self.blocks(inner),
+            Block::BulletList(items) | Block::OrderedList(_, items) => {
+                items.iter().for_each(|item| self.blocks(item))
+            }
+            Block::DefinitionList(items) => {
+                for (term, defs) in items {
+                    self.inlines(term);
+                    defs.iter().for_each(|def| self.blocks(def));
+                }
+            }
+            Block::Table(table) => self.table(table),
+            Block::Figure(_, caption, inner) => {
+                if let Some(short) = &caption.short {
+                    self.inlines(short);
+                }
+                self.blocks(&caption.long);
+                self.blocks(inner);
+            }
+            Block::CodeBlock(..) | Block::RawBlock(..) | Block::HorizontalRule | Block::PageBreak => {}
+        }
+    }
+
+    fn table(&mut self, table: &'a Table) {
+        if let Some(short) = &table.caption.short {
+            self.inlines(short);
+        }
+        self.blocks(&table.caption.long);
+        self.rows(&table.head.rows);
+        for body in &table.bodies {
+            self.rows(&body.head);
+            self.rows(&body.body);
+        }
+        self.rows(&table.foot.rows);
+    }
+
+    fn rows(&mut self, rows: &'a [Row]) {
+        for row in rows {
+            row.cells.iter().for_each(|cell| self.blocks(&cell.content));
+        }
+    }
+
+    fn inlines(&mut self, inlines: &'a [Inline]) {
+        for inline in inlines {
+            (self.inline)(inline);
+            match inline {
+                Inline::Emph(inner)
+                | Inline::Strong(inner)
+                | Inline::Underline(inner)
+                | Inline::Strikeout(inner)
+                | Inline::Superscript(inner)
+                | Inline::Subscript(inner)
+                | Inline::SmallCaps(inner)
+                | Inline::Quoted(_, inner)
+                | Inline::Link(_, inner, _)
+                | Inline::Image(_, inner, _)
+                | Inline::Span(_, inner) => self.inlines(inner),
+                Inline::Note(_, blocks) => self.blocks(blocks),
+                _ => {}
+            }
+        }
+    }
+}
+
+struct WalkMut<'f> {
+    block: &'f mut dyn FnMut(&mut Block),
+    inline: &'f mut dyn FnMut(&mut Inline),
+}
+
+impl WalkMut<'_> {
+    fn blocks(&mut self, blocks: &mut [Block]) {
+        for block in blocks {
+            self.block(block);
+        }
+    }
+
+    fn block(&mut self, block: &mut Block) {
+        (self.block)(block);
+        match block {
+            Block::Plain(inlines) | Block::Para(inlines) | Block::Heading(_, _, inlines) => {
+                self.inlines(inlines)
+            }
+            Block::LineBlock(lines) => lines.iter_mut().for_each(|line| self.inlines(line)),
+            Block::BlockQuote(inner) | Block::Div(_, inner) => self.blocks(inner),
+            Block::BulletList(items) | Block::OrderedList(_, items) => {
+                items.iter_mut().for_each(|item| self.blocks(item))
+            }
+            Block::DefinitionList(items) => {
+                for (term, defs) in items {
+                    self.inlines(term);
+                    defs.iter_mut().for_each(|def| self.blocks(def));
+                }
+            }
+            Block::Table(table) => self.table(table),
+            Block::Figure(_, caption, inner) => {
+                if let Some(short) = &mut caption.short {
+                    self.inlines(short);
+                }
+                self.blocks(&mut caption.long);
+                self.blocks(inner);
+            }
+            Block::CodeBlock(..) | Block::RawBlock(..) | Block::HorizontalRule | Block::PageBreak => {}
+        }
+    }
+
+    fn table(&mut self, table: &mut Table) {
+        if let Some(short) = &mut table.caption.short {
+            self.inlines(short);
+        }
+        self.blocks(&mut table.caption.long);
+        self.rows(&mut table.head.rows);
+        for body in &mut table.bodies {
+            self.rows(&mut body.head);
+            self.rows(&mut body.body);
+        }
+        self.rows(&mut table.foot.rows);
+    }
+
+    fn rows(&mut self, rows: &mut [Row]) {
+        for row in rows {
+            row.cells.iter_mut().for_each(|cell| self.blocks(&mut cell.content));
+        }
+    }
+
+    fn inlines(&mut self, inlines: &mut [Inline]) {
+        for inline in inlines {
+            (self.inline)(inline);
+            match inline {
+                Inline::Emph(inner)
+                | Inline::Strong(inner)
+                | Inline::Underline(inner)
+                | Inline::Strikeout(inner)
+                | Inline::Superscript(inner)
+                | Inline::Subscript(inner)
+                | Inline::SmallCaps(inner)
+                | Inline::Quoted(_, inner)
+                | Inline::Link(_, inner, _)
+                | Inline::Image(_, inner, _)
+                | Inline::Span(_, inner) => self.inlines(inner),
+                Inline::Note(_, blocks) => self.blocks(blocks),
+                _ => {}
+            }
+        }
+    }
+}
@@ -1,12 +1,18 @@
 use clap::Parser;
 use std::fs;
 use std::io::{self, Read};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use pandorust::readers::markdown::read_markdown;
+use pandorust::ast::{Block, Document, Inline};
+use pandorust::readers::asciidoc::read_asciidoc_with;
+use pandorust::readers::include::expand_includes;
+use pandorust::readers::markdown::{read_markdown_with, read_metadata};
+use pandorust::readers::options::ReaderOptions;
 use pandorust::utils::error::{PandorustError, Result};
-use pandorust::writers::docx::write_docx;
-use pandorust::writers::html::write_html;
+use pandorust::utils::resources::{find_resource, is_local_resource};
+use pandorust::writers::docx::write_docx_with;
+use pandorust::writers::html::write_html_with;
+use pandorust::writers::options::{EmbeddedFont, HtmlFormat, MathMethod, WriterOptions};
 
 #[derive(Parser)]
 #[command(
@@ -14,15 +20,17 @@ use pandorust::writers::html::write_html;
     version,
     about = "A pure-Rust document converter — single binary, no runtime dependencies.",
     long_about = "A pure-Rust document converter — single binary, no runtime dependencies.\n\n\
-        Converts Markdown to HTML or DOCX. Supports YAML front matter for metadata\n\
-        (title, author, date, fontsize), pandoc-style grid tables, and fenced divs.\n\n\
-        INPUT FORMATS:  markdown (md)\n\
+        Converts Markdown or AsciiDoc to HTML or DOCX. Supports YAML front matter\n\
+        for metadata (title, author, date, fontsize), pandoc-style grid tables, and\n\
+        fenced divs.\n\n\
+        INPUT FORMATS:  markdown (md, gfm, commonmark), asciidoc (adoc, asc)\n\
         OUTPUT FORMATS: html, docx\n\n\
         Use \"-\" as input to read from stdin. Formats auto-detect from file extensions.",
     after_help = "\
 EXAMPLES:\n\
   pandorust input.md -o output.html          Convert Markdown to HTML\n\
   pandorust input.md -o output.docx          Convert Markdown to DOCX\n\
+  pandorust notes.adoc -o notes.html         Convert AsciiDoc to HTML\n\
   pandorust input.md -o out.html -t html     Explicit output format\n\
   pandorust data.txt -f md -t html -o o.html Non-standard extension with format flags\n\
   cat input.md | pandorust - -t html -o o.html  Read from stdin\n\n\
@@ -36,12 +44,15 @@ YAML FRONT MATTER:\n\
   title    → HTML <title>, DOCX core properties\n\
   author   → DOCX core properties\n\
   date     → DOCX core properties\n\
-  fontsize → body text size (default: 12pt). DOCX uses half-points (11pt=22).\n\n\
+  fontsize → body text size (default: 12pt). DOCX uses half-points (11pt=22).\n\
+  abstract → abstract block under the title (Markdown allowed)\n\
+  heading-space-before/-after → DOCX heading spacing in points (h1-space-before...)\n\n\
 SUPPORTED MARKDOWN FEATURES:\n\
   GFM (GitHub Flavored Markdown), pipe tables, grid tables (+---+---+),\n\
   fenced code blocks, blockquotes, ordered/unordered lists, inline formatting\n\
   (bold, italic, strikethrough, code, links, images), horizontal rules,\n\
-  YAML front matter, fenced divs (::: syntax), \\newpage.\n\n\
+  YAML front matter, fenced divs (::: syntax), \\newpage,\n\
+  TeX math ($...$ and $$...$$), definition lists (Term / : Definition).\n\n\
 EXIT CODES:\n\
   0  Success\n\
   1  Error (details on stderr)"
@@ -51,17 +62,194 @@ struct Cli {
     input: Option<String>,
 
     /// Output file path (required). Extension determines format unless -t is set.
+    /// Repeat to write several formats from one parse (-o out.html -o out.docx).
     #[arg(short, long)]
-    output: Option<String>,
+    output: Vec<String>,
 
-    /// Input format: markdown, md. Auto-detected from extension if omitted.
+    /// Input format: markdown (md, gfm, commonmark), asciidoc (adoc). Auto-detected from extension if omitted.
     #[arg(short = 'f', long, value_name = "FORMAT")]
     from: Option<String>,
 
-    /// Output format: html, docx. Auto-detected from extension if omitted.
+    /// Output format: html (htm), docx (word). Auto-detected from extension if omitted.
     #[arg(short = 't', long, value_name = "FORMAT")]
     to: Option<String>,
 
+    /// Class prefix for code block languages in HTML (default: "language-").
+    /// An empty prefix emits the bare language name as the class.
+    #[arg(long, value_name = "PREFIX")]
+    highlight_prefix: Option<String>,
+
+    /// How to render TeX math in HTML: "tex" keeps \(...\) delimiters for a
+    /// JavaScript renderer, "mathml" converts it to MathML at conversion time.
+    #[arg(long, value_name = "MODE", value_parser = ["tex", "mathml"])]
+    math: Option<String>,
+
+    /// Remove HTML comments (<!-- ... -->) from the HTML output.
+    #[arg(long)]
+    strip_comments: bool,
+
+    /// Make HTML safe for untrusted input: drop raw HTML, event-handler
+    /// attributes and front matter css, and neutralize javascript: links.
+    #[arg(long)]
+    sanitize: bool,
+
+    /// Layout of the HTML source: "compact" (default), "pretty" indents
+    /// nested elements, "minify" drops whitespace between them.
+    #[arg(long, value_name = "FORMAT", value_parser = ["compact", "pretty", "minify"])]
+    html_format: Option<String>,
+
+    /// Write non-ASCII characters in HTML as numeric entities (&#160;)
+    /// instead of raw UTF-8.
+    #[arg(long)]
+    numeric_entities: bool,
+
+    /// Write ASCII-only HTML: non-ASCII characters become numeric entities,
+    /// and CSS or script escapes in stylesheets and scripts.
+    #[arg(long)]
+    ascii: bool,
+
+    /// Show all front matter as a key/value table at the top of the body.
+    #[arg(long)]
+    show_metadata_table: bool,
+
+    /// Leave ==text== as literal text instead of highlighting it.
+    #[arg(long)]
+    no_highlight: bool,
+
+    /// Use typographic apostrophes in contractions (don't → don’t).
+    #[arg(long)]
+    smart: bool,
+
+    /// Parse CriticMarkup review marks ({++insert++}, {--delete--},
+    /// {~~old~>new~~}, {==highlight==}, {>>comment<<}).
+    #[arg(long)]
+    criticmarkup: bool,
+
+    /// Read Markdown with GitHub extensions: tables, strikethrough, task
+    /// lists (the default).
+    #[arg(long, conflicts_with = "commonmark")]
+    gfm: bool,
+
+    /// Read strict CommonMark, where ~~ and | tables stay literal. Also
+    /// selected by -f commonmark.
+    #[arg(long)]
+    commonmark: bool,
+
+    /// Read four-space-indented lines as ordinary text instead of a code
+    /// block; only fenced code is code.
+    #[arg(long)]
+    no_indented_code: bool,
+
+    /// Read raw HTML <table> blocks as real tables, so they reach formats
+    /// such as DOCX that drop raw HTML.
+    #[arg(long)]
+    parse_raw_html_tables: bool,
+
+    /// Directories to search, in order, for images and !include files that
+    /// are not found next to the input file. Separated by ':' (';' on Windows).
+    #[arg(long, value_name = "DIRS")]
+    resource_path: Option<String>,
+
+    /// Replace emoji shortcodes such as :tada: or :+1: with emoji.
+    #[arg(long)]
+    emoji: bool,
+
+    /// Render GitHub alert blockquotes ("> [!NOTE]", "> [!WARNING]", ...) as
+    /// titled callouts.
+    #[arg(long)]
+    github_alerts: bool,
+
+    /// Expand "!include path/to/file.md" lines in Markdown input, relative to
+    /// the including file. Off by default: includes can read any file.
+    #[arg(long)]
+    enable_includes: bool,
+
+    /// Continue ordered-list numbering across interrupting paragraphs instead
+    /// of restarting at 1. A heading starts a new count.
+    #[arg(long)]
+    continue_lists: bool,
+
+    /// Render a final "— Author" paragraph in a blockquote as a citation.
+    #[arg(long)]
+    cite_attribution: bool,
+
+    /// Let Word auto-fit DOCX tables to their content and the page width
+    /// instead of fixed equal-width columns.
+    #[arg(long)]
+    fit_tables: bool,
+
+    /// Keep tab characters in code blocks instead of expanding them to spaces.
+    #[arg(long)]
+    preserve_tabs: bool,
+
+    /// Columns per tab stop when expanding tabs in code blocks.
+    #[arg(long, default_value_t = 4)]
+    tab_stop: usize,
+
+    /// Append a "Tasks: 3/5 complete (60%)" summary of task-list items
+    /// ("- [x] done", "- [ ] todo") to the end of the document.
+    #[arg(long)]
+    task_summary: bool,
+
+    /// Warn about images without alt text (accessibility audit).
+    #[arg(long)]
+    require_alt: bool,
+
+    /// Warn about front matter keys pandorust does not recognize (typos like
+    /// "titel"). Keys under "metadata" are always allowed.
+    #[arg(long)]
+    strict_metadata: bool,
+
+    /// Prepend a UTF-8 byte order mark to text outputs (HTML). Off by default;
+    /// some Windows tools need it to detect UTF-8. Binary outputs are unaffected.
+    #[arg(long)]
+    add_bom: bool,
+
+    /// Embed the font file given by --font-file in HTML output as the main
+    /// font, so the page renders the same without the font installed.
+    #[arg(long, requires = "font_file")]
+    embed_fonts: bool,
+
+    /// Font file (.woff2, .woff, .ttf or .otf) for --embed-fonts.
+    #[arg(long, value_name = "PATH")]
+    font_file: Option<String>,
+
+    /// Print the parsed document tree to stdout and exit without writing
+    /// any output. Useful for debugging and bug reports.
+    #[arg(long)]
+    dump_ast: bool,
+
+    /// Put the DOCX title block on a cover page of its own (also set by
+    /// "coverpage: true" metadata).
+    #[arg(long)]
+    cover_page: bool,
+
+    /// Leave the title, author and date out of the document body. They are
+    /// still read, so HTML keeps its <title>.
+    #[arg(long)]
+    strip_metadata_block: bool,
+
+    /// Insert the contents of FILE verbatim at the start of the body: raw
+    /// HTML in HTML output, plain paragraphs in DOCX. May be repeated.
+    #[arg(long, value_name = "FILE")]
+    include_before_body: Vec<String>,
+
+    /// Insert the contents of FILE verbatim at the end of the body, like
+    /// --include-before-body. May be repeated.
+    #[arg(long, value_name = "FILE")]
+    include_after_body: Vec<String>,
+
+    /// Prepend PREFIX to every HTML id (headings, footnotes, figures) and
+    /// to the links that point at them, so several documents can share a
+    /// page.
+    #[arg(long, value_name = "PREFIX")]
+    id_prefix: Option<String>,
+
+    /// Read metadata from a YAML or JSON file, for values shared by many
+    /// documents. The document's own front matter takes precedence.
+    #[arg(long, value_name = "FILE")]
+    metadata_file: Option<String>,
+
     /// List supported input and output formats, then exit.
     #[arg(long)]
     list_formats: bool,
@@ -73,10 +261,17 @@ fn main() {
     if cli.list_formats {
         println!("Input formats:");
         println!("  markdown  (.md)   GitHub Flavored Markdown with YAML front matter");
+        println!("            aliases: md, gfm, commonmark");
+        println!("  asciidoc  (.adoc) AsciiDoc (common subset)");
+        println!("            aliases: adoc, asc");
         println!();
         println!("Output formats:");
         println!("  html      (.html) Styled HTML with embedded CSS");
+        println!("            aliases: htm");
         println!("  docx      (.docx) Microsoft Word (Open XML)");
+        println!("            aliases: word");
+        println!();
+        println!("Format names are case-insensitive.");
         return;
     }
 
@@ -87,64 +282,267 @@ fn main() {
             std::process::exit(1);
         }
     };
-    let output_path = match &cli.output {
-        Some(o) => o.clone(),
-        None => {
+
+    // Check the outputs before reading, so a bad command line fails fast
+    let outputs = if cli.dump_ast {
+        Vec::new()
+    } else {
+        if cli.output.is_empty() {
             eprintln!("Error: --output <OUTPUT> is required. Run with --help for usage.");
             std::process::exit(1);
         }
+        match output_formats(&cli) {
+            Ok(outputs) => outputs,
+            Err(message) => {
+                eprintln!("Error: {}", message);
+                std::process::exit(1);
+            }
+        }
+    };
+
+    let doc = match read_document(&input_path, &cli) {
+        Ok((doc, warnings)) => {
+            for warning in warnings {
+                eprintln!("Warning: {}", warning);
+            }
+            doc
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
     };
+    if cli.dump_ast {
+        println!("{:#?}", doc);
+        return;
+    }
 
-    if let Err(e) = run(&input_path, &output_path, &cli) {
+    if let Err(e) = run(doc, &outputs, &cli) {
         eprintln!("Error: {}", e);
         std::process::exit(1);
     }
 }
 
-fn run(input_path: &str, output_path: &str, cli: &Cli) -> Result<()> {
-    let from_fmt = cli
-        .from
-        .clone()
-        .unwrap_or_else(|| detect_format(input_path));
-    let to_fmt = cli.to.clone().unwrap_or_else(|| detect_format(output_path));
+/// Read and parse the input file (or stdin for "-") into a Document, along
+/// with any warnings about it for the caller to report.
+fn read_document(input_path: &str, cli: &Cli) -> Result<(Document, Vec<String>)> {
+    let from_fmt = match &cli.from {
+        Some(f) => normalize_format(f),
+        None => normalize_format(&detect_format(input_path)),
+    };
 
-    // Read input: from stdin if "-", otherwise from file
-    let input = if input_path == "-" {
-        let mut buf = String::new();
+    // Read input: from stdin if "-", otherwise from file. Read bytes first so
+    // a binary file gets a clear error rather than an opaque IO one.
+    let bytes = if input_path == "-" {
+        let mut buf = Vec::new();
         io::stdin()
-            .read_to_string(&mut buf)
+            .read_to_end(&mut buf)
             .map_err(PandorustError::Io)?;
         buf
     } else {
-        fs::read_to_string(input_path).map_err(PandorustError::Io)?
+        fs::read(input_path).map_err(PandorustError::Io)?
+    };
+    let input = String::from_utf8(bytes).map_err(|_| {
+        let name = if input_path == "-" { "<stdin>" } else { input_path };
+        PandorustError::InvalidUtf8(name.to_string())
+    })?;
+
+    let resource_path: Vec<PathBuf> = cli
+        .resource_path
+        .as_deref()
+        .map(|dirs| std::env::split_paths(dirs).collect())
+        .unwrap_or_default();
+
+    let input = if cli.enable_includes && from_fmt == "markdown" {
+        let path = (input_path != "-").then(|| Path::new(input_path));
+        expand_includes(&input, path, &resource_path)?
+    } else {
+        input
     };
 
     // Parse
+    let mut reader_options = ReaderOptions {
+        highlight: !cli.no_highlight,
+        continue_lists: cli.continue_lists,
+        smart: cli.smart,
+        criticmarkup: cli.criticmarkup,
+        emoji: cli.emoji,
+        commonmark: cli.commonmark
+            || cli.from.as_deref().is_some_and(|f| f.eq_ignore_ascii_case("commonmark")),
+        github_alerts: cli.github_alerts,
+        indented_code: !cli.no_indented_code,
+        html_tables: cli.parse_raw_html_tables,
+        ..Default::default()
+    };
+    if let Some(path) = &cli.metadata_file {
+        let bytes = fs::read(path).map_err(PandorustError::Io)?;
+        let text = String::from_utf8(bytes).map_err(|_| PandorustError::InvalidUtf8(path.clone()))?;
+        reader_options.metadata = read_metadata(&text, &reader_options)?;
+    }
     let doc = match from_fmt.as_str() {
-        "md" | "markdown" => read_markdown(&input)?,
+        "markdown" => read_markdown_with(&input, &reader_options)?,
+        "asciidoc" => read_asciidoc_with(&input, &reader_options)?,
         other => {
             return Err(PandorustError::UnsupportedInputFormat(other.to_string()))
         }
     };
 
-    // Write
-    match to_fmt.as_str() {
-        "html" => {
-            let html = write_html(&doc);
-            fs::write(output_path, html).map_err(PandorustError::Io)?;
+    let mut warnings = Vec::new();
+    if cli.require_alt {
+        for url in doc.images_missing_alt() {
+            warnings.push(format!("image '{}' has no alt text", url));
         }
-        "docx" => {
-            let bytes = write_docx(&doc)?;
-            fs::write(output_path, bytes).map_err(PandorustError::Io)?;
+    }
+
+    // Report local images that cannot be found next to the input or on the
+    // resource path
+    if cli.resource_path.is_some() {
+        let base_dir = match Path::new(input_path).parent() {
+            Some(dir) if input_path != "-" => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let mut missing = Vec::new();
+        doc.walk_inlines(|inline| {
+            if let Inline::Image(_, _, target) = inline
+                && is_local_resource(&target.url)
+                && find_resource(&target.url, &base_dir, &resource_path).is_none()
+            {
+                missing.push(target.url.clone());
+            }
+        });
+        for url in missing {
+            warnings.push(format!(
+                "could not find image '{}' (searched the input directory and --resource-path)",
+                url
+            ));
         }
-        other => {
-            return Err(PandorustError::UnsupportedOutputFormat(other.to_string()))
+    }
+
+    if cli.strict_metadata {
+        for key in doc.meta.unknown_keys() {
+            warnings.push(format!("unknown front matter key '{}'", key));
+        }
+    }
+
+    Ok((doc, warnings))
+}
+
+/// Pair each output path with its format. With several outputs, each format
+/// comes from the file extension and may be written only once.
+fn output_formats(cli: &Cli) -> std::result::Result<Vec<(String, String)>, String> {
+    if cli.output.len() > 1 && cli.to.is_some() {
+        return Err("-t cannot be combined with several outputs; formats come from their extensions".to_string());
+    }
+    let mut outputs: Vec<(String, String)> = Vec::new();
+    for path in &cli.output {
+        let format = match &cli.to {
+            Some(t) => normalize_format(t),
+            None => normalize_format(&detect_format(path)),
+        };
+        if let Some((other, _)) = outputs.iter().find(|(_, f)| *f == format) {
+            return Err(format!(
+                "outputs '{}' and '{}' are both {}; give each format once",
+                other, path, format
+            ));
+        }
+        if !matches!(format.as_str(), "html" | "docx") {
+            return Err(PandorustError::UnsupportedOutputFormat(format).to_string());
+        }
+        outputs.push((path.clone(), format));
+    }
+    Ok(outputs)
+}
+
+/// Write the parsed document to every `(path, format)` output.
+fn run(mut doc: Document, outputs: &[(String, String)], cli: &Cli) -> Result<()> {
+    if cli.show_metadata_table
+        && let Some(table) = doc.meta.to_table()
+    {
+        doc.blocks.insert(0, Block::Table(table));
+    }
+
+    let embed_font = match (&cli.font_file, cli.embed_fonts) {
+        (Some(path), true) => {
+            let data = fs::read(path).map_err(PandorustError::Io)?;
+            Some(
+                EmbeddedFont::from_extension(&detect_format(path), data)
+                    .ok_or_else(|| PandorustError::UnsupportedFontFormat(path.clone()))?,
+            )
+        }
+        _ => None,
+    };
+    let writer_options = WriterOptions {
+        highlight_prefix: cli
+            .highlight_prefix
+            .clone()
+            .unwrap_or_else(|| WriterOptions::default().highlight_prefix),
+        math: match cli.math.as_deref() {
+            Some("mathml") => MathMethod::MathMl,
+            _ => MathMethod::Tex,
+        },
+        cite_attribution: cli.cite_attribution,
+        strip_comments: cli.strip_comments,
+        sanitize: cli.sanitize,
+        fit_tables: cli.fit_tables,
+        task_summary: cli.task_summary,
+        preserve_tabs: cli.preserve_tabs,
+        tab_stop: cli.tab_stop,
+        embed_font,
+        html_format: match cli.html_format.as_deref() {
+            Some("pretty") => HtmlFormat::Pretty,
+            Some("minify") => HtmlFormat::Minify,
+            _ => HtmlFormat::Compact,
+        },
+        numeric_entities: cli.numeric_entities,
+        ascii: cli.ascii,
+        cover_page: cli.cover_page,
+        strip_metadata_block: cli.strip_metadata_block,
+        include_before_body: read_includes(&cli.include_before_body)?,
+        include_after_body: read_includes(&cli.include_after_body)?,
+        id_prefix: cli.id_prefix.clone().unwrap_or_default(),
+    };
+
+    // Write
+    for (output_path, format) in outputs {
+        match format.as_str() {
+            "html" => {
+                let html = write_html_with(&doc, &writer_options);
+                write_text(output_path, &html, cli.add_bom)?;
+            }
+            "docx" => {
+                let bytes = write_docx_with(&doc, &writer_options)?;
+                fs::write(output_path, bytes).map_err(PandorustError::Io)?;
+            }
+            other => {
+                return Err(PandorustError::UnsupportedOutputFormat(other.to_string()))
+            }
         }
     }
 
     Ok(())
 }
 
+/// Read the files given to --include-before-body / --include-after-body.
+fn read_includes(paths: &[String]) -> Result<Vec<String>> {
+    paths
+        .iter()
+        .map(|path| {
+            let bytes = fs::read(path).map_err(PandorustError::Io)?;
+            String::from_utf8(bytes).map_err(|_| PandorustError::InvalidUtf8(path.clone()))
+        })
+        .collect()
+}
+
+/// Write a text output as UTF-8, optionally preceded by a byte order mark.
+fn write_text(path: &str, text: &str, add_bom: bool) -> Result<()> {
+    let mut bytes = Vec::with_capacity(text.len() + 3);
+    if add_bom {
+        bytes.extend_from_slice(b"\xEF\xBB\xBF");
+    }
+    bytes.extend_from_slice(text.as_bytes());
+    fs::write(path, bytes).map_err(PandorustError::Io)
+}
+
 fn detect_format(path: &str) -> String {
     Path::new(path)
         .extension()
@@ -152,3 +550,18 @@ fn detect_format(path: &str) -> String {
         .unwrap_or("")
         .to_lowercase()
 }
+
+/// Map a format name to its canonical form, ignoring case and accepting
+/// common aliases and file extensions (`htm` → `html`, `word` → `docx`,
+/// `md`/`mkd`/`mdown`/`mkdn`/`gfm`/`commonmark` → `markdown`, `adoc`/`asc` →
+/// `asciidoc`). Unknown names are returned lowercased.
+fn normalize_format(name: &str) -> String {
+    let lower = name.to_lowercase();
+    match lower.as_str() {
+        "md" | "mkd" | "mdown" | "mkdn" | "gfm" | "commonmark" => "markdown".to_string(),
+        "adoc" | "asc" => "asciidoc".to_string(),
+        "htm" => "html".to_string(),
+        "word" => "docx".to_string(),
+        _ => lower,
+    }
+}
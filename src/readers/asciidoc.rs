@@ -0,0 +1,363 @@
+use crate::ast::*;
+use crate::readers::options::ReaderOptions;
+use crate::utils::error::Result;
+
+/// Parse an AsciiDoc document into a Document AST.
+///
+/// Handles the common subset: a `= Title` header with an optional author line
+/// and `:name: value` attributes, `==` section headings, paragraphs with
+/// `*bold*`, `_italic_` and `` `mono` `` text, `*`/`-` bullet and `.`
+/// numbered lists, `----` listing blocks (`[source,lang]` sets the language),
+/// `|===` tables, `'''` rules and `<<<` page breaks. Anything else is read as
+/// paragraph text.
+pub fn read_asciidoc(input: &str) -> Result<Document> {
+    read_asciidoc_with(input, &ReaderOptions::default())
+}
+
+/// Parse an AsciiDoc document with the given options. Only the metadata
+/// defaults apply; the other options are Markdown syntax.
+pub fn read_asciidoc_with(input: &str, options: &ReaderOptions) -> Result<Document> {
+    let lines: Vec<&str> = input.lines().collect();
+    let mut meta = Meta::default();
+    let mut i = skip_blank(&lines, 0);
+
+    if let Some(title) = lines.get(i).and_then(|l| l.strip_prefix("= ")) {
+        meta.entries
+            .insert("title".to_string(), MetaValue::String(title.trim().to_string()));
+        i += 1;
+        // An author line may follow the title: "Jane Doe <jane@example.com>"
+        if let Some(line) = lines.get(i)
+            && !line.trim().is_empty()
+            && attribute_entry(line).is_none()
+        {
+            let author = line.split('<').next().unwrap_or(line).trim();
+            meta.entries
+                .insert("author".to_string(), MetaValue::String(author.to_string()));
+            i += 1;
+        }
+        while let Some(line) = lines.get(i)
+            && let Some((name, value)) = attribute_entry(line)
+        {
+            let key = match name {
+                "revdate" => "date",
+                other => other,
+            };
+            meta.entries
+                .insert(key.to_string(), MetaValue::String(value.to_string()));
+            i += 1;
+        }
+    }
+
+    meta.merge_defaults(&options.metadata);
+    let mut doc = Document {
+        meta,
+        blocks: parse_blocks(&lines[i..]),
+    };
+    doc.apply_substitutions();
+    Ok(doc)
+}
+
+/// A `:name: value` attribute entry.
+fn attribute_entry(line: &str) -> Option<(&str, &str)> {
+    let (name, value) = line.strip_prefix(':')?.split_once(':')?;
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((name, value.trim()))
+}
+
+fn skip_blank(lines: &[&str], mut i: usize) -> usize {
+    while i < lines.len() && lines[i].trim().is_empty() {
+        i += 1;
+    }
+    i
+}
+
+/// Settings from a `[...]` block attribute line, applied to the next block.
+#[derive(Default)]
+struct BlockAttrs {
+    language: Option<String>,
+    header: bool,
+}
+
+fn block_attrs(line: &str) -> Option<BlockAttrs> {
+    let inner = line.trim().strip_prefix('[')?.strip_suffix(']')?;
+    let mut parts = inner.split(',').map(str::trim);
+    let first = parts.next().unwrap_or("");
+    Some(BlockAttrs {
+        language: (first == "source")
+            .then(|| parts.next().map(str::to_string))
+            .flatten(),
+        header: inner.contains("header"),
+    })
+}
+
+/// A line of four or more `-` (listing block delimiter).
+fn is_listing_delimiter(line: &str) -> bool {
+    let line = line.trim_end();
+    line.len() >= 4 && line.chars().all(|c| c == '-')
+}
+
+/// Section heading level and text for `== Title` lines (`==` is level 1).
+fn heading(line: &str) -> Option<(u8, &str)> {
+    let marks = line.chars().take_while(|&c| c == '=').count();
+    let text = line[marks..].strip_prefix(' ')?;
+    if marks == 0 || text.trim().is_empty() {
+        return None;
+    }
+    Some((marks.saturating_sub(1).clamp(1, 6) as u8, text.trim()))
+}
+
+/// List item marker: `*`/`-` bullets or `.` numbers, returning whether the
+/// list is ordered and the item text.
+fn list_item(line: &str) -> Option<(bool, &str)> {
+    let line = line.trim_start();
+    if let Some(text) = line.strip_prefix("- ") {
+        return Some((false, text));
+    }
+    for (marker, ordered) in [('*', false), ('.', true)] {
+        let count = line.chars().take_while(|&c| c == marker).count();
+        if count > 0
+            && let Some(text) = line[count..].strip_prefix(' ')
+        {
+            return Some((ordered, text));
+        }
+    }
+    None
+}
+
+/// Whether `line` starts a block other than a paragraph.
+fn starts_block(line: &str) -> bool {
+    let trimmed = line.trim();
+    heading(line).is_some()
+        || list_item(line).is_some()
+        || is_listing_delimiter(line)
+        || block_attrs(line).is_some()
+        || trimmed == "|==="
+        || trimmed == "'''"
+        || trimmed == "<<<"
+}
+
+fn parse_blocks(lines: &[&str]) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut attrs = BlockAttrs::default();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() || (trimmed.starts_with("//") && !trimmed.starts_with("///")) {
+            i += 1;
+            continue;
+        }
+        if let Some(next) = block_attrs(line) {
+            attrs = next;
+            i += 1;
+            continue;
+        }
+        let pending = std::mem::take(&mut attrs);
+
+        if is_listing_delimiter(line) {
+            let delimiter = line.trim_end();
+            let end = (i + 1..lines.len())
+                .find(|&j| lines[j].trim_end() == delimiter)
+                .unwrap_or(lines.len());
+            let mut code = lines[i + 1..end].join("\n");
+            code.push('\n');
+            let mut attr = Attr::empty();
+            attr.classes.extend(pending.language);
+            blocks.push(Block::CodeBlock(attr, code));
+            i = end + 1;
+        } else if trimmed == "|===" {
+            let end = (i + 1..lines.len())
+                .find(|&j| lines[j].trim() == "|===")
+                .unwrap_or(lines.len());
+            blocks.push(parse_table(&lines[i + 1..end], pending.header));
+            i = end + 1;
+        } else if trimmed == "'''" {
+            blocks.push(Block::HorizontalRule);
+            i += 1;
+        } else if trimmed == "<<<" {
+            blocks.push(Block::PageBreak);
+            i += 1;
+        } else if let Some((level, text)) = heading(line) {
+            blocks.push(Block::Heading(Attr::empty(), level, parse_inlines(text)));
+            i += 1;
+        } else if let Some((ordered, _)) = list_item(line) {
+            let mut items = Vec::new();
+            while let Some(line) = lines.get(i)
+                && let Some((item_ordered, text)) = list_item(line)
+                && item_ordered == ordered
+            {
+                let mut item = text.to_string();
+                i += 1;
+                while i < lines.len() && !lines[i].trim().is_empty() && !starts_block(lines[i]) {
+                    item.push('\n');
+                    item.push_str(lines[i].trim());
+                    i += 1;
+                }
+                items.push(vec![Block::Plain(parse_inlines(&item))]);
+                // Blank lines between items of the same list are allowed
+                let next = skip_blank(lines, i);
+                if lines
+                    .get(next)
+                    .and_then(|l| list_item(l))
+                    .is_some_and(|(o, _)| o == ordered)
+                {
+                    i = next;
+                }
+            }
+            blocks.push(if ordered {
+                Block::OrderedList(ListAttrs::default(), items)
+            } else {
+                Block::BulletList(items)
+            });
+        } else {
+            let start = i;
+            i += 1;
+            while i < lines.len() && !lines[i].trim().is_empty() && !starts_block(lines[i]) {
+                i += 1;
+            }
+            let text = lines[start..i]
+                .iter()
+                .map(|l| l.trim())
+                .collect::<Vec<_>>()
+                .join("\n");
+            blocks.push(Block::Para(parse_inlines(&text)));
+        }
+    }
+    blocks
+}
+
+/// Parse the lines between `|===` delimiters. The column count comes from the
+/// first row; that row is the header when followed by a blank line or when
+/// the block has the `header` option.
+fn parse_table(lines: &[&str], header_option: bool) -> Block {
+    let start = skip_blank(lines, 0);
+    let mut cells: Vec<String> = Vec::new();
+    let mut num_cols = 0;
+    let mut implicit_header = false;
+    for (n, line) in lines.iter().enumerate().skip(start) {
+        let trimmed = line.trim();
+        if let Some(row) = trimmed.strip_prefix('|') {
+            let row_cells: Vec<String> = row.split('|').map(|c| c.trim().to_string()).collect();
+            if num_cols == 0 {
+                num_cols = row_cells.len();
+                implicit_header = lines.get(n + 1).is_some_and(|l| l.trim().is_empty());
+            }
+            cells.extend(row_cells);
+        } else if !trimmed.is_empty()
+            && let Some(last) = cells.last_mut()
+        {
+            // Continuation of the previous cell's text
+            last.push('\n');
+            last.push_str(trimmed);
+        }
+    }
+    let num_cols = num_cols.max(1);
+
+    let to_row = |row: &[String]| Row {
+        attr: Attr::empty(),
+        cells: row
+            .iter()
+            .map(|text| Cell {
+                attr: Attr::empty(),
+                align: Alignment::AlignDefault,
+                row_span: 1,
+                col_span: 1,
+                content: vec![Block::Plain(parse_inlines(text))],
+            })
+            .collect(),
+    };
+    let mut rows: Vec<Row> = cells.chunks(num_cols).map(to_row).collect();
+    let head = if (header_option || implicit_header) && !rows.is_empty() {
+        vec![rows.remove(0)]
+    } else {
+        vec![]
+    };
+
+    Block::Table(Table {
+        attr: Attr::empty(),
+        caption: Caption::default(),
+        col_specs: (0..num_cols)
+            .map(|_| ColSpec {
+                align: Alignment::AlignDefault,
+                width: ColWidth::Default,
+            })
+            .collect(),
+        head: TableHead {
+            attr: Attr::empty(),
+            rows: head,
+        },
+        bodies: vec![TableBody {
+            attr: Attr::empty(),
+            row_head_columns: 0,
+            head: vec![],
+            body: rows,
+        }],
+        foot: TableFoot {
+            attr: Attr::empty(),
+            rows: vec![],
+        },
+    })
+}
+
+/// Parse inline text; line breaks inside a paragraph become soft breaks.
+fn parse_inlines(text: &str) -> Vec<Inline> {
+    let mut inlines = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        if n > 0 {
+            inlines.push(Inline::SoftBreak);
+        }
+        inlines.extend(parse_line_inlines(line));
+    }
+    inlines
+}
+
+/// Constrained formatting: `*strong*`, `_emphasis_` and `` `monospace` ``,
+/// where the opening mark starts a word and the closing mark ends one.
+fn parse_line_inlines(line: &str) -> Vec<Inline> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut inlines = Vec::new();
+    let mut text = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if matches!(c, '*' | '_' | '`')
+            && (i == 0 || !chars[i - 1].is_alphanumeric())
+            && let Some(end) = closing_mark(&chars, i, c)
+        {
+            if !text.is_empty() {
+                inlines.push(Inline::Str(std::mem::take(&mut text)));
+            }
+            let inner: String = chars[i + 1..end].iter().collect();
+            inlines.push(match c {
+                '*' => Inline::Strong(parse_line_inlines(&inner)),
+                '_' => Inline::Emph(parse_line_inlines(&inner)),
+                _ => Inline::Code(Attr::empty(), inner),
+            });
+            i = end + 1;
+            continue;
+        }
+        text.push(c);
+        i += 1;
+    }
+    if !text.is_empty() {
+        inlines.push(Inline::Str(text));
+    }
+    inlines
+}
+
+/// Index of the mark closing the one at `open`. The enclosed text must be
+/// non-empty without surrounding spaces.
+fn closing_mark(chars: &[char], open: usize, mark: char) -> Option<usize> {
+    let first = *chars.get(open + 1)?;
+    if first.is_whitespace() || first == mark {
+        return None;
+    }
+    (open + 2..chars.len()).find(|&j| {
+        chars[j] == mark
+            && !chars[j - 1].is_whitespace()
+            && chars.get(j + 1).is_none_or(|n| !n.is_alphanumeric())
+    })
+}
@@ -0,0 +1,199 @@
+use crate::ast::*;
+use crate::readers::markdown::merge_adjacent_str;
+
+/// CriticMarkup delimiters, longest match first at any position.
+const MARKERS: [&str; 11] = [
+    "{++", "++}", "{--", "--}", "{==", "==}", "{>>", "<<}", "{~~", "~~}", "~>",
+];
+
+#[derive(Debug)]
+enum Token {
+    Inline(Inline),
+    Marker(&'static str),
+}
+
+/// Convert CriticMarkup in an inline sequence into spans:
+///
+/// - `{++text++}` → class `insertion`
+/// - `{--text--}` → class `deletion`
+/// - `{~~old~>new~~}` → a `deletion` followed by an `insertion`
+/// - `{==text==}` → class `mark`
+/// - `{>>text<<}` → class `comment`
+///
+/// A marker without its partner stays literal text.
+pub(crate) fn convert_criticmarkup(inlines: Vec<Inline>) -> Vec<Inline> {
+    let inlines = restore_substitutions(inlines);
+    if !inlines
+        .iter()
+        .any(|i| matches!(i, Inline::Str(s) if MARKERS.iter().any(|m| s.contains(m))))
+    {
+        return inlines;
+    }
+
+    let mut tokens: Vec<Token> = Vec::new();
+    for inline in inlines {
+        match inline {
+            Inline::Str(s) => tokenize(&s, &mut tokens),
+            other => tokens.push(Token::Inline(other)),
+        }
+    }
+
+    let mut result = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let Token::Marker(open) = tokens[i] else {
+            if let Token::Inline(inline) = &tokens[i] {
+                result.push(inline.clone());
+            }
+            i += 1;
+            continue;
+        };
+        let close = match open {
+            "{++" => "++}",
+            "{--" => "--}",
+            "{==" => "==}",
+            "{>>" => "<<}",
+            "{~~" => "~~}",
+            _ => "",
+        };
+        let end = (!close.is_empty())
+            .then(|| (i + 1..tokens.len()).find(|&j| matches!(tokens[j], Token::Marker(m) if m == close)))
+            .flatten();
+        let Some(end) = end else {
+            result.push(Inline::Str(open.to_string()));
+            i += 1;
+            continue;
+        };
+
+        let content = &tokens[i + 1..end];
+        match open {
+            "{~~" => {
+                let split = content
+                    .iter()
+                    .position(|t| matches!(t, Token::Marker("~>")))
+                    .unwrap_or(content.len());
+                result.push(span("deletion", literal(&content[..split])));
+                if split < content.len() {
+                    result.push(span("insertion", literal(&content[split + 1..])));
+                }
+            }
+            "{++" => result.push(span("insertion", literal(content))),
+            "{--" => result.push(span("deletion", literal(content))),
+            "{==" => result.push(span("mark", literal(content))),
+            _ => result.push(span("comment", literal(content))),
+        }
+        i = end + 1;
+    }
+
+    merge_adjacent_str(result)
+}
+
+/// comrak reads `{~~old~>new~~}` as strikethrough between braces; turn it
+/// back into text so the substitution can be recognized.
+fn restore_substitutions(inlines: Vec<Inline>) -> Vec<Inline> {
+    let mut result: Vec<Inline> = Vec::with_capacity(inlines.len());
+    let mut rest = inlines.into_iter().peekable();
+    while let Some(inline) = rest.next() {
+        let opens = matches!(result.last(), Some(Inline::Str(s)) if s.ends_with('{'));
+        let closes = matches!(rest.peek(), Some(Inline::Str(s)) if s.starts_with('}'));
+        match inline {
+            Inline::Strikeout(inner) if opens && closes => {
+                result.push(Inline::Str("~~".to_string()));
+                result.extend(inner);
+                result.push(Inline::Str("~~".to_string()));
+            }
+            other => result.push(other),
+        }
+    }
+    merge_adjacent_str(result)
+}
+
+/// Split text into plain pieces and CriticMarkup markers.
+fn tokenize(text: &str, tokens: &mut Vec<Token>) {
+    let mut rest = text;
+    while !rest.is_empty() {
+        let next = MARKERS
+            .iter()
+            .filter_map(|m| rest.find(m).map(|pos| (pos, *m)))
+            .min_by_key(|(pos, m)| (*pos, std::cmp::Reverse(m.len())));
+        let Some((pos, marker)) = next else {
+            tokens.push(Token::Inline(Inline::Str(rest.to_string())));
+            return;
+        };
+        if pos > 0 {
+            tokens.push(Token::Inline(Inline::Str(rest[..pos].to_string())));
+        }
+        tokens.push(Token::Marker(marker));
+        rest = &rest[pos + marker.len()..];
+    }
+}
+
+/// The inlines in `tokens`, with stray markers kept as text.
+fn literal(tokens: &[Token]) -> Vec<Inline> {
+    let inlines = tokens
+        .iter()
+        .map(|t| match t {
+            Token::Inline(inline) => inline.clone(),
+            Token::Marker(m) => Inline::Str(m.to_string()),
+        })
+        .collect();
+    merge_adjacent_str(inlines)
+}
+
+fn span(class: &str, content: Vec<Inline>) -> Inline {
+    Inline::Span(
+        Attr {
+            id: String::new(),
+            classes: vec![class.to_string()],
+            attrs: vec![],
+        },
+        content,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Inline {
+        Inline::Str(s.to_string())
+    }
+
+    #[test]
+    fn test_insertion_and_deletion() {
+        let out = convert_criticmarkup(vec![text("a {++new++} b {--old--}")]);
+        assert_eq!(
+            out,
+            vec![
+                text("a "),
+                span("insertion", vec![text("new")]),
+                text(" b "),
+                span("deletion", vec![text("old")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_substitution_from_strikeout() {
+        let out = convert_criticmarkup(vec![
+            text("x {"),
+            Inline::Strikeout(vec![text("cat~>dog")]),
+            text("} y"),
+        ]);
+        assert_eq!(
+            out,
+            vec![
+                text("x "),
+                span("deletion", vec![text("cat")]),
+                span("insertion", vec![text("dog")]),
+                text(" y"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unmatched_marker_is_text() {
+        let out = convert_criticmarkup(vec![text("just {++ text")]);
+        assert_eq!(out, vec![text("just {++ text")]);
+    }
+}
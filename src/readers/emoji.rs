@@ -0,0 +1,113 @@
+use crate::ast::Inline;
+
+/// GitHub-style shortcodes and their emoji, sorted by name for binary search.
+const SHORTCODES: &[(&str, &str)] = &[
+    ("+1", "\u{1F44D}"),
+    ("-1", "\u{1F44E}"),
+    ("100", "\u{1F4AF}"),
+    ("bug", "\u{1F41B}"),
+    ("bulb", "\u{1F4A1}"),
+    ("calendar", "\u{1F4C6}"),
+    ("check", "\u{2714}\u{FE0F}"),
+    ("clap", "\u{1F44F}"),
+    ("construction", "\u{1F6A7}"),
+    ("cry", "\u{1F622}"),
+    ("eyes", "\u{1F440}"),
+    ("fire", "\u{1F525}"),
+    ("grin", "\u{1F601}"),
+    ("heart", "\u{2764}\u{FE0F}"),
+    ("heavy_check_mark", "\u{2714}\u{FE0F}"),
+    ("hourglass", "\u{231B}"),
+    ("information_source", "\u{2139}\u{FE0F}"),
+    ("joy", "\u{1F602}"),
+    ("laughing", "\u{1F606}"),
+    ("link", "\u{1F517}"),
+    ("lock", "\u{1F512}"),
+    ("memo", "\u{1F4DD}"),
+    ("no_entry", "\u{26D4}"),
+    ("ok_hand", "\u{1F44C}"),
+    ("pencil", "\u{1F4DD}"),
+    ("pray", "\u{1F64F}"),
+    ("question", "\u{2753}"),
+    ("raised_hands", "\u{1F64C}"),
+    ("rocket", "\u{1F680}"),
+    ("see_no_evil", "\u{1F648}"),
+    ("smile", "\u{1F604}"),
+    ("smiley", "\u{1F603}"),
+    ("sparkles", "\u{2728}"),
+    ("star", "\u{2B50}"),
+    ("tada", "\u{1F389}"),
+    ("thinking", "\u{1F914}"),
+    ("thumbsdown", "\u{1F44E}"),
+    ("thumbsup", "\u{1F44D}"),
+    ("warning", "\u{26A0}\u{FE0F}"),
+    ("wave", "\u{1F44B}"),
+    ("white_check_mark", "\u{2705}"),
+    ("wink", "\u{1F609}"),
+    ("wrench", "\u{1F527}"),
+    ("x", "\u{274C}"),
+    ("zap", "\u{26A1}"),
+];
+
+fn lookup(name: &str) -> Option<&'static str> {
+    SHORTCODES
+        .binary_search_by(|(code, _)| code.cmp(&name))
+        .ok()
+        .map(|i| SHORTCODES[i].1)
+}
+
+/// Replace recognized `:shortcode:` text with its emoji. Unknown shortcodes
+/// are left as written; code spans are not touched.
+pub(crate) fn replace_emoji(inlines: Vec<Inline>) -> Vec<Inline> {
+    inlines
+        .into_iter()
+        .map(|inline| match inline {
+            Inline::Str(s) if s.contains(':') => Inline::Str(replace_in_text(&s)),
+            other => other,
+        })
+        .collect()
+}
+
+fn replace_in_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(':') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let emoji = after.find(':').and_then(|end| {
+            let name = &after[..end];
+            let valid = !name.is_empty()
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-'));
+            valid.then(|| lookup(name).map(|emoji| (emoji, end))).flatten()
+        });
+        match emoji {
+            Some((emoji, end)) => {
+                out.push_str(emoji);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push(':');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortcodes_are_sorted() {
+        assert!(SHORTCODES.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn test_replace_in_text() {
+        assert_eq!(replace_in_text("Ship it :rocket: :tada:"), "Ship it \u{1F680} \u{1F389}");
+        assert_eq!(replace_in_text("at 10:30 :nope: ok"), "at 10:30 :nope: ok");
+        assert_eq!(replace_in_text("a::+1:"), "a:\u{1F44D}");
+    }
+}
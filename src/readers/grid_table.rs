@@ -16,18 +16,114 @@
 /// | --- | --- | --- |
 /// | POS | 3,500 |
 /// ```
+///
+/// Pandoc simple tables (columns marked by a dashed rule under the header)
+/// are converted to GFM pipe tables the same way.
+///
+/// Fenced div markers (`:::`) are stripped, except that when extracting,
+/// a div opened with a bare keyword (`::: warning`) is kept as a pair of
+/// [`fenced_div_placeholder`] lines so the reader can rebuild it as a div.
 
 /// Preprocess the input markdown string, converting grid tables to GFM pipe
 /// tables and `\newpage` to an HTML page-break div.
 pub fn preprocess_grid_tables(input: &str) -> String {
+    preprocess(input, None)
+}
+
+/// Preprocess like [`preprocess_grid_tables`], but lift grid tables out of
+/// the text instead of flattening them to GFM. Each table is replaced by a
+/// [`grid_table_placeholder`] line whose index points into the returned list,
+/// so the reader can parse the cells as full Markdown blocks. Keyword fenced
+/// divs are marked with [`fenced_div_placeholder`] lines.
+pub fn extract_grid_tables(input: &str) -> (String, Vec<GridTable>) {
+    let mut tables = Vec::new();
+    let output = preprocess(input, Some(&mut tables));
+    (output, tables)
+}
+
+/// The HTML comment that stands in for the grid table at `index`.
+pub fn grid_table_placeholder(index: usize) -> String {
+    format!("<!-- pandorust:grid-table:{} -->", index)
+}
+
+/// Recover the table index from a [`grid_table_placeholder`] line.
+pub fn parse_grid_table_placeholder(html: &str) -> Option<usize> {
+    html.trim()
+        .strip_prefix("<!-- pandorust:grid-table:")?
+        .strip_suffix(" -->")?
+        .parse()
+        .ok()
+}
+
+/// The HTML comment that opens (`Some(class)`) or closes (`None`) a fenced
+/// div written with a bare keyword or a single class, such as `::: note` or
+/// `::: {.only-html}`.
+pub fn fenced_div_placeholder(class: Option<&str>) -> String {
+    match class {
+        Some(class) => format!("<!-- pandorust:div:{} -->", class),
+        None => "<!-- pandorust:div-end -->".to_string(),
+    }
+}
+
+/// Recover a [`fenced_div_placeholder`]: `Some(Some(class))` for an
+/// opening line, `Some(None)` for a closing one.
+pub fn parse_fenced_div_placeholder(html: &str) -> Option<Option<&str>> {
+    let inner = html.trim().strip_prefix("<!-- pandorust:div")?.strip_suffix(" -->")?;
+    match inner {
+        "-end" => Some(None),
+        _ => inner.strip_prefix(':').map(Some),
+    }
+}
+
+/// The class of a `::: keyword` or `::: {.class}` div opener; `None` for
+/// other attribute braces.
+fn fenced_div_keyword(line: &str) -> Option<&str> {
+    let word = line.trim_start_matches(':').trim();
+    let word = word
+        .strip_prefix('{')
+        .and_then(|w| w.strip_suffix('}'))
+        .and_then(|w| w.trim().strip_prefix('.'))
+        .unwrap_or(word);
+    let bare = !word.is_empty()
+        && word.chars().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_'));
+    bare.then_some(word)
+}
+
+fn preprocess(input: &str, mut tables: Option<&mut Vec<GridTable>>) -> String {
     let mut output = String::with_capacity(input.len());
     let lines: Vec<&str> = input.lines().collect();
     let len = lines.len();
     let mut i = 0;
+    // Opening fence (char, length) of the code block we are inside, if any
+    let mut open_fence: Option<(char, usize)> = None;
+    // Open fenced divs, and whether each was kept as a placeholder
+    let mut open_divs: Vec<bool> = Vec::new();
 
     while i < len {
         let trimmed = lines[i].trim();
 
+        // Fenced code blocks pass through verbatim
+        if let Some((fence_char, fence_len)) = open_fence {
+            if let Some((c, n)) = code_fence(lines[i])
+                && c == fence_char
+                && n >= fence_len
+                && trimmed.chars().all(|ch| ch == c)
+            {
+                open_fence = None;
+            }
+            output.push_str(lines[i]);
+            output.push('\n');
+            i += 1;
+            continue;
+        }
+        if let Some(fence) = code_fence(lines[i]) {
+            open_fence = Some(fence);
+            output.push_str(lines[i]);
+            output.push('\n');
+            i += 1;
+            continue;
+        }
+
         // Handle \newpage as standalone paragraph
         if trimmed == "\\newpage" {
             output.push_str("<div style=\"page-break-after: always;\"></div>\n");
@@ -43,17 +139,33 @@ pub fn preprocess_grid_tables(input: &str) -> String {
         }
 
         // Handle pandoc fenced divs ::: {custom-style="..."} ... :::
-        // Strip the ::: markers and pass through the inner content
+        // Strip the ::: markers and pass through the inner content; keyword
+        // divs (::: note) are marked for the reader when extracting
         if trimmed.starts_with(":::") {
-            if trimmed.len() > 3 {
-                // Opening ::: with attributes — skip this line
-                i += 1;
-                continue;
-            } else {
-                // Closing ::: — skip this line
-                i += 1;
-                continue;
+            let is_closing = trimmed.chars().all(|c| c == ':');
+            if !is_closing {
+                let keyword = fenced_div_keyword(trimmed).filter(|_| tables.is_some());
+                if let Some(class) = keyword {
+                    output.push('\n');
+                    output.push_str(&fenced_div_placeholder(Some(class)));
+                    output.push_str("\n\n");
+                }
+                open_divs.push(keyword.is_some());
+            } else if open_divs.pop() == Some(true) {
+                output.push('\n');
+                output.push_str(&fenced_div_placeholder(None));
+                output.push_str("\n\n");
             }
+            i += 1;
+            continue;
+        }
+
+        // Pandoc simple tables: columns delimited by a dashed rule line
+        let starts_block = i == 0 || lines[i - 1].trim().is_empty();
+        if starts_block && let Some(consumed) = simple_table_extent(&lines[i..]) {
+            output.push_str(&convert_simple_to_gfm(&lines[i..i + consumed]));
+            i += consumed;
+            continue;
         }
 
         // Check if this line starts a grid table
@@ -77,10 +189,19 @@ pub fn preprocess_grid_tables(input: &str) -> String {
             // Only convert if we have a valid grid table (at least 3 lines:
             // border, data, border)
             if table_lines.len() >= 3 && is_border_line(table_lines.last().unwrap().trim()) {
-                let gfm = convert_grid_to_gfm(&table_lines);
-                output.push_str(&gfm);
-                // Don't add extra newline if the gfm already ends with one
-                if !gfm.ends_with('\n') {
+                let converted = match tables.as_deref_mut() {
+                    Some(tables) => match parse_grid(&table_lines) {
+                        Some(table) => {
+                            tables.push(table);
+                            grid_table_placeholder(tables.len() - 1)
+                        }
+                        None => table_lines.join("\n"),
+                    },
+                    None => convert_grid_to_gfm(&table_lines),
+                };
+                output.push_str(&converted);
+                // Don't add extra newline if the output already ends with one
+                if !converted.ends_with('\n') {
                     output.push('\n');
                 }
             } else {
@@ -109,6 +230,26 @@ pub fn preprocess_grid_tables(input: &str) -> String {
     output
 }
 
+/// Detect a code fence line (```` ``` ```` or `~~~`, at most three spaces of
+/// indentation) and return its fence character and length.
+fn code_fence(line: &str) -> Option<(char, usize)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+    let rest = &line[indent..];
+    let fence_char = rest.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let fence_len = rest.chars().take_while(|c| *c == fence_char).count();
+    if fence_len < 3 {
+        return None;
+    }
+    // Backtick fences may not have backticks in their info string
+    if fence_char == '`' && rest[fence_len..].contains('`') {
+        return None;
+    }
+    Some((fence_char, fence_len))
+}
+
 /// Check if a line is a grid table border line: starts with `+` and contains
 /// only `+`, `-`, and `=` characters.
 fn is_border_line(line: &str) -> bool {
@@ -133,36 +274,185 @@ fn is_header_separator(line: &str) -> bool {
     is_border_line(trimmed) && trimmed.contains('=')
 }
 
+/// Check if a line is a simple-table rule: two or more groups of at least two
+/// dashes separated by spaces, e.g. `-------  ------  ------`.
+fn is_simple_rule_line(line: &str) -> bool {
+    let trimmed = line.trim_end();
+    if trimmed.is_empty() || !trimmed.chars().all(|c| c == '-' || c == ' ') {
+        return false;
+    }
+    let groups = dash_groups(trimmed);
+    groups.len() >= 2 && groups.iter().all(|(start, end)| end - start >= 2)
+}
+
+/// Find the `[start, end)` char positions of each run of dashes in a rule line.
+fn dash_groups(rule: &str) -> Vec<(usize, usize)> {
+    let mut groups = Vec::new();
+    let mut start = None;
+    for (i, c) in rule.chars().enumerate() {
+        match (c == '-', start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                groups.push((s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        groups.push((s, rule.chars().count()));
+    }
+    groups
+}
+
+/// Determine how many lines starting at `lines[0]` form a simple table, if any.
+///
+/// Two shapes are recognized:
+/// - a header line, a dashed rule, then rows up to a blank line (an optional
+///   closing rule may end the table);
+/// - a headerless table: a dashed rule, rows, and a closing dashed rule.
+fn simple_table_extent(lines: &[&str]) -> Option<usize> {
+    let first = lines.first()?;
+    let (mut end, headerless) = if is_simple_rule_line(first) {
+        (1, true)
+    } else if !first.trim().is_empty() && lines.get(1).is_some_and(|l| is_simple_rule_line(l)) {
+        (2, false)
+    } else {
+        return None;
+    };
+
+    let mut rows = 0;
+    while end < lines.len() && !lines[end].trim().is_empty() {
+        if is_simple_rule_line(lines[end]) {
+            return (rows > 0).then_some(end + 1);
+        }
+        rows += 1;
+        end += 1;
+    }
+
+    // A headerless table must be closed by a rule line
+    if headerless || rows == 0 {
+        None
+    } else {
+        Some(end)
+    }
+}
+
+/// Convert the lines of a simple table into a GFM pipe table string.
+fn convert_simple_to_gfm(table_lines: &[&str]) -> String {
+    let headerless = is_simple_rule_line(table_lines[0]);
+    let rule_idx = if headerless { 0 } else { 1 };
+    let rule = table_lines[rule_idx];
+    let groups = dash_groups(rule);
+
+    // Column boundaries sit midway between dash groups; the first column
+    // starts at the line start and the last runs to the line end.
+    let mut bounds = vec![0];
+    for pair in groups.windows(2) {
+        bounds.push((pair[0].1 + pair[1].0) / 2);
+    }
+    bounds.push(usize::MAX);
+
+    let split_row = |line: &str| -> Vec<String> {
+        let chars: Vec<char> = line.chars().collect();
+        (0..groups.len())
+            .map(|col| {
+                let start = bounds[col].min(chars.len());
+                let end = bounds[col + 1].min(chars.len());
+                chars[start..end]
+                    .iter()
+                    .collect::<String>()
+                    .trim()
+                    .replace('|', "\\|")
+            })
+            .collect()
+    };
+
+    let data_lines: Vec<&str> = table_lines
+        .iter()
+        .enumerate()
+        .filter(|(idx, line)| *idx != rule_idx && !is_simple_rule_line(line))
+        .map(|(_, line)| *line)
+        .collect();
+
+    // Alignment comes from where the header text sits relative to its dashes.
+    // Headerless tables use the first row instead.
+    let align_line = data_lines[0];
+    let align_chars: Vec<char> = align_line.chars().collect();
+    let separators: Vec<String> = groups
+        .iter()
+        .enumerate()
+        .map(|(col, &(dash_start, dash_end))| {
+            let start = bounds[col].min(align_chars.len());
+            let end = bounds[col + 1].min(align_chars.len());
+            let text_start = (start..end).find(|&k| !align_chars[k].is_whitespace());
+            let text_end = (start..end).rev().find(|&k| !align_chars[k].is_whitespace());
+            match (text_start, text_end) {
+                (Some(ts), Some(te)) => {
+                    let left = ts == dash_start;
+                    let right = te + 1 == dash_end;
+                    match (left, right) {
+                        (true, false) => ":---".to_string(),
+                        (false, true) => "---:".to_string(),
+                        (false, false) => ":---:".to_string(),
+                        (true, true) => "---".to_string(),
+                    }
+                }
+                _ => "---".to_string(),
+            }
+        })
+        .collect();
+
+    let mut gfm = String::new();
+    for (idx, line) in data_lines.iter().enumerate() {
+        gfm.push_str("| ");
+        gfm.push_str(&split_row(line).join(" | "));
+        gfm.push_str(" |\n");
+        if idx == 0 {
+            gfm.push_str("| ");
+            gfm.push_str(&separators.join(" | "));
+            gfm.push_str(" |\n");
+        }
+    }
+    gfm
+}
+
 /// Find column boundary positions from a border line.
-/// Returns byte positions of `+` characters.
+/// Returns character positions of `+` characters, so data lines with
+/// non-ASCII text line up with the border.
 fn find_column_boundaries(border_line: &str) -> Vec<usize> {
     border_line
-        .char_indices()
+        .chars()
+        .enumerate()
         .filter(|(_, c)| *c == '+')
         .map(|(i, _)| i)
         .collect()
 }
 
-/// Extract cell content from a data line given column boundary positions.
+/// Extract the raw content of a cell from a data line given column boundary
+/// positions (in characters). Only the padding space after the `|` and
+/// trailing whitespace are removed, so indentation inside the cell survives.
 fn extract_cell(line: &str, start: usize, end: usize) -> String {
-    if start + 1 < end && end <= line.len() {
+    let chars: Vec<char> = line.chars().collect();
+    if start + 1 < end && end <= chars.len() {
         // The data line uses `|` at column boundaries instead of `+`
-        line[start + 1..end].trim().to_string()
+        let raw: String = chars[start + 1..end].iter().collect();
+        raw.strip_prefix(' ').unwrap_or(&raw).trim_end().to_string()
     } else {
         String::new()
     }
 }
 
 /// A single logical row may consist of multiple data lines (multiline cells).
-/// This struct accumulates content for each cell across those lines.
+/// This struct accumulates the lines of each cell across those data lines.
 struct GridRow {
-    cells: Vec<String>,
+    cells: Vec<Vec<String>>,
 }
 
 impl GridRow {
     fn new(num_cols: usize) -> Self {
         GridRow {
-            cells: vec![String::new(); num_cols],
+            cells: vec![Vec::new(); num_cols],
         }
     }
 
@@ -172,26 +462,62 @@ impl GridRow {
         for col in 0..num_cols {
             if col + 1 < boundaries.len() {
                 let content = extract_cell(line, boundaries[col], boundaries[col + 1]);
-                if !content.is_empty() {
-                    if !self.cells[col].is_empty() {
-                        self.cells[col].push(' ');
-                    }
-                    self.cells[col].push_str(&content);
-                }
+                self.cells[col].push(content);
             }
         }
     }
+
+    fn has_content(&self) -> bool {
+        self.cells
+            .iter()
+            .any(|lines| lines.iter().any(|l| !l.trim().is_empty()))
+    }
+
+    /// Finish the row: each cell becomes its lines, dedented and joined with
+    /// newlines, with leading and trailing blank lines removed.
+    fn into_cells(self) -> Vec<String> {
+        self.cells
+            .into_iter()
+            .map(|lines| {
+                let indent = lines
+                    .iter()
+                    .filter(|l| !l.trim().is_empty())
+                    .map(|l| l.len() - l.trim_start().len())
+                    .min()
+                    .unwrap_or(0);
+                let dedented: Vec<&str> = lines
+                    .iter()
+                    .map(|l| l.get(indent..).unwrap_or(""))
+                    .collect();
+                dedented.join("\n").trim_matches('\n').to_string()
+            })
+            .collect()
+    }
 }
 
-/// Convert collected grid table lines into a GFM pipe table string.
-fn convert_grid_to_gfm(table_lines: &[&str]) -> String {
+/// A grid table parsed into rows of raw cell text. Cell text keeps its line
+/// structure so it can be parsed as Markdown blocks (paragraphs, lists, ...).
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridTable {
+    /// Number of columns, taken from the top border.
+    pub num_cols: usize,
+    /// Header rows (above the `+===+` separator).
+    pub head: Vec<Vec<String>>,
+    /// Body rows.
+    pub body: Vec<Vec<String>>,
+}
+
+/// Parse collected grid table lines into a [`GridTable`].
+///
+/// Without a `+===+` separator every row is a body row and `head` is empty.
+/// Returns `None` if the lines hold no rows.
+fn parse_grid(table_lines: &[&str]) -> Option<GridTable> {
     // Find column boundaries from the first border line
     let first_border = table_lines[0].trim();
     let boundaries = find_column_boundaries(first_border);
 
     if boundaries.len() < 2 {
-        // Not enough columns, return lines as-is
-        return table_lines.join("\n");
+        return None;
     }
 
     let num_cols = boundaries.len() - 1;
@@ -214,8 +540,7 @@ fn convert_grid_to_gfm(table_lines: &[&str]) -> String {
         if is_border_line(trimmed) {
             if past_first_border {
                 // End of a logical row
-                let has_content = current_row.cells.iter().any(|c| !c.is_empty());
-                if has_content {
+                if current_row.has_content() {
                     if in_header {
                         header_rows.push(current_row);
                     } else {
@@ -235,38 +560,62 @@ fn convert_grid_to_gfm(table_lines: &[&str]) -> String {
         }
     }
 
-    // Build GFM output
-    let mut gfm = String::new();
+    if header_rows.is_empty() && body_rows.is_empty() {
+        return None;
+    }
 
-    // If there are header rows, use the first one as the GFM header
-    // If no header rows (no === separator), use the first body row as header
-    let (gfm_header, gfm_body) = if !header_rows.is_empty() {
-        (header_rows, body_rows)
-    } else if !body_rows.is_empty() {
-        // First body row becomes the header
-        let header = vec![body_rows.remove(0)];
-        (header, body_rows)
-    } else {
+    Some(GridTable {
+        num_cols,
+        head: header_rows.into_iter().map(GridRow::into_cells).collect(),
+        body: body_rows.into_iter().map(GridRow::into_cells).collect(),
+    })
+}
+
+/// Convert collected grid table lines into a GFM pipe table string.
+///
+/// Multi-line cells are joined with spaces since pipe tables cannot hold
+/// more than one line per cell.
+fn convert_grid_to_gfm(table_lines: &[&str]) -> String {
+    let Some(table) = parse_grid(table_lines) else {
+        // Not enough columns or no rows, return lines as-is
         return table_lines.join("\n");
     };
 
-    // Write header row(s) - GFM only supports one header row, use the first
-    if let Some(header) = gfm_header.first() {
+    let flatten = |cells: &[String]| -> String {
+        cells
+            .iter()
+            .map(|cell| {
+                cell.lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>()
+            .join(" | ")
+    };
+
+    let mut gfm = String::new();
+
+    // Write header row - GFM only supports one header row, use the first.
+    // GFM tables need a header, so a header-less table promotes its first row.
+    let mut body = table.body.iter();
+    if let Some(header) = table.head.first().or_else(|| body.next()) {
         gfm.push_str("| ");
-        gfm.push_str(&header.cells.join(" | "));
+        gfm.push_str(&flatten(header));
         gfm.push_str(" |\n");
     }
 
     // Write separator
-    let sep_cells: Vec<String> = (0..num_cols).map(|_| "---".to_string()).collect();
+    let sep_cells: Vec<String> = (0..table.num_cols).map(|_| "---".to_string()).collect();
     gfm.push_str("| ");
     gfm.push_str(&sep_cells.join(" | "));
     gfm.push_str(" |\n");
 
     // Write body rows
-    for row in &gfm_body {
+    for row in body {
         gfm.push_str("| ");
-        gfm.push_str(&row.cells.join(" | "));
+        gfm.push_str(&flatten(row));
         gfm.push_str(" |\n");
     }
 
@@ -311,6 +660,8 @@ mod tests {
             find_column_boundaries("+-----+--------+----------+"),
             vec![0, 6, 15, 26]
         );
+        // Positions count characters, not bytes
+        assert_eq!(find_column_boundaries("+──+--+"), vec![0, 3, 6]);
     }
 
     #[test]
@@ -368,6 +719,114 @@ mod tests {
         assert_eq!(result, input);
     }
 
+    #[test]
+    fn test_extract_grid_tables_keeps_cell_lines() {
+        let input = "\
+Before
+
++-----+-----------+
+| A   | B         |
++=====+===========+
+| 1   | - one     |
+|     | - two     |
++-----+-----------+
+
+After";
+        let (output, tables) = extract_grid_tables(input);
+        assert_eq!(output, "Before\n\n<!-- pandorust:grid-table:0 -->\n\nAfter");
+        assert_eq!(parse_grid_table_placeholder("<!-- pandorust:grid-table:0 -->\n"), Some(0));
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].num_cols, 2);
+        assert_eq!(tables[0].head, vec![vec!["A".to_string(), "B".to_string()]]);
+        assert_eq!(tables[0].body, vec![vec!["1".to_string(), "- one\n- two".to_string()]]);
+    }
+
+    #[test]
+    fn test_is_simple_rule_line() {
+        assert!(is_simple_rule_line("-------  ------  ------"));
+        assert!(is_simple_rule_line("----- -----"));
+        assert!(!is_simple_rule_line("---"));
+        assert!(!is_simple_rule_line("- - -"));
+        assert!(!is_simple_rule_line("+---+---+"));
+        assert!(!is_simple_rule_line("text --- ---"));
+    }
+
+    #[test]
+    fn test_preprocess_simple_table() {
+        let input = "\
+Name    Qty    Price
+------  -----  ------
+Apple   3      1.50
+Pear    10     0.75
+
+After the table.";
+        let result = preprocess_grid_tables(input);
+        assert!(result.contains("| Name | Qty | Price |"), "Got: {}", result);
+        assert!(result.contains("| Apple | 3 | 1.50 |"), "Got: {}", result);
+        assert!(result.contains("| Pear | 10 | 0.75 |"), "Got: {}", result);
+        assert!(result.contains("After the table."), "Got: {}", result);
+    }
+
+    #[test]
+    fn test_preprocess_simple_table_alignment() {
+        let input = concat!(
+            "  Right  Left     Center   Default\n",
+            "-------  ------  --------  -------\n",
+            "     12  12         12     12",
+        );
+        let result = preprocess_grid_tables(input);
+        assert!(result.contains("| ---: | :--- | :---: | --- |"), "Got: {}", result);
+        assert!(result.contains("| 12 | 12 | 12 | 12 |"), "Got: {}", result);
+    }
+
+    #[test]
+    fn test_preprocess_simple_table_no_header() {
+        let input = "\
+-----  -----
+A      B
+1      2
+-----  -----";
+        let result = preprocess_grid_tables(input);
+        // First row becomes header, as with grid tables, and sets alignment
+        assert!(result.contains("| A | B |"), "Got: {}", result);
+        assert!(result.contains("| :--- | :--- |"), "Got: {}", result);
+        assert!(result.contains("| 1 | 2 |"), "Got: {}", result);
+    }
+
+    #[test]
+    fn test_preprocess_leaves_setext_heading_and_rules() {
+        let input = "Heading\n-------\n\nText\n\n---\n\nMore";
+        assert_eq!(preprocess_grid_tables(input), input);
+    }
+
+    #[test]
+    fn test_preprocess_skips_fenced_code() {
+        let input = "\
+```markdown
+::: {.x}
++---+---+
+| a | b |
++---+---+
+\\newpage
+:::
+```
+
+~~~~
+```
+::: note
+~~~~";
+        assert_eq!(preprocess_grid_tables(input), input);
+    }
+
+    #[test]
+    fn test_preprocess_resumes_after_fence() {
+        let input = "```\n::: keep\n```\n\n::: {.x}\nText\n:::";
+        let result = preprocess_grid_tables(input);
+        assert!(result.contains("::: keep"), "Got: {}", result);
+        assert!(!result.contains("::: {.x}"), "Got: {}", result);
+        assert!(result.contains("Text"), "Got: {}", result);
+    }
+
     #[test]
     fn test_preprocess_no_header_separator() {
         let input = "\
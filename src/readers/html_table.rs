@@ -0,0 +1,218 @@
+use crate::ast::Alignment;
+
+/// A cell of a raw HTML table, with its content still HTML.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HtmlCell {
+    /// The markup between the cell's tags.
+    pub content: String,
+    /// A `<th>` rather than a `<td>`.
+    pub header: bool,
+    pub align: Alignment,
+    pub row_span: u32,
+    pub col_span: u32,
+}
+
+/// A raw HTML `<table>` split into rows of cells. The caption and cell
+/// contents stay HTML, to be read as Markdown with inline HTML.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HtmlTable {
+    pub caption: Option<String>,
+    /// Rows in `<thead>`, or a leading row of `<th>` cells.
+    pub head: Vec<Vec<HtmlCell>>,
+    pub body: Vec<Vec<HtmlCell>>,
+    pub foot: Vec<Vec<HtmlCell>>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Head,
+    Body,
+    Foot,
+}
+
+/// Parse a raw HTML block that is exactly one `<table>...</table>`.
+/// Returns `None` for anything else, including nested tables and tables
+/// without rows, so the block can stay raw HTML.
+pub fn parse_html_table(html: &str) -> Option<HtmlTable> {
+    let html = html.trim();
+    let lower = html.to_ascii_lowercase();
+    if !lower.starts_with("<table") || !lower.ends_with("</table>") {
+        return None;
+    }
+
+    let mut table = HtmlTable::default();
+    let mut section = Section::Body;
+    let mut explicit_head = false;
+    let mut row: Option<Vec<HtmlCell>> = None;
+    // The open cell and where its content starts
+    let mut cell: Option<(HtmlCell, usize)> = None;
+    let mut caption_start: Option<usize> = None;
+    let mut tables = 0;
+    let mut pos = 0;
+
+    while let Some(offset) = html[pos..].find('<') {
+        let start = pos + offset;
+        let end = start + html[start..].find('>')? + 1;
+        let tag = &html[start..end];
+        pos = end;
+        let closing = tag.starts_with("</");
+        let name = tag[if closing { 2 } else { 1 }..]
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+
+        // End tags are optional: the next row or section closes the open
+        // row, and the next cell closes the open cell
+        let ends_row = matches!(name.as_str(), "tr" | "thead" | "tbody" | "tfoot")
+            || (closing && name == "table");
+        let ends_cell = ends_row || matches!(name.as_str(), "td" | "th");
+        if ends_cell && let Some((mut open, content_start)) = cell.take() {
+            open.content = html[content_start..start].trim().to_string();
+            row.get_or_insert_with(Vec::new).push(open);
+        }
+        if ends_row && let Some(cells) = row.take() {
+            push_row(&mut table, section, cells);
+        }
+
+        match (name.as_str(), closing) {
+            ("table", false) => {
+                tables += 1;
+                if tables > 1 {
+                    return None;
+                }
+            }
+            ("caption", false) => caption_start = Some(end),
+            ("caption", true) => {
+                table.caption = caption_start.take().map(|s| html[s..start].trim().to_string());
+            }
+            ("thead", false) => {
+                section = Section::Head;
+                explicit_head = true;
+            }
+            ("tbody", false) => section = Section::Body,
+            ("tfoot", false) => section = Section::Foot,
+            ("thead" | "tfoot", true) => section = Section::Body,
+            ("tr", false) => row = Some(Vec::new()),
+            ("td" | "th", false) => {
+                let cell_attr = |key: &str| attribute(tag, key);
+                let span = |key: &str| cell_attr(key).and_then(|v| v.trim().parse().ok()).filter(|n| *n > 0);
+                cell = Some((
+                    HtmlCell {
+                        content: String::new(),
+                        header: name == "th",
+                        align: alignment(cell_attr("align"), cell_attr("style")),
+                        row_span: span("rowspan").unwrap_or(1),
+                        col_span: span("colspan").unwrap_or(1),
+                    },
+                    end,
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    // Without a <thead>, a first row of <th> cells is the header
+    if !explicit_head
+        && table.head.is_empty()
+        && table.body.first().is_some_and(|r| !r.is_empty() && r.iter().all(|c| c.header))
+    {
+        table.head.push(table.body.remove(0));
+    }
+    if table.head.is_empty() && table.body.is_empty() && table.foot.is_empty() {
+        return None;
+    }
+    Some(table)
+}
+
+fn push_row(table: &mut HtmlTable, section: Section, cells: Vec<HtmlCell>) {
+    if cells.is_empty() {
+        return;
+    }
+    match section {
+        Section::Head => table.head.push(cells),
+        Section::Body => table.body.push(cells),
+        Section::Foot => table.foot.push(cells),
+    }
+}
+
+/// The value of attribute `key` in an opening tag, quoted or not.
+fn attribute<'a>(tag: &'a str, key: &str) -> Option<&'a str> {
+    let lower = tag.to_ascii_lowercase();
+    let mut from = 0;
+    while let Some(found) = lower[from..].find(key) {
+        let at = from + found;
+        from = at + key.len();
+        // Must be a whole attribute name followed by `=`
+        let before = lower[..at].chars().next_back();
+        if !before.is_some_and(char::is_whitespace) {
+            continue;
+        }
+        let rest = tag[at + key.len()..].trim_start();
+        let Some(value) = rest.strip_prefix('=') else {
+            continue;
+        };
+        let value = value.trim_start();
+        return Some(match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => value[1..].split(quote).next().unwrap_or(""),
+            _ => value.split(|c: char| c.is_whitespace() || c == '>' || c == '/').next().unwrap_or(""),
+        });
+    }
+    None
+}
+
+/// A cell's alignment from its `align` attribute or `text-align` style.
+fn alignment(align: Option<&str>, style: Option<&str>) -> Alignment {
+    let from_style = style.and_then(|s| {
+        s.split(';').find_map(|decl| {
+            let (prop, value) = decl.split_once(':')?;
+            (prop.trim().eq_ignore_ascii_case("text-align")).then_some(value.trim())
+        })
+    });
+    match from_style.or(align).map(str::to_ascii_lowercase).as_deref() {
+        Some("left") => Alignment::AlignLeft,
+        Some("right") => Alignment::AlignRight,
+        Some("center") => Alignment::AlignCenter,
+        _ => Alignment::AlignDefault,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_html_table() {
+        let html = "<table>\n<caption>Totals</caption>\n<tr><th>Item</th><th align=\"right\">Qty</th></tr>\n\
+                    <tr><td>Apples</td><td style=\"text-align: right\">3</td></tr>\n\
+                    <tr><td colspan=2>All <b>done</b></td></tr>\n</table>";
+        let table = parse_html_table(html).unwrap();
+        assert_eq!(table.caption.as_deref(), Some("Totals"));
+        assert_eq!(table.head.len(), 1);
+        assert_eq!(table.head[0][1].align, Alignment::AlignRight);
+        assert_eq!(table.body.len(), 2);
+        assert_eq!(table.body[0][1].content, "3");
+        assert_eq!(table.body[0][1].align, Alignment::AlignRight);
+        assert_eq!(table.body[1][0].content, "All <b>done</b>");
+        assert_eq!(table.body[1][0].col_span, 2);
+    }
+
+    #[test]
+    fn test_implicitly_closed_cells_and_sections() {
+        let html = "<TABLE><THEAD><TR><TH>a<TH>b<TBODY><TR><TD>1<TD>2<TR><TD>3<TD>4</TABLE>";
+        let table = parse_html_table(html).unwrap();
+        assert_eq!(table.head.len(), 1);
+        assert_eq!(table.body.len(), 2);
+        assert_eq!(table.body[1][1].content, "4");
+    }
+
+    #[test]
+    fn test_rejects_other_html() {
+        assert_eq!(parse_html_table("<div>x</div>"), None);
+        assert_eq!(parse_html_table("<table></table>"), None);
+        assert_eq!(
+            parse_html_table("<table><tr><td><table><tr><td>x</td></tr></table></td></tr></table>"),
+            None
+        );
+    }
+}
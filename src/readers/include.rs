@@ -0,0 +1,101 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::readers::markdown::split_front_matter;
+use crate::utils::error::{PandorustError, Result};
+use crate::utils::resources::find_resource;
+
+/// How deeply `!include` directives may nest.
+pub const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Splice `!include path/to/file.md` lines into the Markdown source before
+/// parsing. Paths are relative to the directory of the including file;
+/// `input_path` is the top-level file, or `None` for stdin (paths are then
+/// relative to the working directory). A file not found there is looked up
+/// in each `resource_path` directory in turn. Included files may include others;
+/// their front matter is dropped so only the top file's metadata counts.
+/// Directives inside fenced code blocks are left as written.
+///
+/// Fails on a missing file, an include cycle, or nesting deeper than
+/// [`MAX_INCLUDE_DEPTH`].
+pub fn expand_includes(
+    input: &str,
+    input_path: Option<&Path>,
+    resource_path: &[PathBuf],
+) -> Result<String> {
+    let mut stack = Vec::new();
+    let mut base_dir = PathBuf::from(".");
+    if let Some(canonical) = input_path.and_then(|p| p.canonicalize().ok()) {
+        if let Some(parent) = canonical.parent() {
+            base_dir = parent.to_path_buf();
+        }
+        stack.push(canonical);
+    }
+    expand(input, &base_dir, resource_path, &mut stack)
+}
+
+fn expand(
+    input: &str,
+    base_dir: &Path,
+    resource_path: &[PathBuf],
+    stack: &mut Vec<PathBuf>,
+) -> Result<String> {
+    let mut out = String::with_capacity(input.len());
+    let mut fence: Option<String> = None;
+
+    for line in input.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if let Some(marker) = fence_marker(trimmed) {
+            match &fence {
+                Some(open) if marker.starts_with(open.as_str()) => fence = None,
+                None => fence = Some(marker),
+                _ => {}
+            }
+        }
+        let target = trimmed.strip_prefix("!include ").map(str::trim);
+        let Some(target) = target.filter(|t| fence.is_none() && !t.is_empty()) else {
+            out.push_str(line);
+            continue;
+        };
+
+        let path = find_resource(target, base_dir, resource_path)
+            .unwrap_or_else(|| base_dir.join(target));
+        let canonical = path.canonicalize().map_err(|e| {
+            PandorustError::IncludeError(format!("cannot include '{}': {}", path.display(), e))
+        })?;
+        if stack.contains(&canonical) {
+            return Err(PandorustError::IncludeError(format!(
+                "include cycle: '{}' includes itself",
+                path.display()
+            )));
+        }
+        if stack.len() > MAX_INCLUDE_DEPTH {
+            return Err(PandorustError::IncludeError(format!(
+                "includes nested deeper than {} levels at '{}'",
+                MAX_INCLUDE_DEPTH,
+                path.display()
+            )));
+        }
+
+        let source = String::from_utf8(fs::read(&canonical)?)
+            .map_err(|_| PandorustError::InvalidUtf8(path.display().to_string()))?;
+        let (_, body) = split_front_matter(&source);
+        let dir = canonical.parent().unwrap_or(base_dir).to_path_buf();
+        stack.push(canonical);
+        let included = expand(body, &dir, resource_path, stack)?;
+        stack.pop();
+
+        // Keep the included text a separate block from its neighbours
+        out.push('\n');
+        out.push_str(included.trim_matches('\n'));
+        out.push_str("\n\n");
+    }
+    Ok(out)
+}
+
+/// The run of backticks or tildes opening a fenced code block line.
+fn fence_marker(line: &str) -> Option<String> {
+    let c = line.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let run: String = line.chars().take_while(|&x| x == c).collect();
+    (run.len() >= 3).then_some(run)
+}
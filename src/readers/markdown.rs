@@ -1,43 +1,858 @@
+use std::collections::HashMap;
+
 use comrak::nodes::{AstNode, ListType, NodeValue, TableAlignment};
 use comrak::{parse_document, Arena, Options};
 
+use crate::ast::inline::inlines_text;
+use crate::ast::walk::walk_blocks_mut;
 use crate::ast::*;
+use crate::readers::criticmarkup::convert_criticmarkup;
+use crate::readers::emoji::replace_emoji;
+use crate::readers::grid_table::{
+    extract_grid_tables, parse_fenced_div_placeholder, parse_grid_table_placeholder, GridTable,
+};
+use crate::readers::html_table::{parse_html_table, HtmlCell, HtmlTable};
+use crate::readers::options::ReaderOptions;
 use crate::utils::error::Result;
 
 /// Parse a markdown string into a Document AST.
 pub fn read_markdown(input: &str) -> Result<Document> {
-    // Pre-process grid tables and \newpage before passing to comrak
-    let preprocessed = crate::readers::grid_table::preprocess_grid_tables(input);
-    let (yaml, body) = split_front_matter(&preprocessed);
-    let meta = parse_yaml_meta(yaml)?;
+    read_markdown_with(input, &ReaderOptions::default())
+}
 
-    let arena = Arena::new();
+/// Parse a markdown string into a Document AST with the given options.
+pub fn read_markdown_with(input: &str, options: &ReaderOptions) -> Result<Document> {
+    // Pre-process grid tables and \newpage before passing to comrak. Strict
+    // CommonMark has neither.
+    let (preprocessed, grid_tables) = if options.commonmark {
+        (input.to_string(), Vec::new())
+    } else {
+        extract_grid_tables(input)
+    };
+    let (yaml, blocks) = parse_document_blocks(&preprocessed, options);
+    let mut meta = parse_yaml_meta(yaml.as_deref())?;
+    parse_abstract(&mut meta, options);
+    meta.merge_defaults(&options.metadata);
+
+    let mut blocks = group_fenced_divs(splice_grid_tables(blocks, &grid_tables));
+    if options.html_tables {
+        walk_blocks_mut(&mut blocks, &mut |block| {
+            if let Block::RawBlock(format, html) = block
+                && format.0 == "html"
+                && let Some(table) = parse_html_table(html)
+            {
+                *block = html_to_table(&table);
+            }
+        });
+    }
+    let mut blocks = apply_inline_passes(blocks, options);
+    if options.continue_lists {
+        blocks = continue_ordered_lists(blocks);
+    }
+
+    let mut doc = Document { meta, blocks };
+    doc.apply_substitutions();
+    Ok(doc)
+}
+
+/// Read a standalone YAML or JSON metadata file (`--metadata-file`) into
+/// Meta, the same way as front matter.
+pub fn read_metadata(input: &str, options: &ReaderOptions) -> Result<Meta> {
+    let mut meta = parse_yaml_meta(Some(input.trim()))?;
+    parse_abstract(&mut meta, options);
+    Ok(meta)
+}
+
+/// The abstract is authored as Markdown, so it may contain emphasis etc.
+fn parse_abstract(meta: &mut Meta, options: &ReaderOptions) {
+    if let Some(MetaValue::String(text)) = meta.entries.get("abstract").cloned() {
+        let blocks = apply_inline_passes(parse_blocks(&text, options), options);
+        meta.entries
+            .insert("abstract".to_string(), MetaValue::Blocks(blocks));
+    }
+}
+
+/// Renumber ordered lists that start at 1 to follow on from the previous
+/// ordered list in the same block sequence. A heading starts a new count, and
+/// a list with any other explicit start keeps it.
+fn continue_ordered_lists(blocks: Vec<Block>) -> Vec<Block> {
+    let mut next: Option<u32> = None;
+    blocks
+        .into_iter()
+        .map(|block| match block {
+            Block::OrderedList(mut attrs, items) => {
+                if attrs.start == 1
+                    && let Some(n) = next
+                {
+                    attrs.start = n;
+                }
+                next = Some(attrs.start + items.len() as u32);
+                Block::OrderedList(attrs, items)
+            }
+            Block::Heading(..) => {
+                next = None;
+                block
+            }
+            Block::BlockQuote(inner) => Block::BlockQuote(continue_ordered_lists(inner)),
+            Block::Div(attr, inner) => Block::Div(attr, continue_ordered_lists(inner)),
+            other => other,
+        })
+        .collect()
+}
+
+/// Run the inline syntax passes over the whole document.
+fn apply_inline_passes(blocks: Vec<Block>, options: &ReaderOptions) -> Vec<Block> {
+    let mut blocks = blocks;
+    // Before merging text, while escaped characters are still their own Str
+    if !options.commonmark {
+        blocks = map_inlines(blocks, &escaped_spaces_to_nbsp);
+    }
+    blocks = map_inlines(blocks, &merge_adjacent_str);
+    blocks = map_inlines(blocks, &convert_html_tag_pairs);
+    if !options.commonmark {
+        blocks = map_inlines(blocks, &inline_code_attributes);
+    }
+    // Before highlighting, which would otherwise claim `{==text==}`
+    if options.criticmarkup {
+        blocks = map_inlines(blocks, &convert_criticmarkup);
+    }
+    if options.highlight && !options.commonmark {
+        blocks = map_inlines(blocks, &mark_highlights);
+    }
+    if options.smart {
+        blocks = map_inlines(blocks, &smart_apostrophes);
+    }
+    if options.emoji {
+        blocks = map_inlines(blocks, &replace_emoji);
+    }
+    blocks
+}
+
+/// Replace a backslash before a space (`Fig.\ 1`) with U+00A0, as pandoc
+/// does. comrak leaves `\ ` as text, while an escaped backslash (`\\ `)
+/// arrives as a Str of its own and is not matched.
+fn escaped_spaces_to_nbsp(inlines: Vec<Inline>) -> Vec<Inline> {
+    inlines
+        .into_iter()
+        .map(|inline| match inline {
+            Inline::Str(s) if s.contains("\\ ") => Inline::Str(s.replace("\\ ", "\u{a0}")),
+            other => other,
+        })
+        .collect()
+}
+
+/// Attach an attribute block written right after inline code
+/// (`` `let x = 1`{.rust} ``) to the code. Braces holding anything but
+/// `.class`, `#id` and `key=value` (`` `HashMap`{K, V} ``) stay text.
+fn inline_code_attributes(inlines: Vec<Inline>) -> Vec<Inline> {
+    let mut result: Vec<Inline> = Vec::with_capacity(inlines.len());
+    for inline in inlines {
+        if let Inline::Str(s) = &inline
+            && let Some(Inline::Code(attr, _)) = result.last_mut()
+            && *attr == Attr::empty()
+            && let Some(end) = s.strip_prefix('{').and_then(|rest| rest.find('}'))
+            && is_attr_block(&s[1..end + 1])
+        {
+            *attr = parse_attr_block(&s[1..end + 1]);
+            let rest = &s[end + 2..];
+            if !rest.is_empty() {
+                result.push(Inline::Str(rest.to_string()));
+            }
+            continue;
+        }
+        result.push(inline);
+    }
+    result
+}
+
+/// Replace a straight apostrophe between two letters with U+2019.
+fn smart_apostrophes(inlines: Vec<Inline>) -> Vec<Inline> {
+    inlines
+        .into_iter()
+        .map(|inline| match inline {
+            Inline::Str(s) if s.contains('\'') => {
+                let chars: Vec<char> = s.chars().collect();
+                let text = chars
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &c)| {
+                        let between_letters = i > 0
+                            && chars[i - 1].is_alphabetic()
+                            && chars.get(i + 1).is_some_and(|n| n.is_alphabetic());
+                        if c == '\'' && between_letters { '\u{2019}' } else { c }
+                    })
+                    .collect();
+                Inline::Str(text)
+            }
+            other => other,
+        })
+        .collect()
+}
+
+/// Apply `f` to every inline sequence in `blocks`, innermost first,
+/// including table cells, captions, definition terms and notes.
+fn map_inlines<F: Fn(Vec<Inline>) -> Vec<Inline>>(blocks: Vec<Block>, f: &F) -> Vec<Block> {
+    let map_items = |items: Vec<Vec<Block>>| -> Vec<Vec<Block>> {
+        items.into_iter().map(|item| map_inlines(item, f)).collect()
+    };
+    let map_rows = |rows: Vec<Row>| -> Vec<Row> {
+        rows.into_iter()
+            .map(|row| Row {
+                attr: row.attr,
+                cells: row
+                    .cells
+                    .into_iter()
+                    .map(|cell| Cell {
+                        content: map_inlines(cell.content, f),
+                        ..cell
+                    })
+                    .collect(),
+            })
+            .collect()
+    };
+
+    blocks
+        .into_iter()
+        .map(|block| match block {
+            Block::Plain(inlines) => Block::Plain(map_inline_seq(inlines, f)),
+            Block::Para(inlines) => Block::Para(map_inline_seq(inlines, f)),
+            Block::LineBlock(lines) => Block::LineBlock(
+                lines.into_iter().map(|l| map_inline_seq(l, f)).collect(),
+            ),
+            Block::Heading(attr, level, inlines) => {
+                Block::Heading(attr, level, map_inline_seq(inlines, f))
+            }
+            Block::BlockQuote(inner) => Block::BlockQuote(map_inlines(inner, f)),
+            Block::BulletList(items) => Block::BulletList(map_items(items)),
+            Block::OrderedList(attrs, items) => Block::OrderedList(attrs, map_items(items)),
+            Block::DefinitionList(items) => Block::DefinitionList(
+                items
+                    .into_iter()
+                    .map(|(term, defs)| (map_inline_seq(term, f), map_items(defs)))
+                    .collect(),
+            ),
+            Block::Table(table) => Block::Table(Table {
+                caption: Caption {
+                    short: table.caption.short.map(|s| map_inline_seq(s, f)),
+                    long: map_inlines(table.caption.long, f),
+                },
+                head: TableHead {
+                    attr: table.head.attr,
+                    rows: map_rows(table.head.rows),
+                },
+                bodies: table
+                    .bodies
+                    .into_iter()
+                    .map(|body| TableBody {
+                        head: map_rows(body.head),
+                        body: map_rows(body.body),
+                        ..body
+                    })
+                    .collect(),
+                foot: TableFoot {
+                    attr: table.foot.attr,
+                    rows: map_rows(table.foot.rows),
+                },
+                ..table
+            }),
+            Block::Figure(attr, caption, inner) => Block::Figure(
+                attr,
+                Caption {
+                    short: caption.short.map(|s| map_inline_seq(s, f)),
+                    long: map_inlines(caption.long, f),
+                },
+                map_inlines(inner, f),
+            ),
+            Block::Div(attr, inner) => Block::Div(attr, map_inlines(inner, f)),
+            other => other,
+        })
+        .collect()
+}
+
+/// Apply `f` to an inline sequence after applying it to any nested ones.
+fn map_inline_seq<F: Fn(Vec<Inline>) -> Vec<Inline>>(inlines: Vec<Inline>, f: &F) -> Vec<Inline> {
+    let mapped = inlines
+        .into_iter()
+        .map(|inline| match inline {
+            Inline::Emph(inner) => Inline::Emph(map_inline_seq(inner, f)),
+            Inline::Strong(inner) => Inline::Strong(map_inline_seq(inner, f)),
+            Inline::Underline(inner) => Inline::Underline(map_inline_seq(inner, f)),
+            Inline::Strikeout(inner) => Inline::Strikeout(map_inline_seq(inner, f)),
+            Inline::Superscript(inner) => Inline::Superscript(map_inline_seq(inner, f)),
+            Inline::Subscript(inner) => Inline::Subscript(map_inline_seq(inner, f)),
+            Inline::SmallCaps(inner) => Inline::SmallCaps(map_inline_seq(inner, f)),
+            Inline::Quoted(kind, inner) => Inline::Quoted(kind, map_inline_seq(inner, f)),
+            Inline::Link(attr, inner, target) => Inline::Link(attr, map_inline_seq(inner, f), target),
+            Inline::Image(attr, inner, target) => {
+                Inline::Image(attr, map_inline_seq(inner, f), target)
+            }
+            Inline::Span(attr, inner) => Inline::Span(attr, map_inline_seq(inner, f)),
+            Inline::Note(label, blocks) => Inline::Note(label, map_inlines(blocks, f)),
+            other => other,
+        })
+        .collect();
+    f(mapped)
+}
+
+/// Turn `==text==` runs into `Span` inlines with class `mark`. The text may
+/// span several inlines (`==a *b* c==`) but must not start or end with a space.
+fn mark_highlights(inlines: Vec<Inline>) -> Vec<Inline> {
+    enum Token {
+        Inline(Inline),
+        Marker,
+    }
+
+    if !inlines
+        .iter()
+        .any(|i| matches!(i, Inline::Str(s) if s.contains("==")))
+    {
+        return inlines;
+    }
+
+    let mut tokens = Vec::new();
+    for inline in inlines {
+        match inline {
+            Inline::Str(s) if s.contains("==") => {
+                for (n, part) in s.split("==").enumerate() {
+                    if n > 0 {
+                        tokens.push(Token::Marker);
+                    }
+                    if !part.is_empty() {
+                        tokens.push(Token::Inline(Inline::Str(part.to_string())));
+                    }
+                }
+            }
+            other => tokens.push(Token::Inline(other)),
+        }
+    }
+
+    let is_space = |token: &Token, at_start: bool| match token {
+        Token::Inline(Inline::Space | Inline::SoftBreak | Inline::LineBreak) => true,
+        Token::Inline(Inline::Str(s)) => {
+            let c = if at_start { s.chars().next() } else { s.chars().last() };
+            c.is_some_and(char::is_whitespace)
+        }
+        _ => false,
+    };
+
+    let mut result: Vec<Inline> = Vec::new();
+    let mut tokens = tokens.into_iter().peekable();
+    let mut pending: Vec<Token> = Vec::new();
+    while let Some(token) = tokens.next() {
+        if !matches!(token, Token::Marker) {
+            pending.push(token);
+            continue;
+        }
+        // Collect up to the next marker
+        let mut inner = Vec::new();
+        let mut closed = false;
+        while let Some(t) = tokens.next_if(|t| !matches!(t, Token::Marker)) {
+            inner.push(t);
+        }
+        if tokens.next_if(|t| matches!(t, Token::Marker)).is_some() {
+            closed = true;
+        }
+        for t in pending.drain(..) {
+            if let Token::Inline(i) = t {
+                result.push(i);
+            }
+        }
+        let valid = closed
+            && !inner.is_empty()
+            && !is_space(&inner[0], true)
+            && !is_space(&inner[inner.len() - 1], false);
+        if valid {
+            let content = inner
+                .into_iter()
+                .filter_map(|t| match t {
+                    Token::Inline(i) => Some(i),
+                    Token::Marker => None,
+                })
+                .collect();
+            let attr = Attr {
+                id: String::new(),
+                classes: vec!["mark".to_string()],
+                attrs: vec![],
+            };
+            result.push(Inline::Span(attr, content));
+        } else {
+            // Not a highlight: keep the markers as text
+            result.push(Inline::Str("==".to_string()));
+            for t in inner {
+                if let Token::Inline(i) = t {
+                    result.push(i);
+                }
+            }
+            if closed {
+                result.push(Inline::Str("==".to_string()));
+            }
+        }
+    }
+    for t in pending {
+        if let Token::Inline(i) = t {
+            result.push(i);
+        }
+    }
+
+    merge_adjacent_str(result)
+}
+
+/// Inline HTML tags that have a semantic equivalent in the AST.
+const SEMANTIC_HTML_TAGS: [&str; 5] = ["sub", "sup", "mark", "u", "kbd"];
+
+/// Tag name of a bare `<tag>` or `</tag>` with no attributes, if it is one
+/// of [`SEMANTIC_HTML_TAGS`]. Returns the name and whether it is a close tag.
+fn semantic_html_tag(inline: &Inline) -> Option<(&'static str, bool)> {
+    let Inline::RawInline(Format(fmt), raw) = inline else {
+        return None;
+    };
+    if fmt != "html" {
+        return None;
+    }
+    let inner = raw.strip_prefix('<')?.strip_suffix('>')?;
+    let (name, closing) = match inner.strip_prefix('/') {
+        Some(name) => (name, true),
+        None => (inner, false),
+    };
+    let name = name.trim().to_ascii_lowercase();
+    SEMANTIC_HTML_TAGS
+        .iter()
+        .find(|t| **t == name)
+        .map(|t| (*t, closing))
+}
+
+/// Replace matched pairs like `<sup>2</sup>` with the semantic inline so they
+/// render in every output format. Unmatched or unknown tags stay raw HTML.
+fn convert_html_tag_pairs(mut inlines: Vec<Inline>) -> Vec<Inline> {
+    let mut i = 0;
+    while i < inlines.len() {
+        if let Some((tag, false)) = semantic_html_tag(&inlines[i])
+            && let Some(len) = matching_close_tag(&inlines[i + 1..], tag)
+        {
+            let content: Vec<Inline> = inlines.drain(i + 1..i + 1 + len).collect();
+            inlines.remove(i + 1); // the close tag
+            inlines[i] = semantic_inline(tag, convert_html_tag_pairs(content));
+        }
+        i += 1;
+    }
+    inlines
+}
+
+/// Index of the close tag matching an already-opened `tag`, allowing nested
+/// pairs of the same tag.
+fn matching_close_tag(inlines: &[Inline], tag: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, inline) in inlines.iter().enumerate() {
+        match semantic_html_tag(inline) {
+            Some((t, false)) if t == tag => depth += 1,
+            Some((t, true)) if t == tag => {
+                if depth == 0 {
+                    return Some(i);
+                }
+                depth -= 1;
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The AST inline for a recognized HTML tag wrapping `content`.
+fn semantic_inline(tag: &str, content: Vec<Inline>) -> Inline {
+    match tag {
+        "sub" => Inline::Subscript(content),
+        "sup" => Inline::Superscript(content),
+        "u" => Inline::Underline(content),
+        "mark" => Inline::Span(
+            Attr {
+                id: String::new(),
+                classes: vec!["mark".to_string()],
+                attrs: vec![],
+            },
+            content,
+        ),
+        _ => Inline::Code(
+            Attr {
+                id: String::new(),
+                classes: vec!["kbd".to_string()],
+                attrs: vec![],
+            },
+            inlines_text(&content),
+        ),
+    }
+}
+
+/// Join neighbouring `Str` inlines split apart by a pass.
+pub(crate) fn merge_adjacent_str(inlines: Vec<Inline>) -> Vec<Inline> {
+    let mut merged: Vec<Inline> = Vec::with_capacity(inlines.len());
+    for inline in inlines {
+        match (merged.last_mut(), inline) {
+            (Some(Inline::Str(prev)), Inline::Str(s)) => prev.push_str(&s),
+            (_, inline) => merged.push(inline),
+        }
+    }
+    merged
+}
+
+/// Parse a Markdown fragment that has not been preprocessed yet, such as the
+/// text of a grid table cell. Grid tables nested inside it are handled too.
+fn parse_fragment(text: &str) -> Vec<Block> {
+    let (preprocessed, grid_tables) = extract_grid_tables(text);
+    let blocks = parse_blocks(&preprocessed, &ReaderOptions::default());
+    group_fenced_divs(splice_grid_tables(blocks, &grid_tables))
+}
+
+/// Rebuild keyword fenced divs (`::: note`) from the placeholder lines the
+/// preprocessor left around their content. The keyword becomes the class.
+fn group_fenced_divs(blocks: Vec<Block>) -> Vec<Block> {
+    let mut open: Vec<(Attr, Vec<Block>)> = Vec::new();
+    let mut result = Vec::new();
+    let push = |open: &mut Vec<(Attr, Vec<Block>)>, result: &mut Vec<Block>, block: Block| {
+        match open.last_mut() {
+            Some((_, inner)) => inner.push(block),
+            None => result.push(block),
+        }
+    };
+
+    for block in blocks {
+        let marker = match &block {
+            Block::RawBlock(format, html) if format.0 == "html" => {
+                parse_fenced_div_placeholder(html).map(|class| class.map(str::to_string))
+            }
+            _ => None,
+        };
+        match marker {
+            Some(Some(class)) => {
+                let mut attr = Attr::empty();
+                attr.classes.push(class);
+                open.push((attr, Vec::new()));
+            }
+            Some(None) => {
+                if let Some((attr, inner)) = open.pop() {
+                    push(&mut open, &mut result, Block::Div(attr, inner));
+                }
+            }
+            None => push(&mut open, &mut result, block),
+        }
+    }
+    // An unclosed div runs to the end of the document
+    while let Some((attr, inner)) = open.pop() {
+        push(&mut open, &mut result, Block::Div(attr, inner));
+    }
+    result
+}
+
+/// Replace grid table placeholders left by the preprocessor with the tables
+/// they stand for, searching inside container blocks as well.
+fn splice_grid_tables(blocks: Vec<Block>, tables: &[GridTable]) -> Vec<Block> {
+    if tables.is_empty() {
+        return blocks;
+    }
+    let splice_all = |items: Vec<Vec<Block>>| -> Vec<Vec<Block>> {
+        items
+            .into_iter()
+            .map(|item| splice_grid_tables(item, tables))
+            .collect()
+    };
+    blocks
+        .into_iter()
+        .map(|block| match block {
+            Block::RawBlock(format, html) if format.0 == "html" => {
+                match parse_grid_table_placeholder(&html).and_then(|i| tables.get(i)) {
+                    Some(table) => grid_to_table(table),
+                    None => Block::RawBlock(format, html),
+                }
+            }
+            Block::BlockQuote(inner) => Block::BlockQuote(splice_grid_tables(inner, tables)),
+            Block::BulletList(items) => Block::BulletList(splice_all(items)),
+            Block::OrderedList(attrs, items) => Block::OrderedList(attrs, splice_all(items)),
+            Block::DefinitionList(items) => Block::DefinitionList(
+                items
+                    .into_iter()
+                    .map(|(term, defs)| (term, splice_all(defs)))
+                    .collect(),
+            ),
+            Block::Div(attr, inner) => Block::Div(attr, splice_grid_tables(inner, tables)),
+            Block::Figure(attr, caption, inner) => {
+                Block::Figure(attr, caption, splice_grid_tables(inner, tables))
+            }
+            other => other,
+        })
+        .collect()
+}
+
+/// Build a Table from a grid table, parsing each cell as Markdown blocks.
+fn grid_to_table(grid: &GridTable) -> Block {
+    let to_row = |cells: &Vec<String>| Row {
+        attr: Attr::empty(),
+        cells: cells
+            .iter()
+            .map(|text| Cell {
+                attr: Attr::empty(),
+                align: Alignment::AlignDefault,
+                row_span: 1,
+                col_span: 1,
+                content: cell_blocks(text),
+            })
+            .collect(),
+    };
+
+    Block::Table(Table {
+        attr: Attr::empty(),
+        caption: Caption::default(),
+        col_specs: (0..grid.num_cols)
+            .map(|_| ColSpec {
+                align: Alignment::AlignDefault,
+                width: ColWidth::Default,
+            })
+            .collect(),
+        head: TableHead {
+            attr: Attr::empty(),
+            rows: grid.head.iter().map(to_row).collect(),
+        },
+        bodies: vec![TableBody {
+            attr: Attr::empty(),
+            row_head_columns: 0,
+            head: vec![],
+            body: grid.body.iter().map(to_row).collect(),
+        }],
+        foot: TableFoot {
+            attr: Attr::empty(),
+            rows: vec![],
+        },
+    })
+}
+
+/// Parse a table cell or caption as Markdown blocks. A lone paragraph is
+/// cell text, like pipe table cells.
+fn cell_blocks(text: &str) -> Vec<Block> {
+    match parse_fragment(text).as_slice() {
+        [] => vec![Block::Plain(vec![])],
+        [Block::Para(inlines)] => vec![Block::Plain(inlines.clone())],
+        blocks => blocks.to_vec(),
+    }
+}
+
+/// Build a Table from a raw HTML table. Cell contents are read as Markdown,
+/// so inline HTML in them goes through the same passes as the rest of the
+/// document.
+fn html_to_table(html: &HtmlTable) -> Block {
+    let to_row = |cells: &Vec<HtmlCell>| Row {
+        attr: Attr::empty(),
+        cells: cells
+            .iter()
+            .map(|cell| Cell {
+                attr: Attr::empty(),
+                align: cell.align.clone(),
+                row_span: cell.row_span,
+                col_span: cell.col_span,
+                content: cell_blocks(&cell.content),
+            })
+            .collect(),
+    };
+    let rows = html.head.iter().chain(&html.body).chain(&html.foot);
+    let num_cols = rows
+        .clone()
+        .map(|row| row.iter().map(|c| c.col_span as usize).sum::<usize>())
+        .max()
+        .unwrap_or(0);
+    // Column alignment follows the first row whose cells each span one column
+    let aligns: Vec<Alignment> = rows
+        .clone()
+        .find(|row| row.len() == num_cols)
+        .map(|row| row.iter().map(|c| c.align.clone()).collect())
+        .unwrap_or_else(|| vec![Alignment::AlignDefault; num_cols]);
+
+    Block::Table(Table {
+        attr: Attr::empty(),
+        caption: Caption {
+            short: None,
+            long: html.caption.as_deref().map(cell_blocks).unwrap_or_default(),
+        },
+        col_specs: aligns
+            .into_iter()
+            .map(|align| ColSpec {
+                align,
+                width: ColWidth::Default,
+            })
+            .collect(),
+        head: TableHead {
+            attr: Attr::empty(),
+            rows: html.head.iter().map(to_row).collect(),
+        },
+        bodies: vec![TableBody {
+            attr: Attr::empty(),
+            row_head_columns: 0,
+            head: vec![],
+            body: html.body.iter().map(to_row).collect(),
+        }],
+        foot: TableFoot {
+            attr: Attr::empty(),
+            rows: html.foot.iter().map(to_row).collect(),
+        },
+    })
+}
+
+/// comrak options shared by every Markdown parse. Strict CommonMark turns
+/// off every extension, leaving `~~`, `|` tables and the like literal.
+fn markdown_options(reader: &ReaderOptions) -> Options<'static> {
     let mut options = Options::default();
+    if reader.commonmark {
+        return options;
+    }
     options.extension.strikethrough = true;
     options.extension.table = true;
     options.extension.tasklist = true;
     options.extension.superscript = true;
+    options.extension.math_dollars = true;
+    options.extension.description_lists = true;
+    options.extension.footnotes = true;
+    options.extension.alerts = reader.github_alerts;
+    // Keep escaped characters apart, so `\\ ` is not read as `\ `
+    options.render.escaped_char_spans = true;
+    options
+}
 
-    let root = parse_document(&arena, body, &options);
-    let blocks = convert_children(root);
+/// Parse a markdown body (no front matter) into blocks.
+fn parse_blocks(body: &str, reader: &ReaderOptions) -> Vec<Block> {
+    let arena = Arena::new();
+    let root = parse_document(&arena, body, &markdown_options(reader));
+    if !reader.indented_code {
+        reparse_indented_code(&arena, root, &markdown_options(reader));
+    }
+    convert_root(root).1
+}
 
-    Ok(Document { meta, blocks })
+/// Replace each indented (unfenced) code block with its text parsed as
+/// Markdown, so indentation alone never makes code.
+fn reparse_indented_code<'a>(arena: &'a Arena<AstNode<'a>>, root: &'a AstNode<'a>, options: &Options) {
+    let indented: Vec<&'a AstNode<'a>> = root
+        .descendants()
+        .filter(|node| matches!(&node.data.borrow().value, NodeValue::CodeBlock(code) if !code.fenced))
+        .collect();
+    for node in indented {
+        let text = match &node.data.borrow().value {
+            NodeValue::CodeBlock(code) => code.literal.clone(),
+            _ => continue,
+        };
+        let parsed = parse_document(arena, &text, options);
+        // The text lost one level of indentation; deeper lines may still be code
+        reparse_indented_code(arena, parsed, options);
+        let children: Vec<&'a AstNode<'a>> = parsed.children().collect();
+        for child in children {
+            node.insert_before(child);
+        }
+        node.detach();
+    }
 }
 
-fn split_front_matter(input: &str) -> (Option<&str>, &str) {
-    let trimmed = input.trim_start();
-    if !trimmed.starts_with("---") {
-        return (None, input);
+/// Parse a whole document, letting comrak pick out the YAML front matter.
+/// Returns the front matter contents without delimiters, and the body blocks.
+fn parse_document_blocks(input: &str, reader: &ReaderOptions) -> (Option<String>, Vec<Block>) {
+    let arena = Arena::new();
+    let mut options = markdown_options(reader);
+    options.extension.front_matter_delimiter = Some("---".to_string());
+    let root = parse_document(&arena, input, &options);
+    if !reader.indented_code {
+        reparse_indented_code(&arena, root, &markdown_options(reader));
     }
+    let (yaml, blocks) = convert_root(root);
 
-    let after_open = &trimmed[3..];
-    if let Some(close_pos) = after_open.find("\n---") {
-        let yaml = after_open[..close_pos].trim();
-        let body = &after_open[close_pos + 4..];
-        (Some(yaml), body)
-    } else {
-        (None, input)
+    // comrak only accepts `---` as the closing line and no leading blank lines
+    if yaml.is_none()
+        && let (Some(fallback), body) = split_front_matter(input)
+    {
+        return (Some(fallback.to_string()), parse_blocks(body, reader));
+    }
+    (yaml, blocks)
+}
+
+/// Convert a parsed document's top-level nodes, returning the front matter
+/// contents and the blocks. Footnote definitions are moved into the `Note`
+/// inlines that reference them, wherever those are (table cells included).
+fn convert_root<'a>(root: &'a AstNode<'a>) -> (Option<String>, Vec<Block>) {
+    let mut yaml = None;
+    let mut notes: HashMap<String, Vec<Block>> = HashMap::new();
+    let mut blocks = Vec::new();
+    for node in root.children() {
+        match &node.data.borrow().value {
+            NodeValue::FrontMatter(raw) => yaml = Some(front_matter_contents(raw)),
+            NodeValue::FootnoteDefinition(def) => {
+                notes.insert(def.name.to_lowercase(), convert_children(node));
+            }
+            _ => blocks.push(convert_node(node)),
+        }
+    }
+    if notes.is_empty() {
+        return (yaml, blocks);
     }
+
+    let resolve = |inlines: Vec<Inline>| -> Vec<Inline> {
+        inlines
+            .into_iter()
+            .map(|inline| match footnote_name(&inline).and_then(|name| notes.get_key_value(name)) {
+                Some((label, body)) => Inline::Note(label.clone(), body.clone()),
+                None => inline,
+            })
+            .collect()
+    };
+    (yaml, map_inlines(blocks, &resolve))
+}
+
+/// Placeholder for a footnote reference until its definition is known.
+fn footnote_ref(name: &str) -> Inline {
+    Inline::Span(
+        Attr {
+            id: String::new(),
+            classes: vec!["footnote-ref".to_string()],
+            attrs: vec![("name".to_string(), name.to_lowercase())],
+        },
+        vec![],
+    )
+}
+
+fn footnote_name(inline: &Inline) -> Option<&str> {
+    match inline {
+        Inline::Span(attr, content)
+            if content.is_empty() && attr.classes.iter().any(|c| c == "footnote-ref") =>
+        {
+            attr.attrs.iter().find(|(k, _)| k == "name").map(|(_, v)| v.as_str())
+        }
+        _ => None,
+    }
+}
+
+/// Strip the `---` delimiter lines from a comrak front matter literal.
+fn front_matter_contents(raw: &str) -> String {
+    let inner = raw.trim_end();
+    let inner = inner.strip_suffix("---").unwrap_or(inner);
+    let inner = inner.trim_start();
+    inner.strip_prefix("---").unwrap_or(inner).trim().to_string()
+}
+
+/// Split YAML front matter from the body by hand. The front matter must open
+/// with a line of exactly `---` and close with a `---` or `...` line;
+/// otherwise the whole input is body (e.g. a document starting with a `----`
+/// rule). Used when comrak's front matter extension does not match.
+pub(crate) fn split_front_matter(input: &str) -> (Option<&str>, &str) {
+    let trimmed = input.trim_start();
+    let mut lines = trimmed.split_inclusive('\n');
+    let yaml_start = match lines.next() {
+        Some(first) if first.trim_end() == "---" => first.len(),
+        _ => return (None, input),
+    };
+
+    let mut offset = yaml_start;
+    for line in lines {
+        let line_end = offset + line.len();
+        let content = line.trim_end();
+        if content == "---" || content == "..." {
+            let yaml = trimmed[yaml_start..offset].trim();
+            return (Some(yaml), &trimmed[line_end..]);
+        }
+        offset = line_end;
+    }
+    (None, input)
 }
 
 fn parse_yaml_meta(yaml: Option<&str>) -> Result<Meta> {
@@ -92,22 +907,36 @@ fn convert_node<'a>(node: &'a AstNode<'a>) -> Block {
             Block::Heading(Attr::empty(), heading.level, collect_inlines(node))
         }
         NodeValue::CodeBlock(code) => {
-            let lang = code.info.clone();
-            let attr = if lang.is_empty() {
-                Attr::empty()
-            } else {
-                Attr {
-                    id: String::new(),
-                    classes: vec![lang],
-                    attrs: vec![],
+            // A ```{=latex} fence holds raw content for that format
+            let info = code.info.trim();
+            match info.strip_prefix("{=").and_then(|f| f.strip_suffix('}')) {
+                Some(format) if !format.is_empty() && !format.contains(char::is_whitespace) => {
+                    Block::RawBlock(Format(format.to_string()), code.literal.to_string())
                 }
-            };
-            Block::CodeBlock(attr, code.literal.clone())
+                _ => Block::CodeBlock(parse_fence_info(info), code.literal.to_string()),
+            }
         }
         NodeValue::BlockQuote => Block::BlockQuote(convert_children(node)),
+        NodeValue::Alert(alert) => {
+            let kind = alert.alert_type.default_title().to_ascii_lowercase();
+            let mut attr = Attr::empty();
+            attr.classes = vec!["alert".to_string(), format!("alert-{}", kind)];
+            if let Some(title) = &alert.title {
+                attr.attrs.push(("title".to_string(), title.clone()));
+            }
+            Block::Div(attr, convert_children(node))
+        }
         NodeValue::List(list) => {
-            let items: Vec<Vec<Block>> =
-                node.children().map(|item| convert_children(item)).collect();
+            let items: Vec<Vec<Block>> = node
+                .children()
+                .map(|item| {
+                    let mut blocks = convert_children(item);
+                    if let NodeValue::TaskItem(checked) = item.data.borrow().value {
+                        add_task_checkbox(&mut blocks, checked.is_some());
+                    }
+                    if list.tight { tighten(blocks) } else { blocks }
+                })
+                .collect();
             match list.list_type {
                 ListType::Bullet => Block::BulletList(items),
                 ListType::Ordered => Block::OrderedList(
@@ -119,6 +948,31 @@ fn convert_node<'a>(node: &'a AstNode<'a>) -> Block {
                 ),
             }
         }
+        NodeValue::DescriptionList => {
+            let mut items: Vec<(Vec<Inline>, Vec<Vec<Block>>)> = Vec::new();
+            for item in node.children() {
+                let mut term = Vec::new();
+                let mut definitions = Vec::new();
+                for part in item.children() {
+                    match &part.data.borrow().value {
+                        NodeValue::DescriptionTerm => {
+                            term = part.children().flat_map(collect_inlines).collect();
+                        }
+                        NodeValue::DescriptionDetails => {
+                            definitions.push(convert_children(part));
+                        }
+                        _ => {}
+                    }
+                }
+                // comrak gives each extra `: definition` its own term-less
+                // item; attach it to the preceding term instead
+                match items.last_mut() {
+                    Some((_, previous)) if term.is_empty() => previous.extend(definitions),
+                    _ => items.push((term, definitions)),
+                }
+            }
+            Block::DefinitionList(items)
+        }
         NodeValue::ThematicBreak => Block::HorizontalRule,
         NodeValue::Table(table_data) => convert_table(node, table_data),
         NodeValue::HtmlBlock(html) => {
@@ -142,11 +996,120 @@ fn convert_node<'a>(node: &'a AstNode<'a>) -> Block {
     }
 }
 
+/// Paragraphs of a tight list item are plain text, as in pandoc.
+fn tighten(blocks: Vec<Block>) -> Vec<Block> {
+    blocks
+        .into_iter()
+        .map(|block| match block {
+            Block::Para(inlines) => Block::Plain(inlines),
+            other => other,
+        })
+        .collect()
+}
+
+/// Start a task-list item's text with its checkbox, a ballot box as in
+/// pandoc, so the checked state survives into every writer.
+fn add_task_checkbox(blocks: &mut Vec<Block>, checked: bool) {
+    let checkbox = Inline::task_checkbox(checked);
+    match blocks.first_mut() {
+        Some(Block::Para(inlines) | Block::Plain(inlines)) => {
+            inlines.splice(0..0, [checkbox, Inline::Space]);
+        }
+        _ => blocks.insert(0, Block::Plain(vec![checkbox])),
+    }
+}
+
+/// Build code block attributes from a fence info string.
+///
+/// Accepts a bare language (```` ```rust ````), a pandoc attribute block
+/// (```` ```{.rust .wrap #id key=value} ````), or both (```` ```rust {.wrap} ````).
+/// The language always becomes the first class.
+fn parse_fence_info(info: &str) -> Attr {
+    let info = info.trim();
+    let (lang, block) = match info.find('{') {
+        Some(open) if info.ends_with('}') => {
+            (info[..open].trim(), Some(&info[open + 1..info.len() - 1]))
+        }
+        _ => (info.split_whitespace().next().unwrap_or(""), None),
+    };
+
+    let mut attr = block.map(parse_attr_block).unwrap_or_default();
+    attr.attrs.retain(|(key, _)| is_attr_name(key));
+    if !lang.is_empty() {
+        attr.classes.insert(0, lang.to_string());
+    }
+    attr
+}
+
+/// Parse the inside of a pandoc attribute block: `#id .class key=value`.
+/// Values may be double-quoted to include spaces.
+fn parse_attr_block(s: &str) -> Attr {
+    let mut attr = Attr::empty();
+    for word in attr_words(s) {
+        if let Some(id) = word.strip_prefix('#') {
+            attr.id = id.to_string();
+        } else if let Some(class) = word.strip_prefix('.') {
+            attr.classes.push(class.to_string());
+        } else if let Some((key, value)) = word.split_once('=') {
+            attr.attrs.push((key.to_string(), value.to_string()));
+        } else if !word.is_empty() {
+            attr.classes.push(word);
+        }
+    }
+    attr
+}
+
+/// Whether `s` (without braces) is an attribute block made only of
+/// `.class`, `#id` and `key=value` words.
+fn is_attr_block(s: &str) -> bool {
+    let words = attr_words(s);
+    !words.is_empty()
+        && words.iter().all(|word| {
+            word.strip_prefix('.')
+                .or_else(|| word.strip_prefix('#'))
+                .map_or_else(|| word.split_once('=').is_some_and(|(key, _)| is_attr_name(key)), is_attr_name)
+        })
+}
+
+/// Whether `n` is a usable id, class or attribute key: letters, digits,
+/// `-`, `_` and `:` only.
+fn is_attr_name(n: &str) -> bool {
+    !n.is_empty() && n.chars().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | ':'))
+}
+
+/// The whitespace-separated words of an attribute block. Double quotes
+/// group a value with spaces and are removed.
+fn attr_words(s: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut word = String::new();
+        let mut in_quotes = false;
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() && !in_quotes {
+                break;
+            }
+            if c == '"' {
+                in_quotes = !in_quotes;
+            } else {
+                word.push(c);
+            }
+            chars.next();
+        }
+        words.push(word);
+    }
+    words
+}
+
 fn convert_table<'a>(
     node: &'a AstNode<'a>,
     table_data: &comrak::nodes::NodeTable,
 ) -> Block {
-    let col_specs: Vec<ColSpec> = table_data
+    let mut col_specs: Vec<ColSpec> = table_data
         .alignments
         .iter()
         .map(|a| ColSpec {
@@ -187,6 +1150,15 @@ fn convert_table<'a>(
         }
     }
 
+    // A row wider than the alignment row still gets a column for every cell
+    let widest = head_rows.iter().chain(&body_rows).map(|r| r.cells.len()).max().unwrap_or(0);
+    while col_specs.len() < widest {
+        col_specs.push(ColSpec {
+            align: Alignment::AlignDefault,
+            width: ColWidth::Default,
+        });
+    }
+
     Block::Table(Table {
         attr: Attr::empty(),
         caption: Caption::default(),
@@ -238,9 +1210,27 @@ fn convert_inline<'a>(node: &'a AstNode<'a>) -> Vec<Inline> {
                 title: link.title.clone(),
             },
         )],
+        NodeValue::Math(math) => {
+            let math_type = if math.display_math {
+                MathType::DisplayMath
+            } else {
+                MathType::InlineMath
+            };
+            vec![Inline::Math(math_type, math.literal.to_string())]
+        }
         NodeValue::HtmlInline(html) => {
             vec![Inline::RawInline(Format("html".into()), html.clone())]
         }
+        NodeValue::FootnoteReference(reference) => vec![footnote_ref(&reference.name)],
+        // A backslash-escaped character is plain text
+        NodeValue::Escaped => collect_inlines(node),
+        // Text between escaped delimiters keeps the delimiters as text
+        NodeValue::EscapedTag(tag) => {
+            let mut inlines = vec![Inline::Str(tag.clone())];
+            inlines.extend(collect_inlines(node));
+            inlines.push(Inline::Str(tag.clone()));
+            merge_adjacent_str(inlines)
+        }
         _ => collect_inlines(node),
     }
 }
@@ -1,2 +1,8 @@
+pub mod asciidoc;
+pub(crate) mod criticmarkup;
+pub(crate) mod emoji;
 pub mod grid_table;
-pub mod markdown;
\ No newline at end of file
+pub(crate) mod html_table;
+pub mod include;
+pub mod markdown;
+pub mod options;
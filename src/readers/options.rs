@@ -0,0 +1,54 @@
+use crate::ast::Meta;
+
+/// Options controlling how Markdown is read.
+#[derive(Debug, Clone)]
+pub struct ReaderOptions {
+    /// Turn `==text==` into a highlighted span (class `mark`).
+    pub highlight: bool,
+    /// Number an ordered list that starts at 1 on from the previous ordered
+    /// list in the same section, so a list split by a paragraph keeps counting.
+    pub continue_lists: bool,
+    /// Turn straight apostrophes between letters (`don't`) into typographic
+    /// ones. Code is left alone.
+    pub smart: bool,
+    /// Parse CriticMarkup review marks (`{++insert++}`, `{--delete--}`,
+    /// `{~~old~>new~~}`, `{==highlight==}`, `{>>comment<<}`) into spans.
+    pub criticmarkup: bool,
+    /// Replace `:shortcode:` text (`:tada:`, `:+1:`) with emoji. Unknown
+    /// shortcodes stay literal.
+    pub emoji: bool,
+    /// Parse strict CommonMark: no tables, strikethrough, task lists, grid
+    /// tables, fenced divs or `==highlight==`. Front matter is still read.
+    pub commonmark: bool,
+    /// Read GitHub alert blockquotes (`> [!NOTE]`, `> [!WARNING]`, ...) as
+    /// divs with classes `alert` and `alert-<type>`; the marker line is
+    /// dropped. A title after the marker becomes the `title` attribute.
+    pub github_alerts: bool,
+    /// Read lines indented four spaces as a code block, as CommonMark does.
+    /// When off, only fenced code is code.
+    pub indented_code: bool,
+    /// Read a raw HTML block that is a single `<table>` as a real table.
+    /// Cell contents are read as Markdown; tables the parser cannot follow,
+    /// such as nested ones, stay raw HTML.
+    pub html_tables: bool,
+    /// Metadata defaults, such as from `--metadata-file`. The document's
+    /// own metadata overrides them; both count for `{{key}}` substitutions.
+    pub metadata: Meta,
+}
+
+impl Default for ReaderOptions {
+    fn default() -> Self {
+        Self {
+            highlight: true,
+            continue_lists: false,
+            smart: false,
+            criticmarkup: false,
+            emoji: false,
+            commonmark: false,
+            github_alerts: false,
+            indented_code: true,
+            html_tables: false,
+            metadata: Meta::default(),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MONTHS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June", "July", "August", "September",
+    "October", "November", "December",
+];
+
+const WEEKDAYS: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+
+/// A calendar date in the proleptic Gregorian calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Date {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// Parse an ISO `YYYY-MM-DD` date. Anything else, including impossible
+    /// dates such as `2026-02-30`, is `None`.
+    pub fn parse_iso(text: &str) -> Option<Date> {
+        let mut parts = text.trim().split('-');
+        let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+        if parts.next().is_some()
+            || year.len() != 4
+            || month.len() != 2
+            || day.len() != 2
+            || ![year, month, day].iter().all(|p| p.bytes().all(|b| b.is_ascii_digit()))
+        {
+            return None;
+        }
+        let date = Date { year: year.parse().ok()?, month: month.parse().ok()?, day: day.parse().ok()? };
+        ((1..=12).contains(&date.month) && (1..=days_in_month(date.year, date.month)).contains(&date.day))
+            .then_some(date)
+    }
+
+    /// Today's date in UTC.
+    pub fn today() -> Date {
+        let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        civil_from_days(secs as i64 / 86_400)
+    }
+
+    /// Format with strftime-style directives: `%Y` (2026), `%y` (26), `%m`
+    /// (01), `%d` (01), `%e` (1), `%B` (January), `%b` (Jan), `%A` (Monday),
+    /// `%a` (Mon), `%j` (day of the year) and `%%`. Other directives are kept
+    /// as written.
+    pub fn format(&self, format: &str) -> String {
+        let month = MONTHS[self.month as usize - 1];
+        let weekday = WEEKDAYS[(days_from_civil(self) + 3).rem_euclid(7) as usize];
+        let mut out = String::with_capacity(format.len() + 16);
+        let mut chars = format.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                out.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('Y') => out.push_str(&self.year.to_string()),
+                Some('y') => out.push_str(&format!("{:02}", self.year.rem_euclid(100))),
+                Some('m') => out.push_str(&format!("{:02}", self.month)),
+                Some('d') => out.push_str(&format!("{:02}", self.day)),
+                Some('e') => out.push_str(&self.day.to_string()),
+                Some('B') => out.push_str(month),
+                Some('b') => out.push_str(&month[..3]),
+                Some('A') => out.push_str(weekday),
+                Some('a') => out.push_str(&weekday[..3]),
+                Some('j') => {
+                    let start = Date { year: self.year, month: 1, day: 1 };
+                    out.push_str(&format!("{:03}", days_from_civil(self) - days_from_civil(&start) + 1));
+                }
+                Some('%') => out.push('%'),
+                Some(other) => {
+                    out.push('%');
+                    out.push(other);
+                }
+                None => out.push('%'),
+            }
+        }
+        out
+    }
+
+    /// The ISO `YYYY-MM-DD` form.
+    pub fn to_iso(&self) -> String {
+        self.format("%Y-%m-%d")
+    }
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 (a Thursday).
+fn days_from_civil(date: &Date) -> i64 {
+    let year = if date.month <= 2 { date.year - 1 } else { date.year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = date.month as i64;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + date.day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The date `days` after 1970-01-01.
+fn civil_from_days(days: i64) -> Date {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    Date { year, month, day }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_iso() {
+        assert_eq!(Date::parse_iso("2026-01-01"), Some(Date { year: 2026, month: 1, day: 1 }));
+        assert_eq!(Date::parse_iso("2024-02-29").map(|d| d.day), Some(29));
+        assert_eq!(Date::parse_iso("2026-02-29"), None);
+        assert_eq!(Date::parse_iso("Spring 2026"), None);
+        assert_eq!(Date::parse_iso("2026-1-1"), None);
+    }
+
+    #[test]
+    fn test_format() {
+        let date = Date::parse_iso("2026-01-01").unwrap();
+        assert_eq!(date.format("%B %e, %Y"), "January 1, 2026");
+        assert_eq!(date.format("%a %d %b %y"), "Thu 01 Jan 26");
+        assert_eq!(date.format("%A, day %j, 100%% %Q"), "Thursday, day 001, 100% %Q");
+    }
+
+    #[test]
+    fn test_civil_days_round_trip() {
+        for days in [-1, 0, 11_016, 20_454, 60_000] {
+            let date = civil_from_days(days);
+            assert_eq!(days_from_civil(&date), days, "{:?}", date);
+        }
+        assert_eq!(civil_from_days(0).to_iso(), "1970-01-01");
+    }
+}
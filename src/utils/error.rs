@@ -14,6 +14,15 @@ pub enum PandorustError {
     #[error("YAML front matter parse error: {0}")]
     YamlError(#[from] serde_yaml::Error),
 
+    #[error("{0}: input is not valid UTF-8 text; is this really a Markdown file?")]
+    InvalidUtf8(String),
+
+    #[error("{0}: unsupported font file; use .woff2, .woff, .ttf or .otf")]
+    UnsupportedFontFormat(String),
+
+    #[error("Include error: {0}")]
+    IncludeError(String),
+
     #[error("DOCX generation error: {0}")]
     DocxError(String),
 }
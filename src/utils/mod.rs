@@ -1 +1,4 @@
-pub mod error;
\ No newline at end of file
+pub mod base64;
+pub mod date;
+pub mod error;
+pub mod resources;
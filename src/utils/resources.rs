@@ -0,0 +1,28 @@
+use std::path::{Path, PathBuf};
+
+/// Find a file referenced by a document: relative to `base_dir` (the
+/// directory of the referencing file) first, then in each `resource_path`
+/// directory in order. Absolute paths are only checked as given.
+pub fn find_resource(name: &str, base_dir: &Path, resource_path: &[PathBuf]) -> Option<PathBuf> {
+    let name = Path::new(name);
+    if name.is_absolute() {
+        return name.is_file().then(|| name.to_path_buf());
+    }
+    std::iter::once(base_dir)
+        .chain(resource_path.iter().map(PathBuf::as_path))
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Whether an image or link target names a local file rather than a URL
+/// (`https://...`, `data:...`) or an in-document anchor.
+pub fn is_local_resource(url: &str) -> bool {
+    // A one-letter "scheme" is a Windows drive (C:\...)
+    let has_scheme = url.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.len() > 1
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    });
+    !url.is_empty() && !url.starts_with('#') && !has_scheme
+}
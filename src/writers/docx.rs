@@ -1,13 +1,20 @@
-use std::io::Cursor;
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Write};
 
 use docx_rs::{
-    AlignmentType, BreakType, Docx, LineSpacing, Paragraph, Run, RunFonts, Shading, ShdType,
-    Table, TableCell, TableCellBorder, TableCellBorderPosition, TableCellBorders,
-    TableCellMargins, TableRow, WidthType,
+    AlignmentType, BreakType, Docx, LineSpacing, Paragraph, ParagraphBorder,
+    ParagraphBorderPosition, ParagraphBorders, Run, RunFonts, Shading, ShdType,
+    SpecialIndentType, Table, TableCell, TableCellBorder, TableCellBorderPosition, TableCellBorders,
+    TableCellMargins, TableLayoutType, TableRow, VertAlignType, WidthType,
 };
 
-use crate::ast::{Block, Document, Inline};
+use zip::write::SimpleFileOptions;
+use zip::{DateTime, ZipArchive, ZipWriter};
+
+use crate::ast::inline::inlines_text;
+use crate::ast::{Alignment, Attr, Block, Document, Inline, MathType, Meta};
 use crate::utils::error::{PandorustError, Result};
+use crate::writers::options::WriterOptions;
 
 /// Parse fontsize metadata (e.g. "11pt") to half-points for DOCX.
 /// DOCX sizes are in half-points: 11pt = 22, 12pt = 24, etc.
@@ -21,49 +28,183 @@ fn parse_fontsize(meta_fontsize: Option<&str>) -> usize {
     24 // default: 12pt = 24 half-points
 }
 
-/// Write a Document AST to DOCX bytes.
+/// Rendering settings resolved once from the options and document metadata.
+struct DocxContext {
+    /// Body text size in half-points.
+    base_size: usize,
+    /// Font used for body text runs (`mainfont`, default Calibri).
+    body_font: RunFonts,
+    /// Font used for code and math runs (`monofont`, default Courier New).
+    mono_font: RunFonts,
+    /// Render a trailing `— Author` blockquote paragraph right-aligned in italics.
+    cite_attribution: bool,
+    /// Space before and after headings, in twips, indexed by level - 1.
+    heading_before: [u32; 6],
+    heading_after: [u32; 6],
+    /// Let Word size table columns to their content and the page width
+    /// instead of using fixed equal columns.
+    fit_tables: bool,
+    /// Document language from `lang` metadata, used for quotation marks.
+    lang: Option<String>,
+    /// Expand code block tabs to this many columns; `None` emits tab runs.
+    tab_stop: Option<usize>,
+    /// Line spacing multiple from `linestretch` (or `line-spacing`)
+    /// metadata; `None` keeps each block's built-in spacing.
+    line_stretch: Option<f64>,
+}
+
+impl DocxContext {
+    fn new(meta: &Meta, options: &WriterOptions) -> Self {
+        Self {
+            base_size: parse_fontsize(meta.get_str("fontsize")),
+            body_font: font_family(meta.get_str("mainfont").unwrap_or("Calibri")),
+            mono_font: font_family(meta.get_str("monofont").unwrap_or("Courier New")),
+            cite_attribution: options.cite_attribution,
+            heading_before: heading_spacing(meta, "before", |level| if level <= 2 { 400 } else { 280 }),
+            heading_after: heading_spacing(meta, "after", |_| 160),
+            fit_tables: options.fit_tables,
+            lang: meta.get_str("lang").map(str::to_string),
+            tab_stop: (!options.preserve_tabs).then_some(options.tab_stop),
+            line_stretch: meta
+                .get_str("linestretch")
+                .or_else(|| meta.get_str("line-spacing"))
+                .and_then(|s| s.trim().parse::<f64>().ok())
+                .filter(|stretch| *stretch > 0.0),
+        }
+    }
+
+    /// The `w:line` value for text spaced `default` (240ths of a line) when
+    /// no `linestretch` is set: 240 is single spacing, 360 one and a half.
+    fn line(&self, default: i32) -> i32 {
+        self.line_stretch.map_or(default, |stretch| (240.0 * stretch).round() as i32)
+    }
+}
+
+/// RunFonts using `name` for all scripts.
+fn font_family(name: &str) -> RunFonts {
+    RunFonts::new().ascii(name).hi_ansi(name).cs(name)
+}
+
+/// Resolve heading spacing for each level from `hN-space-<side>`, then
+/// `heading-space-<side>` (both in points), then the built-in default.
+fn heading_spacing(meta: &Meta, side: &str, default: impl Fn(u8) -> u32) -> [u32; 6] {
+    let general = meta
+        .get_str(&format!("heading-space-{side}"))
+        .and_then(parse_points_to_twips);
+    std::array::from_fn(|i| {
+        let level = i as u8 + 1;
+        meta.get_str(&format!("h{level}-space-{side}"))
+            .and_then(parse_points_to_twips)
+            .or(general)
+            .unwrap_or_else(|| default(level))
+    })
+}
+
+/// Parse a length in points (e.g. "24pt" or "6") to twips (1pt = 20 twips).
+fn parse_points_to_twips(value: &str) -> Option<u32> {
+    let number = value.trim().trim_end_matches("pt").trim();
+    let points: f64 = number.parse().ok()?;
+    (points >= 0.0).then(|| (points * 20.0).round() as u32)
+}
+
+/// Write a Document AST to DOCX bytes with default options.
 pub fn write_docx(doc: &Document) -> Result<Vec<u8>> {
+    write_docx_with(doc, &WriterOptions::default())
+}
+
+/// Write a Document AST to DOCX bytes.
+pub fn write_docx_with(doc: &Document, options: &WriterOptions) -> Result<Vec<u8>> {
     let mut docx = Docx::new();
-    let base_size = parse_fontsize(doc.meta.get_str("fontsize"));
-    let body_font = RunFonts::new()
-        .ascii("Calibri")
-        .hi_ansi("Calibri")
-        .cs("Calibri");
+    let ctx = DocxContext::new(&doc.meta, options);
+    docx = write_includes(docx, &options.include_before_body, &ctx);
 
     // --- Metadata block ---
-    if let Some(title) = doc.meta.title() {
-        let p = Paragraph::new()
-            .align(AlignmentType::Center)
-            .line_spacing(LineSpacing::new().after(60))
-            .add_run(Run::new().fonts(body_font.clone()).bold().size(48).add_text(title));
-        docx = docx.add_paragraph(p);
-    }
-    if let Some(subtitle) = doc.meta.subtitle() {
-        let p = Paragraph::new()
-            .align(AlignmentType::Center)
-            .line_spacing(LineSpacing::new().after(60))
-            .add_run(Run::new().fonts(body_font.clone()).size(32).add_text(subtitle));
-        docx = docx.add_paragraph(p);
+    // On a cover page it is set larger, a third of the way down the page,
+    // with the body starting on the next page
+    let cover_page = options.cover_page || doc.meta.get_bool("coverpage") == Some(true);
+    let has_metadata_block = doc.meta.title().is_some()
+        || doc.meta.subtitle().is_some()
+        || doc.meta.author().is_some()
+        || doc.meta.date().is_some();
+    if !options.strip_metadata_block {
+        if let Some(title) = doc.meta.title() {
+            let (before, size) = if cover_page { (3600, 72) } else { (0, 48) };
+            let p = Paragraph::new()
+                .align(AlignmentType::Center)
+                .line_spacing(LineSpacing::new().before(before).after(60))
+                .add_run(Run::new().fonts(ctx.body_font.clone()).bold().size(size).add_text(title));
+            docx = docx.add_paragraph(p);
+        }
+        if let Some(subtitle) = doc.meta.subtitle() {
+            let size = if cover_page { 40 } else { 32 };
+            let p = Paragraph::new()
+                .align(AlignmentType::Center)
+                .line_spacing(LineSpacing::new().after(60))
+                .add_run(Run::new().fonts(ctx.body_font.clone()).size(size).add_text(subtitle));
+            docx = docx.add_paragraph(p);
+        }
+        if let Some(author) = doc.meta.author() {
+            let p = Paragraph::new()
+                .align(AlignmentType::Center)
+                .line_spacing(LineSpacing::new().after(40))
+                .add_run(Run::new().fonts(ctx.body_font.clone()).size(ctx.base_size).add_text(format!("Author: {}", author)));
+            docx = docx.add_paragraph(p);
+        }
+        if let Some(date) = doc.meta.display_date() {
+            let p = Paragraph::new()
+                .align(AlignmentType::Center)
+                .line_spacing(LineSpacing::new().after(200))
+                .add_run(Run::new().fonts(ctx.body_font.clone()).size(ctx.base_size).add_text(date));
+            docx = docx.add_paragraph(p);
+        }
+        if cover_page && has_metadata_block {
+            docx = docx.add_paragraph(Paragraph::new().add_run(Run::new().add_break(BreakType::Page)));
+        }
     }
-    if let Some(author) = doc.meta.author() {
-        let p = Paragraph::new()
+
+    // --- Abstract ---
+    if let Some(blocks) = doc.meta.abstract_blocks() {
+        let heading = Paragraph::new()
             .align(AlignmentType::Center)
-            .line_spacing(LineSpacing::new().after(40))
-            .add_run(Run::new().fonts(body_font.clone()).size(base_size).add_text(format!("Author: {}", author)));
-        docx = docx.add_paragraph(p);
+            .line_spacing(LineSpacing::new().before(200).after(80))
+            .add_run(Run::new().fonts(ctx.body_font.clone()).bold().size(ctx.base_size).add_text("Abstract"));
+        docx = docx.add_paragraph(heading);
+        for block in &blocks {
+            docx = write_abstract_block(docx, block, &ctx);
+        }
     }
-    if let Some(date) = doc.meta.date() {
-        let p = Paragraph::new()
-            .align(AlignmentType::Center)
-            .line_spacing(LineSpacing::new().after(200))
-            .add_run(Run::new().fonts(body_font.clone()).size(base_size).add_text(date));
-        docx = docx.add_paragraph(p);
+
+    // --- Lists of tables and figures ---
+    for (_, title, entries) in super::caption_lists(doc) {
+        let heading = Paragraph::new()
+            .line_spacing(LineSpacing::new().before(200).after(80))
+            .add_run(Run::new().fonts(ctx.body_font.clone()).bold().size(ctx.base_size).add_text(title));
+        docx = docx.add_paragraph(heading);
+        for (n, entry) in entries.iter().enumerate() {
+            let mut p = Paragraph::new()
+                .line_spacing(LineSpacing::new().after(40))
+                .add_run(Run::new().fonts(ctx.body_font.clone()).size(ctx.base_size).add_text(format!("{}. ", n + 1)));
+            for run in build_runs(entry, Some(ctx.base_size), None, &ctx) {
+                p = p.add_run(run);
+            }
+            docx = docx.add_paragraph(p);
+        }
     }
 
     // --- Body blocks ---
     for block in &doc.blocks {
-        docx = write_block(docx, block, base_size, &body_font);
+        docx = write_block(docx, block, &ctx, 0);
+    }
+
+    if options.task_summary
+        && let Some(summary) = super::task_summary(doc)
+    {
+        let p = Paragraph::new()
+            .line_spacing(LineSpacing::new().before(240))
+            .add_run(Run::new().fonts(ctx.body_font.clone()).bold().size(ctx.base_size).add_text(summary));
+        docx = docx.add_paragraph(p);
     }
+    docx = write_includes(docx, &options.include_after_body, &ctx);
 
     // --- Pack to bytes ---
     let mut buf = Vec::new();
@@ -71,42 +212,144 @@ pub fn write_docx(doc: &Document) -> Result<Vec<u8>> {
         .pack(Cursor::new(&mut buf))
         .map_err(|e| PandorustError::DocxError(e.to_string()))?;
 
-    Ok(buf)
+    normalize_package(&buf)
+}
+
+/// Write `--include-before-body` / `--include-after-body` contents as plain
+/// paragraphs, one per non-blank line. The text is not interpreted.
+fn write_includes(docx: Docx, includes: &[String], ctx: &DocxContext) -> Docx {
+    let mut d = docx;
+    for line in includes.iter().flat_map(|include| include.lines()) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let run = Run::new().fonts(ctx.body_font.clone()).size(ctx.base_size).add_text(line);
+        d = d.add_paragraph(Paragraph::new().add_run(run));
+    }
+    d
+}
+
+/// Re-pack a DOCX so identical input always yields identical bytes.
+///
+/// Every part keeps its original order and compression but gets a fixed
+/// 1980-01-01 timestamp, and the paragraph ids docx-rs draws from a process-wide
+/// counter are renumbered from 1.
+fn normalize_package(bytes: &[u8]) -> Result<Vec<u8>> {
+    let zip_err = |e: zip::result::ZipError| PandorustError::DocxError(e.to_string());
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).map_err(zip_err)?;
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+
+    let mut para_ids = HashMap::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(zip_err)?;
+        let name = entry.name().to_string();
+        let options = SimpleFileOptions::default()
+            .compression_method(entry.compression())
+            .last_modified_time(DateTime::default());
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)?;
+        if name.ends_with(".xml")
+            && let Ok(xml) = std::str::from_utf8(&data)
+        {
+            data = renumber_para_ids(xml, &mut para_ids).into_bytes();
+        }
+        writer.start_file(name, options).map_err(zip_err)?;
+        writer.write_all(&data)?;
+    }
+
+    Ok(writer.finish().map_err(zip_err)?.into_inner())
 }
 
-fn write_block(docx: Docx, block: &Block, base_size: usize, body_font: &RunFonts) -> Docx {
+/// Replace every `paraId="XXXXXXXX"` value with a sequential id, mapping the
+/// same original id to the same new id across all parts.
+fn renumber_para_ids(xml: &str, ids: &mut HashMap<String, String>) -> String {
+    const ATTR: &str = "paraId=\"";
+    let mut out = String::with_capacity(xml.len());
+    let mut rest = xml;
+    while let Some(pos) = rest.find(ATTR) {
+        let value_start = pos + ATTR.len();
+        let Some(value_len) = rest[value_start..].find('"') else {
+            break;
+        };
+        let old = &rest[value_start..value_start + value_len];
+        let next = ids.len() + 1;
+        let new = ids
+            .entry(old.to_string())
+            .or_insert_with(|| format!("{:08X}", next));
+        out.push_str(&rest[..value_start]);
+        out.push_str(new);
+        rest = &rest[value_start + value_len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Indent per level of nesting (blockquotes and lists), in twips.
+const NEST_INDENT: i32 = 720;
+
+/// Write a block. `depth` is how many blockquotes and lists enclose it; their
+/// indentation grows with it.
+fn write_block(docx: Docx, block: &Block, ctx: &DocxContext, depth: usize) -> Docx {
     match block {
         Block::Para(inlines) | Block::Plain(inlines) => {
-            let p = build_paragraph(inlines, Some(base_size), None, body_font)
-                .line_spacing(LineSpacing::new().after(160).line(300));
+            // A paragraph holding only display math is set as an equation
+            if let Some(math) = display_math(inlines) {
+                let p = Paragraph::new()
+                    .align(AlignmentType::Center)
+                    .line_spacing(LineSpacing::new().before(120).after(160))
+                    .add_run(Run::new().fonts(ctx.mono_font.clone()).size(ctx.base_size).add_text(math));
+                return docx.add_paragraph(p);
+            }
+            let p = build_paragraph(inlines, Some(ctx.base_size), None, ctx)
+                .line_spacing(LineSpacing::new().after(160).line(ctx.line(300)));
             docx.add_paragraph(p)
         }
 
         Block::Heading(_, level, inlines) => {
-            let size = heading_size(*level, base_size);
-            let before = if *level <= 2 { 400 } else { 280 };
-            let p = build_paragraph(inlines, Some(size), Some(true), body_font)
-                .line_spacing(LineSpacing::new().before(before).after(160));
+            let size = heading_size(*level, ctx.base_size);
+            let idx = (*level).clamp(1, 6) as usize - 1;
+            let mut spacing = LineSpacing::new()
+                .before(ctx.heading_before[idx])
+                .after(ctx.heading_after[idx]);
+            // Headings keep Word's single spacing unless a stretch is set
+            if ctx.line_stretch.is_some() {
+                spacing = spacing.line(ctx.line(240));
+            }
+            let p = build_paragraph(inlines, Some(size), Some(true), ctx).line_spacing(spacing);
             docx.add_paragraph(p)
         }
 
-        Block::CodeBlock(_, code) => {
-            let courier = RunFonts::new()
-                .ascii("Courier New")
-                .hi_ansi("Courier New")
-                .cs("Courier New");
+        Block::CodeBlock(attr, code) => {
             // Render each line separately so newlines work
             let mut d = docx;
+            // A listing caption goes above the code in italics
+            if let Some(caption) = super::code_caption(attr) {
+                let p = Paragraph::new()
+                    .line_spacing(LineSpacing::new().before(120).after(60))
+                    .add_run(Run::new().fonts(ctx.body_font.clone()).size(ctx.base_size).italic().add_text(caption));
+                d = d.add_paragraph(p);
+            }
+            let code = match ctx.tab_stop {
+                Some(tab_stop) => super::expand_tabs(code, tab_stop),
+                None => code.clone(),
+            };
             for line in code.lines() {
-                let run = Run::new()
-                    .fonts(courier.clone())
-                    .add_text(line);
+                // Tabs left in the text become real tab characters
+                let mut run = Run::new().fonts(ctx.mono_font.clone());
+                for (i, piece) in line.split('\t').enumerate() {
+                    if i > 0 {
+                        run = run.add_tab();
+                    }
+                    if !piece.is_empty() {
+                        run = run.add_text(piece);
+                    }
+                }
                 let p = Paragraph::new().add_run(run);
                 d = d.add_paragraph(p);
             }
             // If code was empty, still add one paragraph
             if code.is_empty() {
-                let run = Run::new().fonts(courier).add_text("");
+                let run = Run::new().fonts(ctx.mono_font.clone()).add_text("");
                 d = d.add_paragraph(Paragraph::new().add_run(run));
             }
             d
@@ -114,8 +357,26 @@ fn write_block(docx: Docx, block: &Block, base_size: usize, body_font: &RunFonts
 
         Block::BlockQuote(inner_blocks) => {
             let mut d = docx;
-            for inner in inner_blocks {
-                d = write_block_quote_block(d, inner, base_size, body_font);
+            let attribution = match inner_blocks.split_last() {
+                Some((last, rest)) if ctx.cite_attribution && !rest.is_empty() => {
+                    super::attribution_line(last).map(|inlines| (rest, inlines))
+                }
+                _ => None,
+            };
+            let quoted = attribution.as_ref().map_or(&inner_blocks[..], |(rest, _)| rest);
+            for inner in quoted {
+                d = write_block_quote_block(d, inner, ctx, depth + 1);
+            }
+            if let Some((_, inlines)) = attribution {
+                let mut p = Paragraph::new()
+                    .align(AlignmentType::Right)
+                    .indent(Some(nest_indent(depth + 1)), None, None, None)
+                    .line_spacing(LineSpacing::new().after(120))
+                    .add_run(Run::new().fonts(ctx.body_font.clone()).size(ctx.base_size).italic().add_text("\u{2014} "));
+                for run in build_runs(&inlines, Some(ctx.base_size), None, ctx) {
+                    p = p.add_run(run.italic());
+                }
+                d = d.add_paragraph(p);
             }
             d
         }
@@ -123,12 +384,11 @@ fn write_block(docx: Docx, block: &Block, base_size: usize, body_font: &RunFonts
         Block::BulletList(items) => {
             let mut d = docx;
             for item_blocks in items {
-                let text = extract_inline_text_from_blocks(item_blocks);
-                let p = Paragraph::new()
-                    .indent(Some(720), None, None, None)
-                    .line_spacing(LineSpacing::new().after(80).line(300))
-                    .add_run(Run::new().fonts(body_font.clone()).size(base_size).add_text(format!("\u{2022} {}", text)));
-                d = d.add_paragraph(p);
+                let (text, nested) = split_list_item(item_blocks);
+                d = d.add_paragraph(list_item_paragraph("\u{2022}".to_string(), text, ctx, depth));
+                for list in nested {
+                    d = write_block(d, list, ctx, depth + 1);
+                }
             }
             d
         }
@@ -138,120 +398,40 @@ fn write_block(docx: Docx, block: &Block, base_size: usize, body_font: &RunFonts
             let start = attrs.start;
             for (i, item_blocks) in items.iter().enumerate() {
                 let num = start as usize + i;
-                let text = extract_inline_text_from_blocks(item_blocks);
-                let p = Paragraph::new()
-                    .indent(Some(720), None, None, None)
-                    .line_spacing(LineSpacing::new().after(80).line(300))
-                    .add_run(Run::new().fonts(body_font.clone()).size(base_size).add_text(format!("{}. {}", num, text)));
-                d = d.add_paragraph(p);
+                let (text, nested) = split_list_item(item_blocks);
+                d = d.add_paragraph(list_item_paragraph(format!("{}.", num), text, ctx, depth));
+                for list in nested {
+                    d = write_block(d, list, ctx, depth + 1);
+                }
             }
             d
         }
 
         Block::Table(table) => {
-            let num_cols = table.col_specs.len().max(1);
-            let col_width = 9000 / num_cols;
-            let grid: Vec<usize> = (0..num_cols).map(|_| col_width).collect();
-
-            let mut rows: Vec<TableRow> = Vec::new();
-
-            // Header rows
-            for (row_idx, row) in table.head.rows.iter().enumerate() {
-                let cells: Vec<TableCell> = row
-                    .cells
-                    .iter()
-                    .map(|cell| {
-                        let text = extract_inline_text_from_blocks(&cell.content);
-                        let run = Run::new()
-                            .fonts(body_font.clone())
-                            .size(base_size)
-                            .bold()
-                            .color("FFFFFF")
-                            .add_text(text);
-                        let p = Paragraph::new().add_run(run);
-                        let shading = Shading::new()
-                            .shd_type(ShdType::Clear)
-                            .color("auto")
-                            .fill("1F4E79");
-                        let borders = make_cell_borders("333333", 6);
-                        TableCell::new()
-                            .width(col_width, WidthType::Dxa)
-                            .shading(shading)
-                            .set_borders(borders)
-                            .add_paragraph(p)
-                    })
-                    .collect();
-                let _ = row_idx;
-                rows.push(TableRow::new(cells));
-            }
-
-            // Body rows
-            for (body_idx, body) in table.bodies.iter().enumerate() {
-                let all_rows = body.head.iter().chain(body.body.iter());
-                for (row_idx, row) in all_rows.enumerate() {
-                    let fill = if row_idx % 2 == 0 { "FFFFFF" } else { "EDF2F7" };
-                    let _ = body_idx;
-                    let cells: Vec<TableCell> = row
-                        .cells
-                        .iter()
-                        .map(|cell| {
-                            let text = extract_inline_text_from_blocks(&cell.content);
-                            let run = Run::new().fonts(body_font.clone()).size(base_size).add_text(text);
-                            let p = Paragraph::new().add_run(run);
-                            let shading = Shading::new()
-                                .shd_type(ShdType::Clear)
-                                .color("auto")
-                                .fill(fill);
-                            let borders = make_cell_borders("333333", 6);
-                            TableCell::new()
-                                .width(col_width, WidthType::Dxa)
-                                .shading(shading)
-                                .set_borders(borders)
-                                .add_paragraph(p)
-                        })
-                        .collect();
-                    rows.push(TableRow::new(cells));
+            // The full caption goes above the table in italics
+            let mut d = docx;
+            let caption = super::caption_inlines(&table.caption);
+            if !caption.is_empty() {
+                let mut p = Paragraph::new().line_spacing(LineSpacing::new().before(120).after(60));
+                for run in build_runs(&caption, Some(ctx.base_size), None, ctx) {
+                    p = p.add_run(run.italic());
                 }
+                d = d.add_paragraph(p);
             }
-
-            // Footer rows
-            for row in &table.foot.rows {
-                let cells: Vec<TableCell> = row
-                    .cells
-                    .iter()
-                    .map(|cell| {
-                        let text = extract_inline_text_from_blocks(&cell.content);
-                        let run = Run::new().fonts(body_font.clone()).size(base_size).add_text(text);
-                        let p = Paragraph::new().add_run(run);
-                        let borders = make_cell_borders("333333", 6);
-                        TableCell::new()
-                            .width(col_width, WidthType::Dxa)
-                            .set_borders(borders)
-                            .add_paragraph(p)
-                    })
-                    .collect();
-                rows.push(TableRow::new(cells));
-            }
-
-            if rows.is_empty() {
-                rows.push(TableRow::new(vec![TableCell::new()]));
-            }
-
-            // Cell padding: 80 DXA top/bottom (~4pt), 120 DXA left/right (~6pt)
-            let cell_margins = TableCellMargins::new().margin(80, 120, 80, 120);
-            let tbl = Table::new(rows)
-                .width(9000, WidthType::Dxa)
-                .set_grid(grid)
-                .margins(cell_margins);
-
             // Add spacing after table
-            docx.add_table(tbl)
+            d.add_table(build_table(table, 9000, ctx))
                 .add_paragraph(Paragraph::new().line_spacing(LineSpacing::new().before(0).after(120)))
         }
 
         Block::HorizontalRule => {
+            // An empty paragraph with a bottom border, like the HTML <hr> style
+            let border = ParagraphBorder::new(ParagraphBorderPosition::Bottom)
+                .size(12)
+                .space(1)
+                .color("CCCCCC");
             let p = Paragraph::new()
-                .add_run(Run::new().fonts(body_font.clone()).size(base_size).add_text("—".repeat(40)));
+                .set_borders(ParagraphBorders::with_empty().set(border))
+                .line_spacing(LineSpacing::new().before(240).after(240));
             docx.add_paragraph(p)
         }
 
@@ -262,30 +442,51 @@ fn write_block(docx: Docx, block: &Block, base_size: usize, body_font: &RunFonts
         }
 
         Block::LineBlock(lines) => {
+            // Lines sit tight together, each indented by its leading spaces
             let mut d = docx;
-            for line_inlines in lines {
-                let p = build_paragraph(line_inlines, Some(base_size), None, body_font);
+            let last = lines.len().saturating_sub(1);
+            for (i, line_inlines) in lines.iter().enumerate() {
+                let (spaces, line) = leading_spaces(line_inlines);
+                let indent = NEST_INDENT * depth as i32 + spaces as i32 * space_width(ctx.base_size);
+                let after = if i == last { 160 } else { 0 };
+                let p = build_paragraph(&line, Some(ctx.base_size), None, ctx)
+                    .indent(Some(indent), None, None, None)
+                    .line_spacing(LineSpacing::new().before(0).after(after));
                 d = d.add_paragraph(p);
             }
             d
         }
 
+        // Raw content is for other formats: docx-rs cannot take raw OpenXML
         Block::RawBlock(_, _) => docx,
+        Block::Div(attr, _) if super::excluded_from(attr, "docx") => docx,
+        Block::Div(attr, blocks) if super::admonition(attr).is_some() => {
+            write_admonition(docx, attr, blocks, ctx, depth)
+        }
         Block::Figure(_, _, blocks) | Block::Div(_, blocks) => {
             let mut d = docx;
             for b in blocks {
-                d = write_block(d, b, base_size, body_font);
+                d = write_block(d, b, ctx, depth);
             }
             d
         }
         Block::DefinitionList(items) => {
             let mut d = docx;
-            for (term_inlines, definitions) in items {
-                let p = build_paragraph(term_inlines, Some(base_size), Some(true), body_font);
+            for (i, (term_inlines, definitions)) in items.iter().enumerate() {
+                // Blank paragraph separates definition groups
+                if i > 0 {
+                    d = d.add_paragraph(
+                        Paragraph::new().line_spacing(LineSpacing::new().before(0).after(0)),
+                    );
+                }
+                // Term: bold, slightly larger, kept with its first definition
+                let p = build_paragraph(term_inlines, Some(ctx.base_size + 2), Some(true), ctx)
+                    .keep_next(true)
+                    .line_spacing(LineSpacing::new().before(120).after(60));
                 d = d.add_paragraph(p);
                 for def_blocks in definitions {
                     for b in def_blocks {
-                        d = write_block_quote_block(d, b, base_size, body_font);
+                        d = write_definition_block(d, b, ctx);
                     }
                 }
             }
@@ -294,25 +495,325 @@ fn write_block(docx: Docx, block: &Block, base_size: usize, body_font: &RunFonts
     }
 }
 
-/// Write a block inside a block quote (indented).
-fn write_block_quote_block(docx: Docx, block: &Block, base_size: usize, body_font: &RunFonts) -> Docx {
+/// Build a DOCX table `width` twips wide with equal columns.
+fn build_table(table: &crate::ast::Table, width: usize, ctx: &DocxContext) -> Table {
+    // Size the grid for the widest row in case the column specs are short
+    let widest = table
+        .head
+        .rows
+        .iter()
+        .chain(table.bodies.iter().flat_map(|b| b.head.iter().chain(&b.body)))
+        .chain(&table.foot.rows)
+        .map(|r| r.cells.iter().map(|c| c.col_span.max(1) as usize).sum::<usize>())
+        .max()
+        .unwrap_or(0);
+    let num_cols = table.col_specs.len().max(widest).max(1);
+    let col_width = width / num_cols;
+    let grid: Vec<usize> = (0..num_cols).map(|_| col_width).collect();
+    let (cell_width, cell_width_type) = if ctx.fit_tables {
+        (0, WidthType::Auto)
+    } else {
+        (col_width, WidthType::Dxa)
+    };
+
+    let mut rows: Vec<TableRow> = Vec::new();
+
+    // Header rows
+    for (row_idx, row) in table.head.rows.iter().enumerate() {
+        let mut col = 0;
+        let cells: Vec<TableCell> = row
+            .cells
+            .iter()
+            .map(|cell| {
+                // A cell's own alignment wins over its column's
+                let align = match cell.align {
+                    Alignment::AlignDefault => table.col_specs.get(col).map(|spec| &spec.align),
+                    ref own => Some(own),
+                };
+                col += cell.col_span.max(1) as usize;
+                let shading = Shading::new()
+                    .shd_type(ShdType::Clear)
+                    .color("auto")
+                    .fill("1F4E79");
+                let borders = make_cell_borders("333333", 6);
+                let tc = TableCell::new()
+                    .width(cell_width, cell_width_type)
+                    .shading(shading)
+                    .set_borders(borders);
+                add_cell_content(tc, &cell.content, true, align, col_width, ctx)
+            })
+            .collect();
+        let _ = row_idx;
+        rows.push(TableRow::new(cells));
+    }
+
+    // Body rows
+    for (body_idx, body) in table.bodies.iter().enumerate() {
+        let all_rows = body.head.iter().chain(body.body.iter());
+        let row_head_columns = body.row_head_columns as usize;
+        for (row_idx, row) in all_rows.enumerate() {
+            let fill = if row_idx % 2 == 0 { "FFFFFF" } else { "EDF2F7" };
+            let _ = body_idx;
+            let mut col = 0;
+            let cells: Vec<TableCell> = row
+                .cells
+                .iter()
+                .map(|cell| {
+                    // Row-header cells (the leading label columns) get
+                    // the same styling as the header row.
+                    let is_row_head = col < row_head_columns;
+                    col += cell.col_span.max(1) as usize;
+                    let shading = Shading::new()
+                        .shd_type(ShdType::Clear)
+                        .color("auto")
+                        .fill(if is_row_head { "1F4E79" } else { fill });
+                    let borders = make_cell_borders("333333", 6);
+                    let tc = TableCell::new()
+                        .width(cell_width, cell_width_type)
+                        .shading(shading)
+                        .set_borders(borders);
+                    add_cell_content(tc, &cell.content, is_row_head, None, col_width, ctx)
+                })
+                .collect();
+            rows.push(TableRow::new(cells));
+        }
+    }
+
+    // Footer rows
+    for row in &table.foot.rows {
+        let cells: Vec<TableCell> = row
+            .cells
+            .iter()
+            .map(|cell| {
+                let borders = make_cell_borders("333333", 6);
+                let tc = TableCell::new()
+                    .width(cell_width, cell_width_type)
+                    .set_borders(borders);
+                add_cell_content(tc, &cell.content, false, None, col_width, ctx)
+            })
+            .collect();
+        rows.push(TableRow::new(cells));
+    }
+
+    if rows.is_empty() {
+        rows.push(TableRow::new(vec![TableCell::new()]));
+    }
+
+    // Cell padding: 80 DXA top/bottom (~4pt), 120 DXA left/right (~6pt)
+    let cell_margins = TableCellMargins::new().margin(80, 120, 80, 120);
+    let tbl = Table::new(rows)
+        .set_grid(grid)
+        .margins(cell_margins);
+    // Pct widths are in fiftieths of a percent: 5000 = 100%
+    if ctx.fit_tables {
+        tbl.width(5000, WidthType::Pct).layout(TableLayoutType::Autofit)
+    } else {
+        tbl.width(width, WidthType::Dxa)
+    }
+}
+
+/// Add table cell content as paragraphs, one per block, so multi-paragraph
+/// cells keep their breaks. A table becomes a nested table `width` twips
+/// wide at most. Header cells are set in bold white text.
+fn add_cell_content(
+    cell: TableCell,
+    blocks: &[Block],
+    header: bool,
+    align: Option<&Alignment>,
+    width: usize,
+    ctx: &DocxContext,
+) -> TableCell {
+    let style = |run: Run| if header { run.bold().color("FFFFFF") } else { run };
+    let jc = match align {
+        Some(Alignment::AlignLeft) => Some(AlignmentType::Left),
+        Some(Alignment::AlignRight) => Some(AlignmentType::Right),
+        Some(Alignment::AlignCenter) => Some(AlignmentType::Center),
+        Some(Alignment::AlignDefault) | None => None,
+    };
+    let mut tc = cell;
+    for block in blocks {
+        let runs = match block {
+            Block::Table(inner) => {
+                // Fit the column, less the cell's left and right padding
+                tc = tc.add_table(build_table(inner, width.saturating_sub(240), ctx));
+                continue;
+            }
+            Block::Para(inlines) | Block::Plain(inlines) => {
+                build_runs(inlines, Some(ctx.base_size), None, ctx)
+            }
+            other => {
+                let text = extract_inline_text_from_blocks(std::slice::from_ref(other));
+                vec![Run::new().fonts(ctx.body_font.clone()).size(ctx.base_size).add_text(text)]
+            }
+        };
+        let mut p = Paragraph::new();
+        if let Some(jc) = jc {
+            p = p.align(jc);
+        }
+        for run in runs {
+            p = p.add_run(style(run));
+        }
+        tc = tc.add_paragraph(p);
+    }
+    // Word needs a paragraph to end a cell, including after a nested table
+    if blocks.is_empty() || matches!(blocks.last(), Some(Block::Table(_))) {
+        tc = tc.add_paragraph(Paragraph::new());
+    }
+    tc
+}
+
+/// The TeX of a paragraph whose only content is display math.
+fn display_math(inlines: &[Inline]) -> Option<&str> {
+    let mut content = inlines.iter().filter(|i| match i {
+        Inline::Str(s) => !s.trim().is_empty(),
+        Inline::Space | Inline::SoftBreak | Inline::LineBreak => false,
+        _ => true,
+    });
+    match (content.next(), content.next()) {
+        (Some(Inline::Math(MathType::DisplayMath, tex)), None) => Some(tex.trim()),
+        _ => None,
+    }
+}
+
+/// Write an admonition div (`::: warning`) as a callout: a bold title and
+/// indented paragraphs, all marked by a thick left border in the kind's color.
+fn write_admonition(docx: Docx, attr: &Attr, blocks: &[Block], ctx: &DocxContext, depth: usize) -> Docx {
+    let Some((kind, title)) = super::admonition(attr) else {
+        return docx;
+    };
+    let color = match kind {
+        "warning" | "caution" => "C27C0E",
+        "danger" | "error" => "B42318",
+        _ => "1F4E79",
+    };
+    let callout = |p: Paragraph| {
+        let border = ParagraphBorder::new(ParagraphBorderPosition::Left)
+            .size(24)
+            .space(8)
+            .color(color);
+        p.set_borders(ParagraphBorders::with_empty().set(border))
+            .indent(Some(nest_indent(depth + 1)), None, None, None)
+    };
+
+    let heading = Paragraph::new()
+        .line_spacing(LineSpacing::new().before(120).after(60))
+        .add_run(Run::new().fonts(ctx.body_font.clone()).bold().color(color).size(ctx.base_size).add_text(title));
+    let mut d = docx.add_paragraph(callout(heading));
+    for block in blocks {
+        d = match block {
+            Block::Para(inlines) | Block::Plain(inlines) => {
+                let p = build_paragraph(inlines, Some(ctx.base_size), None, ctx)
+                    .line_spacing(LineSpacing::new().after(80).line(ctx.line(276)));
+                d.add_paragraph(callout(p))
+            }
+            other => write_block(d, other, ctx, depth + 1),
+        };
+    }
+    d
+}
+
+/// Write a block inside a block quote, indented for its nesting `depth`.
+fn write_block_quote_block(docx: Docx, block: &Block, ctx: &DocxContext, depth: usize) -> Docx {
     match block {
         Block::Para(inlines) | Block::Plain(inlines) => {
-            let p = build_paragraph(inlines, Some(base_size), None, body_font)
-                .indent(Some(720), None, None, None)
-                .line_spacing(LineSpacing::new().after(80).line(276));
+            let p = build_paragraph(inlines, Some(ctx.base_size), None, ctx)
+                .indent(Some(nest_indent(depth)), None, None, None)
+                .line_spacing(LineSpacing::new().after(80).line(ctx.line(276)));
             docx.add_paragraph(p)
         }
-        other => write_block(docx, other, base_size, body_font),
+        other => write_block(docx, other, ctx, depth),
+    }
+}
+
+fn nest_indent(depth: usize) -> i32 {
+    NEST_INDENT * depth.max(1) as i32
+}
+
+/// Approximate width of one character at `size` (half-points), in twips:
+/// half an em.
+fn space_width(size: usize) -> i32 {
+    size as i32 * 5
+}
+
+/// Count a line's leading spaces (plain or non-breaking, as pandoc stores
+/// them in line blocks) and return the line without them.
+fn leading_spaces(inlines: &[Inline]) -> (usize, Vec<Inline>) {
+    let mut count = 0;
+    let mut rest = inlines.iter().peekable();
+    while rest.next_if(|i| matches!(i, Inline::Space)).is_some() {
+        count += 1;
+    }
+    let mut line: Vec<Inline> = Vec::with_capacity(inlines.len());
+    if let Some(Inline::Str(s)) = rest.peek() {
+        let text = s.trim_start_matches([' ', '\u{a0}']);
+        count += s[..s.len() - text.len()].chars().count();
+        if !text.is_empty() {
+            line.push(Inline::Str(text.to_string()));
+        }
+        rest.next();
+    }
+    line.extend(rest.cloned());
+    (count, line)
+}
+
+/// A list item paragraph at `depth`. The marker hangs in the indent, two ems
+/// wide at the body size, so wrapped lines line up with the item text.
+fn list_item_paragraph(marker: String, text: String, ctx: &DocxContext, depth: usize) -> Paragraph {
+    let hanging = ctx.base_size as i32 * 20;
+    Paragraph::new()
+        .indent(Some(nest_indent(depth + 1)), Some(SpecialIndentType::Hanging(hanging)), None, None)
+        .line_spacing(LineSpacing::new().after(80).line(ctx.line(300)))
+        .add_run(Run::new().fonts(ctx.body_font.clone()).size(ctx.base_size).add_text(marker).add_tab())
+        .add_run(Run::new().fonts(ctx.body_font.clone()).size(ctx.base_size).add_text(text))
+}
+
+/// Split a list item into the text of its own paragraphs and its nested
+/// lists, which are written separately one level deeper.
+fn split_list_item(blocks: &[Block]) -> (String, Vec<&Block>) {
+    let (nested, own): (Vec<&Block>, Vec<&Block>) = blocks
+        .iter()
+        .partition(|b| matches!(b, Block::BulletList(_) | Block::OrderedList(..)));
+    let own: Vec<Block> = own.into_iter().cloned().collect();
+    (extract_inline_text_from_blocks(&own), nested)
+}
+
+/// Write a block of a definition: paragraphs are indented under the term with
+/// a small hanging indent so each definition starts visibly apart.
+fn write_definition_block(docx: Docx, block: &Block, ctx: &DocxContext) -> Docx {
+    match block {
+        Block::Para(inlines) | Block::Plain(inlines) => {
+            let p = build_paragraph(inlines, Some(ctx.base_size), None, ctx)
+                .indent(Some(720), Some(SpecialIndentType::Hanging(360)), None, None)
+                .line_spacing(LineSpacing::new().after(80).line(ctx.line(276)));
+            docx.add_paragraph(p)
+        }
+        other => write_block(docx, other, ctx, 0),
+    }
+}
+
+/// Write a block of the abstract: paragraphs are indented on both sides and
+/// set in italics.
+fn write_abstract_block(docx: Docx, block: &Block, ctx: &DocxContext) -> Docx {
+    match block {
+        Block::Para(inlines) | Block::Plain(inlines) => {
+            let mut p = Paragraph::new()
+                .indent(Some(720), None, Some(720), None)
+                .line_spacing(LineSpacing::new().after(120).line(ctx.line(276)));
+            for run in build_runs(inlines, Some(ctx.base_size), None, ctx) {
+                p = p.add_run(run.italic());
+            }
+            docx.add_paragraph(p)
+        }
+        other => write_block(docx, other, ctx, 0),
     }
 }
 
 /// Build a paragraph from a slice of Inline elements.
 /// `size` is in half-points (e.g. 24 = 12pt).
 /// `bold` overrides all runs to bold.
-fn build_paragraph(inlines: &[Inline], size: Option<usize>, bold_override: Option<bool>, body_font: &RunFonts) -> Paragraph {
+fn build_paragraph(inlines: &[Inline], size: Option<usize>, bold_override: Option<bool>, ctx: &DocxContext) -> Paragraph {
     let mut p = Paragraph::new();
-    let runs = build_runs(inlines, size, bold_override, body_font);
+    let runs = build_runs(inlines, size, bold_override, ctx);
     for run in runs {
         p = p.add_run(run);
     }
@@ -320,138 +821,151 @@ fn build_paragraph(inlines: &[Inline], size: Option<usize>, bold_override: Optio
 }
 
 /// Recursively convert Inline elements to docx-rs Runs.
-fn build_runs(inlines: &[Inline], size: Option<usize>, bold_override: Option<bool>, body_font: &RunFonts) -> Vec<Run> {
+fn build_runs(inlines: &[Inline], size: Option<usize>, bold_override: Option<bool>, ctx: &DocxContext) -> Vec<Run> {
     let mut runs: Vec<Run> = Vec::new();
 
     for inline in inlines {
         match inline {
             Inline::Str(s) => {
-                let mut run = Run::new().fonts(body_font.clone()).add_text(s.clone());
+                let mut run = Run::new().fonts(ctx.body_font.clone()).add_text(s.clone());
                 if let Some(sz) = size { run = run.size(sz); }
                 if bold_override == Some(true) { run = run.bold(); }
                 runs.push(run);
             }
 
             Inline::Space | Inline::SoftBreak => {
-                let mut run = Run::new().fonts(body_font.clone()).add_text(" ");
+                let mut run = Run::new().fonts(ctx.body_font.clone()).add_text(" ");
                 if let Some(sz) = size { run = run.size(sz); }
                 if bold_override == Some(true) { run = run.bold(); }
                 runs.push(run);
             }
 
             Inline::LineBreak => {
-                let mut run = Run::new().fonts(body_font.clone()).add_break(BreakType::TextWrapping);
+                let mut run = Run::new().fonts(ctx.body_font.clone()).add_break(BreakType::TextWrapping);
                 if let Some(sz) = size { run = run.size(sz); }
                 runs.push(run);
             }
 
             Inline::Strong(inner) => {
-                for mut r in build_runs(inner, size, Some(true), body_font) {
+                for mut r in build_runs(inner, size, Some(true), ctx) {
                     r = r.bold();
                     runs.push(r);
                 }
             }
 
             Inline::Emph(inner) => {
-                for mut r in build_runs(inner, size, bold_override, body_font) {
+                for mut r in build_runs(inner, size, bold_override, ctx) {
                     r = r.italic();
                     runs.push(r);
                 }
             }
 
             Inline::Strikeout(inner) => {
-                for mut r in build_runs(inner, size, bold_override, body_font) {
+                for mut r in build_runs(inner, size, bold_override, ctx) {
                     r = r.strike();
                     runs.push(r);
                 }
             }
 
             Inline::Underline(inner) => {
-                for mut r in build_runs(inner, size, bold_override, body_font) {
+                for mut r in build_runs(inner, size, bold_override, ctx) {
                     r = r.underline("single");
                     runs.push(r);
                 }
             }
 
             Inline::Code(_, code_str) => {
-                let courier = RunFonts::new()
-                    .ascii("Courier New")
-                    .hi_ansi("Courier New")
-                    .cs("Courier New");
-                let mut run = Run::new().fonts(courier).add_text(code_str.clone());
+                let mut run = Run::new().fonts(ctx.mono_font.clone()).add_text(code_str.clone());
                 if let Some(sz) = size { run = run.size(sz); }
                 runs.push(run);
             }
 
-            Inline::Link(_, content_inlines, target) => {
-                let link_text = if content_inlines.is_empty() {
-                    target.url.clone()
-                } else {
-                    inline_text_content(content_inlines)
-                };
-                let mut run = Run::new().fonts(body_font.clone())
+            Inline::Link(..) => {
+                let link_text = inlines_text(std::slice::from_ref(inline));
+                let mut run = Run::new().fonts(ctx.body_font.clone())
                     .color("0000FF").underline("single").add_text(link_text);
                 if let Some(sz) = size { run = run.size(sz); }
                 if bold_override == Some(true) { run = run.bold(); }
                 runs.push(run);
             }
 
-            Inline::Image(_, alt_inlines, target) => {
-                let alt = if alt_inlines.is_empty() {
-                    target.url.clone()
+            Inline::Image(_, alt_inlines, _) => {
+                // Alt text defaults to empty, never the URL (same as HTML)
+                let alt = inlines_text(alt_inlines);
+                let label = if alt.trim().is_empty() {
+                    "[Image]".to_string()
                 } else {
-                    inline_text_content(alt_inlines)
+                    format!("[Image: {}]", alt)
                 };
-                let mut run = Run::new().fonts(body_font.clone()).italic().add_text(format!("[Image: {}]", alt));
+                let mut run = Run::new().fonts(ctx.body_font.clone()).italic().add_text(label);
                 if let Some(sz) = size { run = run.size(sz); }
                 runs.push(run);
             }
 
             Inline::Superscript(inner) => {
-                runs.extend(build_runs(inner, size, bold_override, body_font));
+                for r in build_runs(inner, size, bold_override, ctx) {
+                    runs.push(r.vert_align(VertAlignType::SuperScript));
+                }
             }
 
             Inline::Subscript(inner) => {
-                runs.extend(build_runs(inner, size, bold_override, body_font));
+                for r in build_runs(inner, size, bold_override, ctx) {
+                    runs.push(r.vert_align(VertAlignType::SubScript));
+                }
             }
 
             Inline::SmallCaps(inner) => {
-                runs.extend(build_runs(inner, size, bold_override, body_font));
+                runs.extend(build_runs(inner, size, bold_override, ctx));
             }
 
-            Inline::Quoted(_, inner) => {
-                let mut open = Run::new().fonts(body_font.clone()).add_text("\u{201C}");
+            Inline::Quoted(kind, inner) => {
+                let (open_mark, close_mark) = super::quote_marks(ctx.lang.as_deref(), kind);
+                let mut open = Run::new().fonts(ctx.body_font.clone()).add_text(open_mark);
                 if let Some(sz) = size { open = open.size(sz); }
                 runs.push(open);
-                runs.extend(build_runs(inner, size, bold_override, body_font));
-                let mut close = Run::new().fonts(body_font.clone()).add_text("\u{201D}");
+                runs.extend(build_runs(inner, size, bold_override, ctx));
+                let mut close = Run::new().fonts(ctx.body_font.clone()).add_text(close_mark);
                 if let Some(sz) = size { close = close.size(sz); }
                 runs.push(close);
             }
 
             Inline::Math(_, math_str) => {
-                let courier = RunFonts::new().ascii("Courier New").hi_ansi("Courier New");
-                let mut run = Run::new().fonts(courier).add_text(math_str.clone());
+                let mut run = Run::new().fonts(ctx.mono_font.clone()).add_text(math_str.clone());
                 if let Some(sz) = size { run = run.size(sz); }
                 runs.push(run);
             }
 
-            Inline::Span(_, inner) => {
-                runs.extend(build_runs(inner, size, bold_override, body_font));
+            Inline::Span(attr, inner) => {
+                let (highlight, color) = span_run_style(attr);
+                let has_class = |name: &str| attr.classes.iter().any(|c| c == name);
+                for mut r in build_runs(inner, size, bold_override, ctx) {
+                    if let Some(h) = highlight {
+                        r = r.highlight(h);
+                    }
+                    if let Some(c) = &color {
+                        r = r.color(c.clone());
+                    }
+                    // CriticMarkup review marks
+                    if has_class("insertion") {
+                        r = r.underline("single").color("008000");
+                    } else if has_class("deletion") {
+                        r = r.strike().color("FF0000");
+                    } else if has_class("comment") {
+                        r = r.italic().color("808080");
+                    }
+                    runs.push(r);
+                }
             }
 
-            Inline::Note(blocks) => {
+            Inline::Note(_, blocks) => {
                 let text = extract_inline_text_from_blocks(blocks);
-                let mut run = Run::new().fonts(body_font.clone()).add_text(format!(" ({})", text));
+                let mut run = Run::new().fonts(ctx.body_font.clone()).add_text(format!(" ({})", text));
                 if let Some(sz) = size { run = run.size(sz); }
                 runs.push(run);
             }
 
-            Inline::RawInline(_, raw) => {
-                let mut run = Run::new().fonts(body_font.clone()).add_text(raw.clone());
-                if let Some(sz) = size { run = run.size(sz); }
-                runs.push(run);
-            }
+            // Dropped like raw blocks
+            Inline::RawInline(..) => {}
         }
     }
 
@@ -463,8 +977,8 @@ fn extract_inline_text_from_blocks(blocks: &[Block]) -> String {
     blocks
         .iter()
         .map(|b| match b {
-            Block::Para(inlines) | Block::Plain(inlines) => inline_text_content(inlines),
-            Block::Heading(_, _, inlines) => inline_text_content(inlines),
+            Block::Para(inlines) | Block::Plain(inlines) => inlines_text(inlines),
+            Block::Heading(_, _, inlines) => inlines_text(inlines),
             Block::CodeBlock(_, code) => code.clone(),
             Block::BulletList(items) => items
                 .iter()
@@ -486,41 +1000,6 @@ fn extract_inline_text_from_blocks(blocks: &[Block]) -> String {
         .to_string()
 }
 
-/// Extract plain text from a list of inlines (recursive, no formatting).
-fn inline_text_content(inlines: &[Inline]) -> String {
-    inlines
-        .iter()
-        .map(|i| match i {
-            Inline::Str(s) => s.clone(),
-            Inline::Space | Inline::SoftBreak => " ".to_string(),
-            Inline::LineBreak => "\n".to_string(),
-            Inline::Strong(inner)
-            | Inline::Emph(inner)
-            | Inline::Underline(inner)
-            | Inline::Strikeout(inner)
-            | Inline::Superscript(inner)
-            | Inline::Subscript(inner)
-            | Inline::SmallCaps(inner)
-            | Inline::Span(_, inner) => inline_text_content(inner),
-            Inline::Quoted(_, inner) => {
-                format!("\u{201C}{}\u{201D}", inline_text_content(inner))
-            }
-            Inline::Code(_, s) => s.clone(),
-            Inline::Math(_, s) => s.clone(),
-            Inline::Link(_, content, target) => {
-                if content.is_empty() {
-                    target.url.clone()
-                } else {
-                    inline_text_content(content)
-                }
-            }
-            Inline::Image(_, alt, _) => inline_text_content(alt),
-            Inline::Note(blocks) => extract_inline_text_from_blocks(blocks),
-            Inline::RawInline(_, s) => s.clone(),
-        })
-        .collect()
-}
-
 /// Returns heading font size in half-points for a given heading level (1-6).
 /// Sizes are relative to the base_size (body text size in half-points).
 fn heading_size(level: u8, base_size: usize) -> usize {
@@ -534,6 +1013,90 @@ fn heading_size(level: u8, base_size: usize) -> usize {
     }
 }
 
+/// Run highlight and text color for a span. The `mark` class highlights in
+/// yellow; a `background`/`background-color` style picks the highlight and a
+/// `color` style or a color-named class (`.red`) picks the text color.
+fn span_run_style(attr: &Attr) -> (Option<&'static str>, Option<String>) {
+    let mut highlight = None;
+    let mut color = None;
+    for class in &attr.classes {
+        if class == "mark" {
+            highlight = Some("yellow");
+        } else if let Some(hex) = css_color_hex(class) {
+            color = Some(hex);
+        }
+    }
+    let style = attr
+        .attrs
+        .iter()
+        .filter(|(k, _)| k == "style")
+        .flat_map(|(_, v)| v.split(';'));
+    for decl in style {
+        let Some((prop, value)) = decl.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match prop.trim().to_ascii_lowercase().as_str() {
+            "background" | "background-color" => {
+                if let Some(h) = highlight_name(value) {
+                    highlight = Some(h);
+                }
+            }
+            "color" => {
+                if let Some(hex) = css_color_hex(value) {
+                    color = Some(hex);
+                }
+            }
+            _ => {}
+        }
+    }
+    (highlight, color)
+}
+
+/// Map a CSS color name to one of Word's fixed highlight colors.
+fn highlight_name(value: &str) -> Option<&'static str> {
+    Some(match value.to_ascii_lowercase().as_str() {
+        "yellow" => "yellow",
+        "lime" | "green" => "green",
+        "cyan" | "aqua" => "cyan",
+        "magenta" | "fuchsia" | "pink" => "magenta",
+        "blue" => "blue",
+        "red" => "red",
+        "gray" | "grey" | "lightgray" | "lightgrey" | "silver" => "lightGray",
+        "darkgray" | "darkgrey" => "darkGray",
+        "black" => "black",
+        "white" => "white",
+        _ => return None,
+    })
+}
+
+/// Hex RGB (without `#`) for a CSS color name or `#rgb`/`#rrggbb` value.
+fn css_color_hex(value: &str) -> Option<String> {
+    let value = value.trim().to_ascii_lowercase();
+    if let Some(hex) = value.strip_prefix('#') {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        return match hex.len() {
+            6 => Some(hex.to_ascii_uppercase()),
+            3 => Some(hex.chars().flat_map(|c| [c, c]).collect::<String>().to_ascii_uppercase()),
+            _ => None,
+        };
+    }
+    let hex = match value.as_str() {
+        "red" => "FF0000",
+        "green" => "008000",
+        "blue" => "0000FF",
+        "orange" => "FFA500",
+        "purple" => "800080",
+        "gray" | "grey" => "808080",
+        "black" => "000000",
+        "white" => "FFFFFF",
+        _ => return None,
+    };
+    Some(hex.to_string())
+}
+
 /// Build a TableCellBorders with all four sides set to a given color and size.
 fn make_cell_borders(color: &str, size: usize) -> TableCellBorders {
     TableCellBorders::new()
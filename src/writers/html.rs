@@ -1,30 +1,154 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+
+use crate::ast::inline::inlines_text;
 use crate::ast::{
-    Alignment, Attr, Block, Document, Inline, MathType, QuoteType,
+    Alignment, Attr, Block, Document, Inline, MathType, Meta,
 };
+use crate::utils::base64;
+use crate::writers::options::{HtmlFormat, MathMethod, WriterOptions};
 
-/// Convert a Document AST into a full HTML string.
+/// Built-in styles after the `body` and `code` rules, which carry the
+/// document's fonts and size.
+const STYLESHEET: &str = "\
+table { border-collapse: collapse; width: 100%; margin: 1em 0; }
+th, td { border: 1px solid #999; padding: 8px 12px; text-align: left; }
+th { background-color: #1F4E79; color: white; font-weight: bold; }
+tr:nth-child(even) { background-color: #EDF2F7; }
+pre { background: #f5f5f5; padding: 1em; overflow-x: auto; border-radius: 4px; }
+pre.wrap { white-space: pre-wrap; overflow-wrap: anywhere; }
+blockquote { border-left: 4px solid #1F4E79; margin: 1em 0; padding: 0.5em 1em; background: #f9f9f9; }
+h1, h2, h3 { color: #1F4E79; }
+hr { border: none; border-top: 2px solid #ccc; margin: 2em 0; }
+.abstract { margin: 1.5em 3em; font-size: 0.95em; }
+blockquote footer { text-align: right; font-style: italic; }
+.abstract h2 { text-align: center; font-size: 1.1em; }
+.task-summary { border-top: 1px solid #ccc; padding-top: 0.5em; font-weight: bold; }
+.admonition, .alert { border-left: 4px solid #1F4E79; background: #F4F8FB; margin: 1em 0; padding: 0.5em 1em; }
+.admonition-title, .alert-title { font-weight: bold; margin: 0 0 0.5em; }
+.admonition.warning, .admonition.caution, .alert-warning, .alert-caution { border-color: #C27C0E; background: #FFF8E6; }
+.admonition.danger, .admonition.error { border-color: #B42318; background: #FEF3F2; }
+p { hyphens: auto; }
+";
+
+/// Rendering settings resolved once from the options and document metadata.
+struct HtmlContext {
+    /// Prefix for the language class on code blocks (`language-` by default).
+    highlight_prefix: String,
+    /// Render math as MathML instead of `\(...\)` TeX delimiters.
+    mathml: bool,
+    /// Render a trailing `— Author` blockquote paragraph as a `<cite>`.
+    cite_attribution: bool,
+    /// Drop `<!-- ... -->` comments from raw HTML.
+    strip_comments: bool,
+    /// Drop raw HTML, event-handler attributes and script URLs.
+    sanitize: bool,
+    /// Document language from `lang` metadata, for quotes and hyphenation.
+    lang: Option<String>,
+    /// Count of figures rendered so far, for generated figure ids.
+    figures: Cell<usize>,
+    /// Footnotes, numbered in the order they are first referenced.
+    notes: RefCell<Vec<Footnote>>,
+    /// Expand code block tabs to this many columns; `None` keeps them.
+    tab_stop: Option<usize>,
+    /// Prefix for every rendered id and for `#` links (`--id-prefix`).
+    id_prefix: String,
+    /// Heading ids handed out so far, to keep generated ones unique.
+    heading_ids: RefCell<HashSet<String>>,
+}
+
+/// A rendered footnote. A note referenced more than once (the same label)
+/// is written once, with a backlink to each reference.
+struct Footnote {
+    label: String,
+    body: String,
+    refs: usize,
+}
+
+impl HtmlContext {
+    fn new(meta: &Meta, options: &WriterOptions) -> Self {
+        Self {
+            highlight_prefix: options.highlight_prefix.clone(),
+            mathml: options.math == MathMethod::MathMl,
+            cite_attribution: options.cite_attribution,
+            strip_comments: options.strip_comments,
+            sanitize: options.sanitize,
+            lang: meta.get_str("lang").map(str::to_string),
+            figures: Cell::new(0),
+            notes: RefCell::new(Vec::new()),
+            tab_stop: (!options.preserve_tabs).then_some(options.tab_stop),
+            id_prefix: options.id_prefix.clone(),
+            heading_ids: RefCell::new(HashSet::new()),
+        }
+    }
+}
+
+/// Convert a Document AST into a full HTML string with default options.
+///
+/// The output always declares `<meta charset="UTF-8">`, which matches the
+/// encoding of the returned `String`; callers must write it out as UTF-8.
 pub fn write_html(doc: &Document) -> String {
+    write_html_with(doc, &WriterOptions::default())
+}
+
+/// Convert a Document AST into a full HTML string.
+pub fn write_html_with(doc: &Document, options: &WriterOptions) -> String {
     let mut out = String::new();
+    let ctx = HtmlContext::new(&doc.meta, options);
 
     // ---- <head> ----
     let title = doc.meta.title().unwrap_or("");
     let fontsize = doc.meta.get_str("fontsize").unwrap_or("12pt");
-    out.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"UTF-8\">\n");
+    // The lang attribute also drives browser hyphenation (`hyphens: auto`)
+    match &ctx.lang {
+        Some(lang) => out.push_str(&format!(
+            "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"UTF-8\">\n",
+            escape_attr(lang)
+        )),
+        None => out.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"UTF-8\">\n"),
+    }
     if !title.is_empty() {
         out.push_str(&format!("<title>{}</title>\n", escape_html(title)));
     }
+    let mainfont = css_font_name(doc.meta.get_str("mainfont").unwrap_or("Calibri"));
+    out.push_str("<style>\n");
+    // An embedded font file stands in for the main font
+    if let Some(font) = &options.embed_font {
+        out.push_str(&format!(
+            "@font-face {{ font-family: \"{}\"; src: url(\"data:{};base64,{}\") format(\"{}\"); }}\n",
+            mainfont,
+            css_font_name(&font.mime),
+            base64::encode(&font.data),
+            css_font_name(&font.format)
+        ));
+    }
+    out.push_str(&format!(
+        "body {{ font-family: \"{}\", \"Segoe UI\", \"Arial\", sans-serif; font-size: {}; line-height: 1.6; max-width: 800px; margin: 0 auto; padding: 2em; color: #333; }}\n",
+        mainfont,
+        escape_html(fontsize),
+    ));
     out.push_str(&format!(
-        "<style>\nbody {{ font-family: \"Calibri\", \"Segoe UI\", \"Arial\", sans-serif; font-size: {}; line-height: 1.6; max-width: 800px; margin: 0 auto; padding: 2em; color: #333; }}\ntable {{ border-collapse: collapse; width: 100%; margin: 1em 0; }}\nth, td {{ border: 1px solid #999; padding: 8px 12px; text-align: left; }}\nth {{ background-color: #1F4E79; color: white; font-weight: bold; }}\ntr:nth-child(even) {{ background-color: #EDF2F7; }}\npre {{ background: #f5f5f5; padding: 1em; overflow-x: auto; border-radius: 4px; }}\ncode {{ font-family: \"Courier New\", monospace; }}\nblockquote {{ border-left: 4px solid #1F4E79; margin: 1em 0; padding: 0.5em 1em; background: #f9f9f9; }}\nh1, h2, h3 {{ color: #1F4E79; }}\nhr {{ border: none; border-top: 2px solid #ccc; margin: 2em 0; }}\n</style>\n",
-        escape_html(fontsize)
+        "code {{ font-family: \"{}\", monospace; }}\n",
+        css_font_name(doc.meta.get_str("monofont").unwrap_or("Courier New")),
     ));
-    out.push_str("</head>\n<body>\n");
+    out.push_str(STYLESHEET);
+    // Document CSS comes last so it overrides the defaults. Untrusted CSS
+    // could load remote URLs or hide content, so sanitizing drops it.
+    if !options.sanitize
+        && let Some(css) = doc.meta.css()
+    {
+        out.push_str(&escape_style(&css));
+        out.push('\n');
+    }
+    out.push_str("</style>\n</head>\n<body>\n");
+    write_includes(&mut out, &options.include_before_body);
 
     // ---- metadata header block ----
-    if !title.is_empty()
+    let has_metadata_block = !title.is_empty()
         || doc.meta.subtitle().is_some()
         || doc.meta.author().is_some()
-        || doc.meta.date().is_some()
-    {
+        || doc.meta.date().is_some();
+    if has_metadata_block && !options.strip_metadata_block {
         out.push_str("<header>\n");
         if !title.is_empty() {
             out.push_str(&format!(
@@ -44,74 +168,174 @@ pub fn write_html(doc: &Document) -> String {
                 escape_html(author)
             ));
         }
-        if let Some(date) = doc.meta.date() {
+        if let Some(date) = doc.meta.display_date() {
             out.push_str(&format!(
                 "<p class=\"date\">{}</p>\n",
-                escape_html(date)
+                escape_html(&date)
             ));
         }
         out.push_str("</header>\n");
     }
 
+    // ---- abstract ----
+    if let Some(blocks) = doc.meta.abstract_blocks() {
+        out.push_str("<div class=\"abstract\">\n<h2>Abstract</h2>\n");
+        for block in &blocks {
+            write_block(&mut out, block, &ctx);
+        }
+        out.push_str("</div>\n");
+    }
+
+    // ---- lists of tables and figures ----
+    for (key, title, entries) in super::caption_lists(doc) {
+        out.push_str(&format!("<nav class=\"{}\">\n<h2>{}</h2>\n<ol>\n", key, title));
+        for entry in &entries {
+            out.push_str("<li>");
+            write_inlines(&mut out, entry, &ctx);
+            out.push_str("</li>\n");
+        }
+        out.push_str("</ol>\n</nav>\n");
+    }
+
     // ---- body blocks ----
     for block in &doc.blocks {
-        write_block(&mut out, block);
+        write_block(&mut out, block, &ctx);
     }
 
+    write_footnotes(&mut out, &ctx);
+
+    if options.task_summary
+        && let Some(summary) = super::task_summary(doc)
+    {
+        out.push_str(&format!("<p class=\"task-summary\">{}</p>\n", summary));
+    }
+
+    write_includes(&mut out, &options.include_after_body);
     out.push_str("</body>\n</html>");
-    out
+    if options.ascii {
+        out = super::html_format::ascii_only(&out);
+    } else if options.numeric_entities {
+        out = super::html_format::numeric_entities(&out);
+    }
+    match options.html_format {
+        HtmlFormat::Compact => out,
+        HtmlFormat::Pretty => super::html_format::pretty(&out),
+        HtmlFormat::Minify => super::html_format::minify(&out),
+    }
 }
 
 // ---------------------------------------------------------------------------
 // Block rendering
 // ---------------------------------------------------------------------------
 
-fn write_block(out: &mut String, block: &Block) {
+fn write_block(out: &mut String, block: &Block, ctx: &HtmlContext) {
     match block {
-        Block::Para(inlines) | Block::Plain(inlines) => {
+        Block::Para(inlines) => {
             out.push_str("<p>");
-            write_inlines(out, inlines);
+            write_inlines(out, inlines, ctx);
             out.push_str("</p>\n");
         }
 
+        // Plain text has no paragraph wrapper (tight list items, table cells)
+        Block::Plain(inlines) => {
+            write_inlines(out, inlines, ctx);
+            out.push('\n');
+        }
+
         Block::Heading(attr, level, inlines) => {
             let tag = heading_tag(*level);
-            let attr_str = render_attr(attr);
+            let mut attr = attr.clone();
+            if attr.id.is_empty() {
+                attr.id = heading_id(inlines, ctx);
+            }
+            let attr_str = render_attr(&attr, ctx);
             out.push_str(&format!("<{tag}{attr_str}>"));
-            write_inlines(out, inlines);
+            write_inlines(out, inlines, ctx);
             out.push_str(&format!("</{tag}>\n"));
         }
 
         Block::CodeBlock(attr, code) => {
-            // First class is treated as the language identifier
-            let lang_class = attr.classes.first().map(|s| s.as_str()).unwrap_or("");
+            // A caption makes the block a listing figure, captioned above
+            let caption = super::code_caption(attr);
+            if let Some(caption) = caption {
+                out.push_str(&format!(
+                    "<figure class=\"listing\">\n<figcaption>{}</figcaption>\n",
+                    escape_html(caption)
+                ));
+            }
+            // `.wrap` soft-wraps long lines; otherwise the first class is
+            // treated as the language identifier. The id, other classes and
+            // key=value attributes (`data-runnable=true`) go on the `<pre>`.
+            let lang_class = attr
+                .classes
+                .iter()
+                .find(|c| *c != "wrap")
+                .map(|s| s.as_str())
+                .unwrap_or("");
+            let pre_attr = Attr {
+                id: attr.id.clone(),
+                classes: attr
+                    .classes
+                    .iter()
+                    .filter(|c| c.as_str() != lang_class)
+                    .cloned()
+                    .collect(),
+                attrs: attr
+                    .attrs
+                    .iter()
+                    .filter(|(key, _)| key != "caption")
+                    .cloned()
+                    .collect(),
+            };
+            out.push_str(&format!("<pre{}>", render_attr(&pre_attr, ctx)));
             if lang_class.is_empty() {
-                out.push_str("<pre><code>");
+                out.push_str("<code>");
             } else {
                 out.push_str(&format!(
-                    "<pre><code class=\"language-{}\">",
+                    "<code class=\"{}{}\">",
+                    escape_attr(&ctx.highlight_prefix),
                     escape_attr(lang_class)
                 ));
             }
-            out.push_str(&escape_html(code));
+            match ctx.tab_stop {
+                Some(tab_stop) => out.push_str(&escape_html(&super::expand_tabs(code, tab_stop))),
+                None => out.push_str(&escape_html(code)),
+            }
             out.push_str("</code></pre>\n");
+            if caption.is_some() {
+                out.push_str("</figure>\n");
+            }
         }
 
         Block::BlockQuote(blocks) => {
             out.push_str("<blockquote>\n");
-            for b in blocks {
-                write_block(out, b);
+            let attribution = match blocks.split_last() {
+                Some((last, rest)) if ctx.cite_attribution && !rest.is_empty() => {
+                    super::attribution_line(last).map(|inlines| (rest, inlines))
+                }
+                _ => None,
+            };
+            match attribution {
+                Some((rest, inlines)) => {
+                    for b in rest {
+                        write_block(out, b, ctx);
+                    }
+                    out.push_str("<footer>\u{2014} <cite>");
+                    write_inlines(out, &inlines, ctx);
+                    out.push_str("</cite></footer>\n");
+                }
+                None => {
+                    for b in blocks {
+                        write_block(out, b, ctx);
+                    }
+                }
             }
             out.push_str("</blockquote>\n");
         }
 
         Block::BulletList(items) => {
             out.push_str("<ul>\n");
-            for item in items {
-                out.push_str("<li>");
-                write_list_item_blocks(out, item);
-                out.push_str("</li>\n");
-            }
+            write_list_items(out, items, ctx);
             out.push_str("</ul>\n");
         }
 
@@ -122,11 +346,7 @@ fn write_block(out: &mut String, block: &Block) {
             } else {
                 out.push_str(&format!("<ol start=\"{start}\">\n"));
             }
-            for item in items {
-                out.push_str("<li>");
-                write_list_item_blocks(out, item);
-                out.push_str("</li>\n");
-            }
+            write_list_items(out, items, ctx);
             out.push_str("</ol>\n");
         }
 
@@ -134,11 +354,11 @@ fn write_block(out: &mut String, block: &Block) {
             out.push_str("<dl>\n");
             for (term, defs) in items {
                 out.push_str("<dt>");
-                write_inlines(out, term);
+                write_inlines(out, term, ctx);
                 out.push_str("</dt>\n");
                 for def in defs {
                     out.push_str("<dd>");
-                    write_list_item_blocks(out, def);
+                    write_list_item_blocks(out, def, ctx);
                     out.push_str("</dd>\n");
                 }
             }
@@ -147,6 +367,12 @@ fn write_block(out: &mut String, block: &Block) {
 
         Block::Table(table) => {
             out.push_str("<table>\n");
+            let caption = super::caption_inlines(&table.caption);
+            if !caption.is_empty() {
+                out.push_str("<caption>");
+                write_inlines(out, &caption, ctx);
+                out.push_str("</caption>\n");
+            }
 
             // thead
             if !table.head.rows.is_empty() {
@@ -157,7 +383,7 @@ fn write_block(out: &mut String, block: &Block) {
                         let align_style = alignment_style(&cell.align);
                         let span_attrs = cell_span_attrs(cell.row_span, cell.col_span);
                         out.push_str(&format!("<th{align_style}{span_attrs}>"));
-                        write_cell_content(out, &cell.content);
+                        write_cell_content(out, &cell.content, ctx);
                         out.push_str("</th>");
                     }
                     out.push_str("</tr>\n");
@@ -179,7 +405,7 @@ fn write_block(out: &mut String, block: &Block) {
                             let align_style = alignment_style(&cell.align);
                             let span_attrs = cell_span_attrs(cell.row_span, cell.col_span);
                             out.push_str(&format!("<td{align_style}{span_attrs}>"));
-                            write_cell_content(out, &cell.content);
+                            write_cell_content(out, &cell.content, ctx);
                             out.push_str("</td>");
                         }
                         out.push_str("</tr>\n");
@@ -197,7 +423,7 @@ fn write_block(out: &mut String, block: &Block) {
                         let align_style = alignment_style(&cell.align);
                         let span_attrs = cell_span_attrs(cell.row_span, cell.col_span);
                         out.push_str(&format!("<td{align_style}{span_attrs}>"));
-                        write_cell_content(out, &cell.content);
+                        write_cell_content(out, &cell.content, ctx);
                         out.push_str("</td>");
                     }
                     out.push_str("</tr>\n");
@@ -208,36 +434,84 @@ fn write_block(out: &mut String, block: &Block) {
             out.push_str("</table>\n");
         }
 
-        Block::Figure(attr, _caption, blocks) => {
-            let attr_str = render_attr(attr);
-            out.push_str(&format!("<figure{attr_str}>\n"));
+        Block::Figure(attr, caption, blocks) => {
+            ctx.figures.set(ctx.figures.get() + 1);
+            let caption_inlines: Vec<Inline> = caption
+                .long
+                .iter()
+                .flat_map(|b| match b {
+                    Block::Para(inlines) | Block::Plain(inlines) => inlines.clone(),
+                    _ => vec![],
+                })
+                .collect();
+
+            // The figcaption labels the figure for assistive technology
+            let mut attr = attr.clone();
+            if attr.id.is_empty() {
+                attr.id = format!("figure-{}", ctx.figures.get());
+            }
+            let caption_id = format!("{}{}-caption", ctx.id_prefix, attr.id);
+            out.push_str(&format!("<figure{} role=\"figure\"", render_attr(&attr, ctx)));
+            if !caption_inlines.is_empty() {
+                out.push_str(&format!(" aria-labelledby=\"{}\"", escape_attr(&caption_id)));
+            }
+            out.push_str(">\n");
             for b in blocks {
-                write_block(out, b);
+                write_block(out, &with_default_alt(b, &caption_inlines), ctx);
+            }
+            if !caption_inlines.is_empty() {
+                out.push_str(&format!("<figcaption id=\"{}\">", escape_attr(&caption_id)));
+                write_inlines(out, &caption_inlines, ctx);
+                out.push_str("</figcaption>\n");
             }
             out.push_str("</figure>\n");
         }
 
+        Block::Div(attr, _) if super::excluded_from(attr, "html") => {}
         Block::Div(attr, blocks) => {
-            let attr_str = render_attr(attr);
-            out.push_str(&format!("<div{attr_str}>\n"));
+            let admonition = super::admonition(attr);
+            // GitHub alerts keep their own `alert` class
+            let base = if attr.classes.iter().any(|c| c == "alert") { "alert" } else { "admonition" };
+            let mut attr = attr.clone();
+            if admonition.is_some() {
+                if base == "admonition" {
+                    attr.classes.insert(0, base.to_string());
+                }
+                // The title is shown in the callout, not as a tooltip
+                attr.attrs.retain(|(key, _)| key != "title");
+            }
+            let tag = if admonition.is_some() { "div" } else { div_tag(&attr) };
+            out.push_str(&format!("<{}{}>\n", tag, render_attr(&attr, ctx)));
+            if let Some((_, title)) = admonition {
+                out.push_str(&format!("<p class=\"{}-title\">{}</p>\n", base, escape_html(title)));
+            }
             for b in blocks {
-                write_block(out, b);
+                write_block(out, b, ctx);
             }
-            out.push_str("</div>\n");
+            out.push_str(&format!("</{}>\n", tag));
         }
 
         Block::LineBlock(lines) => {
             out.push_str("<div class=\"line-block\">\n");
             for line in lines {
-                write_inlines(out, line);
+                write_inlines(out, line, ctx);
                 out.push_str("<br>\n");
             }
             out.push_str("</div>\n");
         }
 
         Block::RawBlock(fmt, content) => {
-            if fmt.0 == "html" {
-                out.push_str(content);
+            if super::raw_format_matches(fmt, "html") && !ctx.sanitize {
+                let content = if ctx.strip_comments {
+                    let stripped = strip_html_comments(content);
+                    if stripped.trim().is_empty() {
+                        return;
+                    }
+                    stripped
+                } else {
+                    content.clone()
+                };
+                out.push_str(&content);
                 if !content.ends_with('\n') {
                     out.push('\n');
                 }
@@ -259,13 +533,13 @@ fn write_block(out: &mut String, block: &Block) {
 // Inline rendering
 // ---------------------------------------------------------------------------
 
-fn write_inlines(out: &mut String, inlines: &[Inline]) {
+fn write_inlines(out: &mut String, inlines: &[Inline], ctx: &HtmlContext) {
     for inline in inlines {
-        write_inline(out, inline);
+        write_inline(out, inline, ctx);
     }
 }
 
-fn write_inline(out: &mut String, inline: &Inline) {
+fn write_inline(out: &mut String, inline: &Inline, ctx: &HtmlContext) {
     match inline {
         Inline::Str(s) => out.push_str(&escape_html(s)),
 
@@ -277,91 +551,111 @@ fn write_inline(out: &mut String, inline: &Inline) {
 
         Inline::Emph(inlines) => {
             out.push_str("<em>");
-            write_inlines(out, inlines);
+            write_inlines(out, inlines, ctx);
             out.push_str("</em>");
         }
 
         Inline::Strong(inlines) => {
             out.push_str("<strong>");
-            write_inlines(out, inlines);
+            write_inlines(out, inlines, ctx);
             out.push_str("</strong>");
         }
 
         Inline::Underline(inlines) => {
             out.push_str("<u>");
-            write_inlines(out, inlines);
+            write_inlines(out, inlines, ctx);
             out.push_str("</u>");
         }
 
         Inline::Strikeout(inlines) => {
             out.push_str("<del>");
-            write_inlines(out, inlines);
+            write_inlines(out, inlines, ctx);
             out.push_str("</del>");
         }
 
         Inline::Superscript(inlines) => {
             out.push_str("<sup>");
-            write_inlines(out, inlines);
+            write_inlines(out, inlines, ctx);
             out.push_str("</sup>");
         }
 
         Inline::Subscript(inlines) => {
             out.push_str("<sub>");
-            write_inlines(out, inlines);
+            write_inlines(out, inlines, ctx);
             out.push_str("</sub>");
         }
 
         Inline::SmallCaps(inlines) => {
             out.push_str("<span style=\"font-variant: small-caps;\">");
-            write_inlines(out, inlines);
+            write_inlines(out, inlines, ctx);
             out.push_str("</span>");
         }
 
         Inline::Quoted(quote_type, inlines) => {
-            let (open, close) = match quote_type {
-                QuoteType::SingleQuote => ("&#8216;", "&#8217;"),
-                QuoteType::DoubleQuote => ("&#8220;", "&#8221;"),
-            };
-            out.push_str(open);
-            write_inlines(out, inlines);
-            out.push_str(close);
+            let (open, close) = super::quote_marks(ctx.lang.as_deref(), quote_type);
+            out.push_str(&char_entities(open));
+            write_inlines(out, inlines, ctx);
+            out.push_str(&char_entities(close));
         }
 
-        Inline::Code(_, code) => {
-            out.push_str("<code>");
+        Inline::Code(attr, code) => {
+            let tag = if attr.classes.iter().any(|c| c == "kbd") { "kbd" } else { "code" };
+            let mut attr = attr.clone();
+            if tag == "kbd" {
+                attr.classes.retain(|c| c != "kbd");
+            } else if let Some(lang) = attr.classes.first_mut() {
+                // The first class names the language, as on code blocks
+                *lang = format!("{}{}", ctx.highlight_prefix, lang);
+            }
+            out.push_str(&format!("<{tag}{}>", render_attr(&attr, ctx)));
             out.push_str(&escape_html(code));
-            out.push_str("</code>");
+            out.push_str(&format!("</{tag}>"));
         }
 
-        Inline::Math(math_type, content) => match math_type {
-            MathType::InlineMath => {
-                out.push_str(&format!("\\({}\\)", escape_html(content)));
-            }
-            MathType::DisplayMath => {
-                out.push_str(&format!("\\[{}\\]", escape_html(content)));
+        Inline::Math(math_type, content) => {
+            let display = *math_type == MathType::DisplayMath;
+            let mathml = if ctx.mathml {
+                super::mathml::tex_to_mathml(content, display)
+            } else {
+                None
+            };
+            match (mathml, math_type) {
+                (Some(mathml), _) => out.push_str(&mathml),
+                // Unsupported constructs fall back to TeX delimiters
+                (None, MathType::InlineMath) => {
+                    out.push_str(&format!("\\({}\\)", escape_html(content)));
+                }
+                (None, MathType::DisplayMath) => {
+                    out.push_str(&format!("\\[{}\\]", escape_html(content)));
+                }
             }
-        },
+        }
 
         Inline::Link(attr, inlines, target) => {
-            let mut extra = format!(" href=\"{}\"", escape_attr(&target.url));
+            // Links within the document follow the prefixed ids
+            let url = match target.url.strip_prefix('#') {
+                Some(id) if !id.is_empty() => format!("#{}{id}", ctx.id_prefix),
+                _ => safe_url(&target.url, ctx).to_string(),
+            };
+            let mut extra = format!(" href=\"{}\"", escape_attr(&url));
             if !target.title.is_empty() {
                 extra.push_str(&format!(" title=\"{}\"", escape_attr(&target.title)));
             }
-            let attr_str = render_attr(attr);
+            let attr_str = render_attr(attr, ctx);
             out.push_str(&format!("<a{extra}{attr_str}>"));
-            write_inlines(out, inlines);
+            write_inlines(out, inlines, ctx);
             out.push_str("</a>");
         }
 
         Inline::Image(attr, inlines, target) => {
             // Collect alt text from inlines
             let mut alt = String::new();
-            write_inlines(&mut alt, inlines);
+            write_inlines(&mut alt, inlines, ctx);
 
-            let attr_str = render_attr(attr);
+            let attr_str = render_attr(attr, ctx);
             out.push_str(&format!(
                 "<img src=\"{}\" alt=\"{}\"",
-                escape_attr(&target.url),
+                escape_attr(safe_url(&target.url, ctx)),
                 escape_attr(&alt)
             ));
             if !target.title.is_empty() {
@@ -370,25 +664,64 @@ fn write_inline(out: &mut String, inline: &Inline) {
             out.push_str(&format!("{attr_str}>"));
         }
 
-        Inline::Note(blocks) => {
-            // Render footnote inline as a span (simplified)
-            out.push_str("<span class=\"footnote\">");
-            for b in blocks {
-                write_block(out, b);
-            }
-            out.push_str("</span>");
+        Inline::Note(label, blocks) => {
+            // A repeated reference to the same label reuses the note's number
+            let existing = ctx.notes.borrow_mut().iter_mut().enumerate().find_map(|(i, note)| {
+                (!label.is_empty() && note.label == *label).then(|| {
+                    note.refs += 1;
+                    (i + 1, note.refs)
+                })
+            });
+            let (n, nth) = match existing {
+                Some(found) => found,
+                None => {
+                    // Number the note now; its body goes in the footnotes section
+                    let n = {
+                        let mut notes = ctx.notes.borrow_mut();
+                        notes.push(Footnote { label: label.clone(), body: String::new(), refs: 1 });
+                        notes.len()
+                    };
+                    let mut body = String::new();
+                    for b in blocks {
+                        write_block(&mut body, b, ctx);
+                    }
+                    ctx.notes.borrow_mut()[n - 1].body = body;
+                    (n, 1)
+                }
+            };
+            out.push_str(&format!(
+                "<sup class=\"footnote-ref\"><a href=\"#{}fn{n}\" id=\"{}\">{n}</a></sup>",
+                escape_attr(&ctx.id_prefix),
+                escape_attr(&footnote_ref_id(n, nth, ctx))
+            ));
         }
 
         Inline::Span(attr, inlines) => {
-            let attr_str = render_attr(attr);
-            out.push_str(&format!("<span{attr_str}>"));
-            write_inlines(out, inlines);
-            out.push_str("</span>");
+            // Highlight and CriticMarkup spans have their own elements
+            let semantic = [("mark", "mark"), ("insertion", "ins"), ("deletion", "del")]
+                .into_iter()
+                .find(|(class, _)| attr.classes.iter().any(|c| c == class));
+            if let Some((class, tag)) = semantic {
+                let mut rest = attr.clone();
+                rest.classes.retain(|c| c != class);
+                out.push_str(&format!("<{tag}{}>", render_attr(&rest, ctx)));
+                write_inlines(out, inlines, ctx);
+                out.push_str(&format!("</{tag}>"));
+            } else {
+                let attr_str = render_attr(attr, ctx);
+                out.push_str(&format!("<span{attr_str}>"));
+                write_inlines(out, inlines, ctx);
+                out.push_str("</span>");
+            }
         }
 
         Inline::RawInline(fmt, content) => {
-            if fmt.0 == "html" {
-                out.push_str(content);
+            if super::raw_format_matches(fmt, "html") && !ctx.sanitize {
+                if ctx.strip_comments {
+                    out.push_str(&strip_html_comments(content));
+                } else {
+                    out.push_str(content);
+                }
             }
         }
     }
@@ -398,39 +731,142 @@ fn write_inline(out: &mut String, inline: &Inline) {
 // Helper functions
 // ---------------------------------------------------------------------------
 
-/// Render list-item block content: unwrap a single Para into plain text,
-/// otherwise render full blocks.
-fn write_list_item_blocks(out: &mut String, blocks: &[Block]) {
-    if blocks.len() == 1 {
-        match &blocks[0] {
-            Block::Para(inlines) | Block::Plain(inlines) => {
-                write_inlines(out, inlines);
-                return;
-            }
-            _ => {}
+/// Copy `--include-before-body` / `--include-after-body` contents as is,
+/// each ending with a newline.
+fn write_includes(out: &mut String, includes: &[String]) {
+    for include in includes {
+        out.push_str(include);
+        if !include.ends_with('\n') {
+            out.push('\n');
         }
     }
-    for b in blocks {
-        write_block(out, b);
+}
+
+/// The document-end footnotes section, one list item per referenced note.
+fn write_footnotes(out: &mut String, ctx: &HtmlContext) {
+    let notes = ctx.notes.borrow();
+    if notes.is_empty() {
+        return;
+    }
+    out.push_str("<section class=\"footnotes\">\n<hr>\n<ol>\n");
+    for (i, note) in notes.iter().enumerate() {
+        let n = i + 1;
+        let backlinks = (1..=note.refs)
+            .map(|nth| {
+                format!(
+                    "<a href=\"#{}\" class=\"footnote-back\">&#8617;</a>",
+                    escape_attr(&footnote_ref_id(n, nth, ctx))
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        out.push_str(&format!(
+            "<li id=\"{}fn{n}\">{}{backlinks}</li>\n",
+            escape_attr(&ctx.id_prefix),
+            note.body
+        ));
+    }
+    out.push_str("</ol>\n</section>\n");
+}
+
+/// The id of the `nth` reference to note `n`: `fnref1`, then `fnref1-2`, ...
+fn footnote_ref_id(n: usize, nth: usize, ctx: &HtmlContext) -> String {
+    if nth == 1 {
+        format!("{}fnref{n}", ctx.id_prefix)
+    } else {
+        format!("{}fnref{n}-{nth}", ctx.id_prefix)
     }
 }
 
-/// Render table cell content (similar to list items: unwrap single Para).
-fn write_cell_content(out: &mut String, blocks: &[Block]) {
-    if blocks.len() == 1 {
-        match &blocks[0] {
-            Block::Para(inlines) | Block::Plain(inlines) => {
-                write_inlines(out, inlines);
-                return;
+/// A unique id for a heading without one, from its text as pandoc does:
+/// lowercased, spaces as hyphens, other punctuation dropped. Repeats get
+/// `-1`, `-2`, ... and a heading with no usable text becomes `section`.
+fn heading_id(inlines: &[Inline], ctx: &HtmlContext) -> String {
+    let text = inlines_text(inlines);
+    let slug = text
+        .split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    let slug = slug.trim_start_matches(|c: char| !c.is_alphabetic());
+    let base = if slug.is_empty() { "section" } else { slug }.to_string();
+    let mut ids = ctx.heading_ids.borrow_mut();
+    let mut id = base.clone();
+    let mut n = 0;
+    while ids.contains(&id) {
+        n += 1;
+        id = format!("{base}-{n}");
+    }
+    ids.insert(id.clone());
+    id
+}
+
+/// Render the `<li>` items of a list. A list is loose when any item holds
+/// a paragraph; then every item's text is wrapped in `<p>`, as GFM does, so
+/// items built without paragraphs don't render tighter than their siblings.
+fn write_list_items(out: &mut String, items: &[Vec<Block>], ctx: &HtmlContext) {
+    let loose = items.iter().flatten().any(|b| matches!(b, Block::Para(_)));
+    for item in items {
+        out.push_str("<li>");
+        if loose {
+            for b in item {
+                match b {
+                    Block::Plain(inlines) => write_block(out, &Block::Para(inlines.clone()), ctx),
+                    other => write_block(out, other, ctx),
+                }
             }
-            _ => {}
+        } else {
+            write_list_item_blocks(out, item, ctx);
         }
+        out.push_str("</li>\n");
+    }
+}
+
+/// Render list-item block content: a lone Plain block is written inline,
+/// otherwise render full blocks.
+fn write_list_item_blocks(out: &mut String, blocks: &[Block], ctx: &HtmlContext) {
+    if let [Block::Plain(inlines)] = blocks {
+        write_inlines(out, inlines, ctx);
+        return;
     }
     for b in blocks {
-        write_block(out, b);
+        write_block(out, b, ctx);
     }
 }
 
+/// Render table cell content (like list items: a lone Plain is inline).
+fn write_cell_content(out: &mut String, blocks: &[Block], ctx: &HtmlContext) {
+    if let [Block::Plain(inlines)] = blocks {
+        write_inlines(out, inlines, ctx);
+        return;
+    }
+    for b in blocks {
+        write_block(out, b, ctx);
+    }
+}
+
+/// The element for a div: the reserved classes `aside`, `section`, `nav`
+/// and `figure` give the semantic element of that name, anything else a
+/// `<div>`. The class is written either way.
+fn div_tag(attr: &Attr) -> &'static str {
+    attr.classes
+        .iter()
+        .find_map(|class| match class.as_str() {
+            "aside" => Some("aside"),
+            "section" => Some("section"),
+            "nav" => Some("nav"),
+            "figure" => Some("figure"),
+            _ => None,
+        })
+        .unwrap_or("div")
+}
+
 fn heading_tag(level: u8) -> &'static str {
     match level {
         1 => "h1",
@@ -442,11 +878,80 @@ fn heading_tag(level: u8) -> &'static str {
     }
 }
 
+/// A font family name safe to place inside a quoted CSS string.
+fn css_font_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '"' | '\\' | '<' | '>' | ';' | '{' | '}'))
+        .collect()
+}
+
+/// Keep CSS from closing the `<style>` element early: `</style` becomes
+/// `<\/style`, in any letter case.
+fn escape_style(css: &str) -> String {
+    let lower = css.to_ascii_lowercase();
+    let mut out = String::with_capacity(css.len());
+    let mut last = 0;
+    for (pos, _) in lower.match_indices("</style") {
+        out.push_str(&css[last..pos]);
+        out.push_str("<\\/");
+        last = pos + 2;
+    }
+    out.push_str(&css[last..]);
+    out
+}
+
+/// Encode each character as a numeric entity (`&#8220;`), keeping the output
+/// ASCII-safe for punctuation.
+fn char_entities(s: &str) -> String {
+    s.chars().map(|c| format!("&#{};", c as u32)).collect()
+}
+
+/// Give images in a figure's paragraph that lack alt text the caption as alt.
+fn with_default_alt(block: &Block, alt: &[Inline]) -> Block {
+    let fill = |inlines: &[Inline]| -> Vec<Inline> {
+        inlines
+            .iter()
+            .map(|i| match i {
+                Inline::Image(attr, existing, target) if existing.is_empty() => {
+                    Inline::Image(attr.clone(), alt.to_vec(), target.clone())
+                }
+                other => other.clone(),
+            })
+            .collect()
+    };
+    match block {
+        Block::Para(inlines) => Block::Para(fill(inlines)),
+        Block::Plain(inlines) => Block::Plain(fill(inlines)),
+        other => other.clone(),
+    }
+}
+
+/// `url`, or `#` when sanitizing and following it would run script.
+fn safe_url<'a>(url: &'a str, ctx: &HtmlContext) -> &'a str {
+    if ctx.sanitize && is_unsafe_url(url) { "#" } else { url }
+}
+
+/// Whether a URL runs script: `javascript:`, `vbscript:`, or a `data:` URL
+/// other than a raster image. Browsers ignore case, whitespace and control
+/// characters in the scheme, so those are ignored here too.
+fn is_unsafe_url(url: &str) -> bool {
+    let scheme: String = url
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .take(16)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    let raster_image = scheme.starts_with("data:image/") && !scheme.starts_with("data:image/svg");
+    scheme.starts_with("javascript:")
+        || scheme.starts_with("vbscript:")
+        || (scheme.starts_with("data:") && !raster_image)
+}
+
 /// Build the HTML attribute string for an Attr (id, class, extra key=value pairs).
-fn render_attr(attr: &Attr) -> String {
+fn render_attr(attr: &Attr, ctx: &HtmlContext) -> String {
     let mut s = String::new();
     if !attr.id.is_empty() {
-        s.push_str(&format!(" id=\"{}\"", escape_attr(&attr.id)));
+        s.push_str(&format!(" id=\"{}{}\"", escape_attr(&ctx.id_prefix), escape_attr(&attr.id)));
     }
     if !attr.classes.is_empty() {
         let classes = attr
@@ -458,11 +963,26 @@ fn render_attr(attr: &Attr) -> String {
         s.push_str(&format!(" class=\"{classes}\""));
     }
     for (k, v) in &attr.attrs {
+        // A key that is not a valid name (`x onclick`) would smuggle in
+        // attributes of its own
+        if !is_attr_name(k) {
+            continue;
+        }
+        if ctx.sanitize && (k.to_ascii_lowercase().starts_with("on") || is_unsafe_url(v)) {
+            continue;
+        }
         s.push_str(&format!(" {}=\"{}\"", escape_attr(k), escape_attr(v)));
     }
     s
 }
 
+/// Whether `name` is a safe HTML attribute name: `[A-Za-z_:][-A-Za-z0-9_:.]*`.
+fn is_attr_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || matches!(c, '_' | ':'))
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':' | '.'))
+}
+
 fn alignment_style(align: &Alignment) -> String {
     match align {
         Alignment::AlignLeft => " style=\"text-align: left;\"".to_string(),
@@ -483,8 +1003,24 @@ fn cell_span_attrs(row_span: u32, col_span: u32) -> String {
     s
 }
 
+/// Remove `<!-- ... -->` comments from raw HTML. An unterminated comment
+/// runs to the end of the input, as in a browser.
+fn strip_html_comments(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find("<!--") {
+        out.push_str(&rest[..start]);
+        match rest[start + 4..].find("-->") {
+            Some(end) => rest = &rest[start + 4 + end + 3..],
+            None => return out,
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
 /// Escape characters that are special in HTML text content.
-fn escape_html(s: &str) -> String {
+pub(crate) fn escape_html(s: &str) -> String {
     let mut out = String::with_capacity(s.len());
     for ch in s.chars() {
         match ch {
@@ -0,0 +1,269 @@
+/// Elements laid out as blocks: `pretty` puts them on their own lines, and
+/// whitespace next to them is insignificant.
+const BLOCK_TAGS: &[&str] = &[
+    "!doctype", "html", "head", "body", "meta", "title", "link", "style", "script", "header",
+    "footer", "main", "nav", "section", "article", "aside", "div", "p", "h1", "h2", "h3", "h4",
+    "h5", "h6", "ul", "ol", "li", "dl", "dt", "dd", "table", "caption", "colgroup", "col",
+    "thead", "tbody", "tfoot", "tr", "th", "td", "blockquote", "pre", "figure", "figcaption",
+    "hr",
+];
+
+/// Elements without a closing tag.
+const VOID_TAGS: &[&str] = &[
+    "!doctype", "meta", "link", "col", "hr", "br", "img", "input", "wbr", "source",
+];
+
+/// Elements whose content is copied unchanged.
+const RAW_TAGS: &[&str] = &["pre", "script", "style", "textarea"];
+
+#[derive(Debug)]
+enum Token<'a> {
+    /// A tag, with its lowercase name and whether it closes an element.
+    Tag { text: &'a str, name: String, closing: bool },
+    /// A comment or a raw element (`<pre>...</pre>`), kept as written.
+    Raw { text: &'a str, name: String },
+    Text(&'a str),
+}
+
+impl Token<'_> {
+    fn is_block(&self) -> bool {
+        match self {
+            Token::Tag { name, .. } | Token::Raw { name, .. } => BLOCK_TAGS.contains(&name.as_str()),
+            Token::Text(_) => false,
+        }
+    }
+}
+
+/// Indent nested block elements two spaces per level, one per line. Inline
+/// content stays on its element's line; `<pre>`, `<script>`, `<style>` and
+/// `<textarea>` content is left untouched.
+pub(crate) fn pretty(html: &str) -> String {
+    let tokens = tokenize(html);
+    let mut out = String::with_capacity(html.len() + html.len() / 4);
+    let mut depth: usize = 0;
+    // For each open block element, whether it has a block child
+    let mut open: Vec<bool> = Vec::new();
+    let mut after_block = false;
+
+    let newline = |out: &mut String, depth: usize| {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&"  ".repeat(depth));
+    };
+
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Tag { text, name, closing } if token.is_block() => {
+                if *closing {
+                    depth = depth.saturating_sub(1);
+                    if open.pop().unwrap_or(false) {
+                        newline(&mut out, depth);
+                    }
+                    out.push_str(text);
+                } else {
+                    if let Some(parent) = open.last_mut() {
+                        *parent = true;
+                    }
+                    newline(&mut out, depth);
+                    out.push_str(text);
+                    if !VOID_TAGS.contains(&name.as_str()) && !text.ends_with("/>") {
+                        depth += 1;
+                        open.push(false);
+                    }
+                }
+                after_block = true;
+            }
+            Token::Raw { text, .. } if token.is_block() => {
+                if let Some(parent) = open.last_mut() {
+                    *parent = true;
+                }
+                newline(&mut out, depth);
+                out.push_str(text);
+                after_block = true;
+            }
+            _ => {
+                let text = inline_text(&tokens, i);
+                if text.is_empty() {
+                    continue;
+                }
+                // Inline content after a nested block starts a new line
+                if after_block && open.last().copied().unwrap_or(false) {
+                    newline(&mut out, depth);
+                }
+                out.push_str(&text);
+                after_block = false;
+            }
+        }
+    }
+    out
+}
+
+/// Drop whitespace between block elements and fold line breaks in text to
+/// single spaces, leaving `<pre>`, `<script>`, `<style>` and `<textarea>`
+/// content untouched.
+pub(crate) fn minify(html: &str) -> String {
+    let tokens = tokenize(html);
+    let mut out = String::with_capacity(html.len());
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Tag { text, .. } | Token::Raw { text, .. } => out.push_str(text),
+            Token::Text(_) => out.push_str(&inline_text(&tokens, i)),
+        }
+    }
+    out
+}
+
+/// The text of an inline token: whitespace next to a block element is
+/// trimmed, and whitespace runs with a line break become one space.
+fn inline_text(tokens: &[Token], i: usize) -> String {
+    let text = match &tokens[i] {
+        Token::Text(text) => *text,
+        Token::Tag { text, .. } | Token::Raw { text, .. } => return text.to_string(),
+    };
+    let mut text = text;
+    if i == 0 || tokens[i - 1].is_block() {
+        text = text.trim_start();
+    }
+    if tokens.get(i + 1).is_none_or(Token::is_block) {
+        text = text.trim_end();
+    }
+    collapse_line_breaks(text)
+}
+
+/// Write every non-ASCII character as a numeric entity (`&#160;`), in text
+/// and attribute values. Stylesheets, scripts and comments keep raw UTF-8.
+pub(crate) fn numeric_entities(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    for token in tokenize(html) {
+        match token {
+            Token::Raw { text, name } if matches!(name.as_str(), "" | "style" | "script") => {
+                out.push_str(text)
+            }
+            Token::Tag { text, .. } | Token::Raw { text, .. } | Token::Text(text) => {
+                escape_non_ascii(text, &mut out, |c| format!("&#{};", c as u32))
+            }
+        }
+    }
+    out
+}
+
+/// Write the whole document as ASCII: numeric entities as in
+/// [`numeric_entities`], and also in comments; CSS escapes (`\E9 `) in
+/// stylesheets and `\u00e9` escapes in scripts, where entities are not
+/// decoded.
+pub(crate) fn ascii_only(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    for token in tokenize(html) {
+        match token {
+            Token::Raw { text, name } if name == "style" => {
+                escape_non_ascii(text, &mut out, |c| format!("\\{:X} ", c as u32))
+            }
+            Token::Raw { text, name } if name == "script" => {
+                escape_non_ascii(text, &mut out, |c| {
+                    let mut units = [0u16; 2];
+                    c.encode_utf16(&mut units).iter().map(|u| format!("\\u{:04x}", u)).collect()
+                })
+            }
+            Token::Tag { text, .. } | Token::Raw { text, .. } | Token::Text(text) => {
+                escape_non_ascii(text, &mut out, |c| format!("&#{};", c as u32))
+            }
+        }
+    }
+    out
+}
+
+fn escape_non_ascii(text: &str, out: &mut String, escape: impl Fn(char) -> String) {
+    for c in text.chars() {
+        if c.is_ascii() {
+            out.push(c);
+        } else {
+            out.push_str(&escape(c));
+        }
+    }
+}
+
+fn collapse_line_breaks(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find(|c: char| c.is_ascii_whitespace()) {
+        out.push_str(&rest[..pos]);
+        let run_len = rest[pos..]
+            .find(|c: char| !c.is_ascii_whitespace())
+            .unwrap_or(rest.len() - pos);
+        let run = &rest[pos..pos + run_len];
+        out.push_str(if run.contains('\n') { " " } else { run });
+        rest = &rest[pos + run_len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while pos < html.len() {
+        let rest = &html[pos..];
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            tokens.push(Token::Text(&rest[..end]));
+            pos += end;
+            continue;
+        }
+        if rest.starts_with("<!--") {
+            let end = rest.find("-->").map_or(rest.len(), |e| e + 3);
+            tokens.push(Token::Raw { text: &rest[..end], name: String::new() });
+            pos += end;
+            continue;
+        }
+        let Some(end) = rest.find('>').map(|e| e + 1) else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        let tag = &rest[..end];
+        let closing = tag.starts_with("</");
+        let name: String = tag[if closing { 2 } else { 1 }..]
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '!')
+            .collect::<String>()
+            .to_ascii_lowercase();
+
+        if !closing && RAW_TAGS.contains(&name.as_str()) {
+            // Keep the whole element, content and closing tag included
+            let close = format!("</{}", name);
+            let content_end = rest[end..]
+                .to_ascii_lowercase()
+                .find(&close)
+                .map_or(rest.len(), |c| {
+                    let after = end + c;
+                    after + rest[after..].find('>').map_or(rest.len() - after, |e| e + 1)
+                });
+            tokens.push(Token::Raw { text: &rest[..content_end], name });
+            pos += content_end;
+            continue;
+        }
+        tokens.push(Token::Tag { text: tag, name, closing });
+        pos += end;
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pretty_indents_blocks() {
+        let html = "<ul>\n<li>One <em>two</em></li>\n<li>Three\n<ul>\n<li>Four</li>\n</ul>\n</li>\n</ul>";
+        assert_eq!(
+            pretty(html),
+            "<ul>\n  <li>One <em>two</em></li>\n  <li>Three\n    <ul>\n      <li>Four</li>\n    </ul>\n  </li>\n</ul>"
+        );
+    }
+
+    #[test]
+    fn test_minify_keeps_pre_and_inline_spaces() {
+        let html = "<div>\n<p>a <b>b</b>\nc</p>\n<pre><code>x\n  y\n</code></pre>\n</div>";
+        assert_eq!(minify(html), "<div><p>a <b>b</b> c</p><pre><code>x\n  y\n</code></pre></div>");
+    }
+}
@@ -0,0 +1,409 @@
+use super::html::escape_html;
+
+/// Convert a TeX math string to a MathML `<math>` element.
+///
+/// Only a common subset of TeX is understood: letters, numbers, operators,
+/// `^`/`_` scripts, braces, `\frac`, `\sqrt`, `\text`, font commands, Greek
+/// letters, `\left`/`\right` and the usual relation and operator symbols.
+/// Returns `None` for anything else (environments, `&`, `\\`, unknown
+/// commands) so the caller can fall back to raw TeX.
+pub(crate) fn tex_to_mathml(tex: &str, display: bool) -> Option<String> {
+    let mut parser = Parser {
+        chars: tex.chars().collect(),
+        pos: 0,
+    };
+    let items = parser.parse_sequence(None)?;
+    let display_attr = if display { " display=\"block\"" } else { "" };
+    Some(format!(
+        "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"{}><semantics><mrow>{}</mrow><annotation encoding=\"application/x-tex\">{}</annotation></semantics></math>",
+        display_attr,
+        items.concat(),
+        escape_html(tex)
+    ))
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    /// Parse atoms until `close` (consumed) or, with no `close`, the end of input.
+    fn parse_sequence(&mut self, close: Option<char>) -> Option<Vec<String>> {
+        let mut items: Vec<String> = Vec::new();
+        loop {
+            self.skip_spaces();
+            match self.peek() {
+                None => return close.is_none().then_some(items),
+                Some(c) if Some(c) == close => {
+                    self.pos += 1;
+                    return Some(items);
+                }
+                Some('^') | Some('_') => {
+                    let base = items.pop().unwrap_or_else(|| "<mrow></mrow>".to_string());
+                    let (sub, sup) = self.parse_scripts()?;
+                    items.push(match (sub, sup) {
+                        (Some(sub), Some(sup)) => {
+                            format!("<msubsup>{}{}{}</msubsup>", base, sub, sup)
+                        }
+                        (Some(sub), None) => format!("<msub>{}{}</msub>", base, sub),
+                        (None, Some(sup)) => format!("<msup>{}{}</msup>", base, sup),
+                        (None, None) => base,
+                    });
+                }
+                Some(_) => items.push(self.parse_atom()?),
+            }
+        }
+    }
+
+    /// Parse a run of `^x` / `_y` scripts attached to the previous atom.
+    fn parse_scripts(&mut self) -> Option<(Option<String>, Option<String>)> {
+        let mut sub = None;
+        let mut sup = None;
+        loop {
+            self.skip_spaces();
+            match self.peek() {
+                Some('^') if sup.is_none() => {
+                    self.pos += 1;
+                    sup = Some(self.parse_argument()?);
+                }
+                Some('_') if sub.is_none() => {
+                    self.pos += 1;
+                    sub = Some(self.parse_argument()?);
+                }
+                _ => return Some((sub, sup)),
+            }
+        }
+    }
+
+    /// Parse a command or script argument: a braced group or a single token.
+    fn parse_argument(&mut self) -> Option<String> {
+        self.skip_spaces();
+        match self.peek()? {
+            c if c.is_ascii_digit() => {
+                self.pos += 1;
+                Some(format!("<mn>{}</mn>", c))
+            }
+            _ => self.parse_atom(),
+        }
+    }
+
+    /// Read the raw text of a braced group, e.g. the argument of `\text`.
+    fn parse_raw_group(&mut self) -> Option<String> {
+        self.skip_spaces();
+        if self.peek()? != '{' {
+            return None;
+        }
+        self.pos += 1;
+        let mut depth = 1;
+        let mut text = String::new();
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(text);
+                    }
+                }
+                _ => {}
+            }
+            text.push(c);
+        }
+        None
+    }
+
+    fn parse_atom(&mut self) -> Option<String> {
+        let c = self.peek()?;
+        self.pos += 1;
+        match c {
+            '{' => Some(mrow(self.parse_sequence(Some('}'))?)),
+            '\\' => self.parse_command(),
+            '0'..='9' | '.' => {
+                let mut number = c.to_string();
+                while let Some(d) = self.peek().filter(|d| d.is_ascii_digit() || *d == '.') {
+                    number.push(d);
+                    self.pos += 1;
+                }
+                Some(format!("<mn>{}</mn>", number))
+            }
+            '-' => Some("<mo>\u{2212}</mo>".to_string()),
+            '\'' => Some("<mo>\u{2032}</mo>".to_string()),
+            '+' | '=' | '<' | '>' | '(' | ')' | '[' | ']' | '|' | ',' | ';' | ':' | '!'
+            | '/' | '*' | '?' => Some(format!("<mo>{}</mo>", escape_html(&c.to_string()))),
+            '}' | '&' | '#' | '%' | '~' | '$' => None,
+            c if c.is_alphabetic() => Some(format!("<mi>{}</mi>", c)),
+            c => Some(format!("<mo>{}</mo>", escape_html(&c.to_string()))),
+        }
+    }
+
+    fn parse_command(&mut self) -> Option<String> {
+        let mut name = String::new();
+        while let Some(c) = self.peek().filter(char::is_ascii_alphabetic) {
+            name.push(c);
+            self.pos += 1;
+        }
+        if name.is_empty() {
+            // Single-symbol commands: \, \; \{ \} \|
+            let c = self.peek()?;
+            self.pos += 1;
+            return match c {
+                ',' => Some("<mspace width=\"0.167em\"/>".to_string()),
+                ':' => Some("<mspace width=\"0.222em\"/>".to_string()),
+                ';' => Some("<mspace width=\"0.278em\"/>".to_string()),
+                ' ' => Some("<mspace width=\"0.333em\"/>".to_string()),
+                '{' | '}' => Some(format!("<mo>{}</mo>", c)),
+                '|' => Some("<mo>\u{2016}</mo>".to_string()),
+                '%' | '$' | '#' | '&' | '_' => Some(format!("<mo>{}</mo>", escape_html(&c.to_string()))),
+                _ => None,
+            };
+        }
+
+        match name.as_str() {
+            "frac" | "dfrac" | "tfrac" => {
+                let num = self.parse_argument()?;
+                let den = self.parse_argument()?;
+                Some(format!("<mfrac>{}{}</mfrac>", num, den))
+            }
+            "sqrt" => {
+                self.skip_spaces();
+                if self.peek() == Some('[') {
+                    self.pos += 1;
+                    let index = mrow(self.parse_sequence(Some(']'))?);
+                    let radicand = self.parse_argument()?;
+                    Some(format!("<mroot>{}{}</mroot>", radicand, index))
+                } else {
+                    Some(format!("<msqrt>{}</msqrt>", self.parse_argument()?))
+                }
+            }
+            "text" | "textrm" | "mbox" => {
+                let text = self.parse_raw_group()?;
+                Some(format!("<mtext>{}</mtext>", escape_html(&text)))
+            }
+            "mathrm" | "operatorname" | "mathbf" | "mathit" | "mathbb" | "mathcal" => {
+                let variant = match name.as_str() {
+                    "mathbf" => "bold",
+                    "mathit" => "italic",
+                    "mathbb" => "double-struck",
+                    "mathcal" => "script",
+                    _ => "normal",
+                };
+                let text = self.parse_raw_group()?;
+                Some(format!(
+                    "<mi mathvariant=\"{}\">{}</mi>",
+                    variant,
+                    escape_html(&text)
+                ))
+            }
+            "left" | "right" => {
+                self.skip_spaces();
+                let delim = match self.peek()? {
+                    '\\' => {
+                        self.pos += 1;
+                        match self.peek()? {
+                            '{' | '}' => self.peek()?.to_string(),
+                            '|' => "\u{2016}".to_string(),
+                            _ => return None,
+                        }
+                    }
+                    '.' => String::new(),
+                    c @ ('(' | ')' | '[' | ']' | '|' | '<' | '>' | '/') => c.to_string(),
+                    _ => return None,
+                };
+                self.pos += 1;
+                if delim.is_empty() {
+                    Some(String::new())
+                } else {
+                    Some(format!("<mo>{}</mo>", escape_html(&delim)))
+                }
+            }
+            "quad" => Some("<mspace width=\"1em\"/>".to_string()),
+            "qquad" => Some("<mspace width=\"2em\"/>".to_string()),
+            "sin" | "cos" | "tan" | "cot" | "sec" | "csc" | "log" | "ln" | "exp" | "lim"
+            | "max" | "min" | "sup" | "inf" | "det" | "gcd" | "arg" | "deg" | "dim" => {
+                Some(format!("<mi>{}</mi>", name))
+            }
+            _ => {
+                if let Some(letter) = greek(&name) {
+                    Some(format!("<mi>{}</mi>", letter))
+                } else {
+                    symbol(&name).map(|op| format!("<mo>{}</mo>", op))
+                }
+            }
+        }
+    }
+}
+
+/// Wrap several items in an `<mrow>`; a single item needs no wrapper.
+fn mrow(items: Vec<String>) -> String {
+    if items.len() == 1 {
+        items.into_iter().next().unwrap_or_default()
+    } else {
+        format!("<mrow>{}</mrow>", items.concat())
+    }
+}
+
+fn greek(name: &str) -> Option<char> {
+    let c = match name {
+        "alpha" => 'α',
+        "beta" => 'β',
+        "gamma" => 'γ',
+        "delta" => 'δ',
+        "epsilon" => 'ϵ',
+        "varepsilon" => 'ε',
+        "zeta" => 'ζ',
+        "eta" => 'η',
+        "theta" => 'θ',
+        "vartheta" => 'ϑ',
+        "iota" => 'ι',
+        "kappa" => 'κ',
+        "lambda" => 'λ',
+        "mu" => 'μ',
+        "nu" => 'ν',
+        "xi" => 'ξ',
+        "pi" => 'π',
+        "varpi" => 'ϖ',
+        "rho" => 'ρ',
+        "sigma" => 'σ',
+        "tau" => 'τ',
+        "upsilon" => 'υ',
+        "phi" => 'ϕ',
+        "varphi" => 'φ',
+        "chi" => 'χ',
+        "psi" => 'ψ',
+        "omega" => 'ω',
+        "Gamma" => 'Γ',
+        "Delta" => 'Δ',
+        "Theta" => 'Θ',
+        "Lambda" => 'Λ',
+        "Xi" => 'Ξ',
+        "Pi" => 'Π',
+        "Sigma" => 'Σ',
+        "Upsilon" => 'Υ',
+        "Phi" => 'Φ',
+        "Psi" => 'Ψ',
+        "Omega" => 'Ω',
+        _ => return None,
+    };
+    Some(c)
+}
+
+fn symbol(name: &str) -> Option<&'static str> {
+    let s = match name {
+        "times" => "×",
+        "cdot" => "⋅",
+        "div" => "÷",
+        "pm" => "±",
+        "mp" => "∓",
+        "ast" => "∗",
+        "leq" | "le" => "≤",
+        "geq" | "ge" => "≥",
+        "neq" | "ne" => "≠",
+        "approx" => "≈",
+        "equiv" => "≡",
+        "sim" => "∼",
+        "propto" => "∝",
+        "infty" => "∞",
+        "partial" => "∂",
+        "nabla" => "∇",
+        "sum" => "∑",
+        "prod" => "∏",
+        "int" => "∫",
+        "oint" => "∮",
+        "to" | "rightarrow" => "→",
+        "leftarrow" => "←",
+        "Rightarrow" => "⇒",
+        "Leftarrow" => "⇐",
+        "leftrightarrow" => "↔",
+        "Leftrightarrow" => "⇔",
+        "mapsto" => "↦",
+        "in" => "∈",
+        "notin" => "∉",
+        "subset" => "⊂",
+        "subseteq" => "⊆",
+        "supset" => "⊃",
+        "cup" => "∪",
+        "cap" => "∩",
+        "emptyset" => "∅",
+        "forall" => "∀",
+        "exists" => "∃",
+        "neg" => "¬",
+        "land" | "wedge" => "∧",
+        "lor" | "vee" => "∨",
+        "ldots" | "dots" => "…",
+        "cdots" => "⋯",
+        "circ" => "∘",
+        "prime" => "′",
+        "langle" => "⟨",
+        "rangle" => "⟩",
+        _ => return None,
+    };
+    Some(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inner(tex: &str) -> String {
+        let math = tex_to_mathml(tex, false).unwrap();
+        let start = math.find("<mrow>").unwrap() + "<mrow>".len();
+        let end = math.find("</mrow><annotation").unwrap();
+        math[start..end].to_string()
+    }
+
+    #[test]
+    fn test_superscript() {
+        assert_eq!(inner("x^2"), "<msup><mi>x</mi><mn>2</mn></msup>");
+    }
+
+    #[test]
+    fn test_sub_and_superscript() {
+        assert_eq!(
+            inner("x_i^{n+1}"),
+            "<msubsup><mi>x</mi><mi>i</mi><mrow><mi>n</mi><mo>+</mo><mn>1</mn></mrow></msubsup>"
+        );
+    }
+
+    #[test]
+    fn test_frac_and_sqrt() {
+        assert_eq!(
+            inner("\\frac{a}{b} \\sqrt{2}"),
+            "<mfrac><mi>a</mi><mi>b</mi></mfrac><msqrt><mn>2</mn></msqrt>"
+        );
+    }
+
+    #[test]
+    fn test_greek_and_symbols() {
+        assert_eq!(
+            inner("\\alpha \\leq \\infty"),
+            "<mi>α</mi><mo>≤</mo><mo>∞</mo>"
+        );
+    }
+
+    #[test]
+    fn test_display_and_annotation() {
+        let math = tex_to_mathml("a<b", true).unwrap();
+        assert!(math.contains("display=\"block\""));
+        assert!(math.contains("<mo>&lt;</mo>"));
+        assert!(math.contains("<annotation encoding=\"application/x-tex\">a&lt;b</annotation>"));
+    }
+
+    #[test]
+    fn test_unsupported_falls_back() {
+        assert!(tex_to_mathml("\\begin{matrix}a & b\\end{matrix}", false).is_none());
+        assert!(tex_to_mathml("\\unknowncommand", false).is_none());
+        assert!(tex_to_mathml("{x", false).is_none());
+    }
+}
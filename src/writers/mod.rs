@@ -1,2 +1,180 @@
 pub mod docx;
 pub mod html;
+pub(crate) mod html_format;
+pub(crate) mod mathml;
+pub mod options;
+
+use crate::ast::{Attr, Block, Caption, Document, Format, Inline, QuoteType};
+
+/// Div classes rendered as admonition callouts, with their titles.
+const ADMONITIONS: &[(&str, &str)] = &[
+    ("note", "Note"),
+    ("tip", "Tip"),
+    ("hint", "Hint"),
+    ("info", "Info"),
+    ("important", "Important"),
+    ("warning", "Warning"),
+    ("caution", "Caution"),
+    ("danger", "Danger"),
+    ("error", "Error"),
+];
+
+/// The admonition kind and title of a div (`::: warning`, or a GitHub alert
+/// with class `alert-warning`), if it is one. A `title` attribute replaces
+/// the kind's default title.
+pub(crate) fn admonition(attr: &Attr) -> Option<(&'static str, &str)> {
+    let (kind, title) = attr.classes.iter().find_map(|class| {
+        let class = class.strip_prefix("alert-").unwrap_or(class);
+        ADMONITIONS.iter().find(|(kind, _)| *kind == class).copied()
+    })?;
+    let custom = attr.attrs.iter().find(|(key, _)| key == "title").map(|(_, value)| value.as_str());
+    Some((kind, custom.unwrap_or(title)))
+}
+
+/// Whether raw content in `format` is meant for `target` output (`html`,
+/// `latex`). Names are case-insensitive; `html4`/`html5` count as HTML and
+/// `tex` as LaTeX.
+pub(crate) fn raw_format_matches(format: &Format, target: &str) -> bool {
+    let name = format.0.to_ascii_lowercase();
+    let name = match name.as_str() {
+        "html4" | "html5" => "html",
+        "tex" => "latex",
+        other => other,
+    };
+    name == target
+}
+
+/// Whether a div is dropped from `target` output: it has `only-<format>`
+/// classes (`::: {.only-html}`) and none of them names `target`. Divs
+/// without such a class show everywhere.
+pub(crate) fn excluded_from(attr: &Attr, target: &str) -> bool {
+    let mut formats = attr.classes.iter().filter_map(|class| class.strip_prefix("only-")).peekable();
+    formats.peek().is_some() && !formats.any(|f| raw_format_matches(&Format(f.to_string()), target))
+}
+
+/// The `caption="..."` attribute of a code block, which makes it a listing.
+pub(crate) fn code_caption(attr: &Attr) -> Option<&str> {
+    attr.attrs
+        .iter()
+        .find(|(key, value)| key == "caption" && !value.trim().is_empty())
+        .map(|(_, value)| value.as_str())
+}
+
+/// Expand tabs in code to spaces, aligning to multiples of `tab_stop`
+/// columns on each line.
+pub(crate) fn expand_tabs(code: &str, tab_stop: usize) -> String {
+    let tab_stop = tab_stop.max(1);
+    let mut out = String::with_capacity(code.len());
+    let mut column = 0;
+    for c in code.chars() {
+        match c {
+            '\t' => {
+                let width = tab_stop - column % tab_stop;
+                out.extend(std::iter::repeat_n(' ', width));
+                column += width;
+            }
+            '\n' => {
+                out.push(c);
+                column = 0;
+            }
+            _ => {
+                out.push(c);
+                column += 1;
+            }
+        }
+    }
+    out
+}
+
+/// The lists requested by the `lot` and `lof` metadata flags, as (key,
+/// title, entries). Each table or figure with a caption contributes its short
+/// caption, or its full caption when it has no short one.
+pub(crate) fn caption_lists(doc: &Document) -> Vec<(&'static str, &'static str, Vec<Vec<Inline>>)> {
+    let mut lists = Vec::new();
+    for (key, title, figures) in [("lot", "List of Tables", false), ("lof", "List of Figures", true)] {
+        if doc.meta.get_bool(key) != Some(true) {
+            continue;
+        }
+        let mut entries = Vec::new();
+        doc.walk_blocks(|block| {
+            let caption = match block {
+                Block::Table(table) if !figures => &table.caption,
+                Block::Figure(_, caption, _) if figures => caption,
+                _ => return,
+            };
+            let entry = caption.short.clone().unwrap_or_else(|| caption_inlines(caption));
+            if !entry.is_empty() {
+                entries.push(entry);
+            }
+        });
+        lists.push((key, title, entries));
+    }
+    lists
+}
+
+/// The inlines of a caption's paragraphs, separated by spaces.
+pub(crate) fn caption_inlines(caption: &Caption) -> Vec<Inline> {
+    let mut inlines = Vec::new();
+    for block in &caption.long {
+        if let Block::Para(content) | Block::Plain(content) = block {
+            if !inlines.is_empty() {
+                inlines.push(Inline::Space);
+            }
+            inlines.extend(content.iter().cloned());
+        }
+    }
+    inlines
+}
+
+/// "Tasks: 3/5 complete (60%)" for a document with task-list items.
+pub(crate) fn task_summary(doc: &Document) -> Option<String> {
+    let (done, total) = doc.task_counts();
+    if total == 0 {
+        return None;
+    }
+    let percent = (done * 100 + total / 2) / total;
+    Some(format!("Tasks: {done}/{total} complete ({percent}%)"))
+}
+
+/// Opening and closing quotation marks for a document language (`lang`
+/// metadata such as `de` or `fr-CA`). English curly quotes are the default.
+pub(crate) fn quote_marks(lang: Option<&str>, kind: &QuoteType) -> (&'static str, &'static str) {
+    let primary = lang
+        .and_then(|l| l.split(['-', '_']).next())
+        .map(str::to_ascii_lowercase);
+    match (primary.as_deref(), kind) {
+        (Some("de"), QuoteType::DoubleQuote) => ("\u{201E}", "\u{201C}"),
+        (Some("de"), QuoteType::SingleQuote) => ("\u{201A}", "\u{2018}"),
+        (Some("fr"), QuoteType::DoubleQuote) => ("\u{AB}\u{A0}", "\u{A0}\u{BB}"),
+        (Some("fr"), QuoteType::SingleQuote) => ("\u{2039}\u{A0}", "\u{A0}\u{203A}"),
+        (_, QuoteType::DoubleQuote) => ("\u{201C}", "\u{201D}"),
+        (_, QuoteType::SingleQuote) => ("\u{2018}", "\u{2019}"),
+    }
+}
+
+/// If `block` is a blockquote attribution line (`— Author` or `-- Author`),
+/// return its inlines with the leading dash removed.
+pub(crate) fn attribution_line(block: &Block) -> Option<Vec<Inline>> {
+    let (Block::Para(inlines) | Block::Plain(inlines)) = block else {
+        return None;
+    };
+    let Some(Inline::Str(first)) = inlines.first() else {
+        return None;
+    };
+    let rest = ["\u{2014}", "\u{2015}", "--"]
+        .iter()
+        .find_map(|dash| first.strip_prefix(dash))?
+        .trim_start_matches('-')
+        .trim_start();
+
+    // A bare dash is followed by a space before the name; drop it
+    let mut result = Vec::with_capacity(inlines.len());
+    let tail = inlines[1..]
+        .iter()
+        .skip_while(|i| rest.is_empty() && matches!(i, Inline::Space | Inline::SoftBreak));
+    if !rest.is_empty() {
+        result.push(Inline::Str(rest.to_string()));
+    }
+    result.extend(tail.cloned());
+    (!result.is_empty()).then_some(result)
+}
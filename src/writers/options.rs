@@ -0,0 +1,131 @@
+/// How TeX math is rendered in HTML.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MathMethod {
+    /// Keep `\(...\)` / `\[...\]` delimiters for a JavaScript renderer.
+    #[default]
+    Tex,
+    /// Convert to MathML at conversion time, falling back to TeX for
+    /// unsupported input.
+    MathMl,
+}
+
+/// Layout of the HTML source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HtmlFormat {
+    /// One block per line, as the writer emits it.
+    #[default]
+    Compact,
+    /// Nested block elements indented two spaces per level.
+    Pretty,
+    /// Whitespace between block elements removed.
+    Minify,
+}
+
+/// A font file embedded in HTML output as an `@font-face` for the main font.
+#[derive(Debug, Clone)]
+pub struct EmbeddedFont {
+    /// The `@font-face` `format()` hint (`woff2`, `woff`, `truetype`,
+    /// `opentype`).
+    pub format: String,
+    /// MIME type of the `data:` URI (`font/woff2`, `font/ttf`, ...).
+    pub mime: String,
+    /// The font file contents.
+    pub data: Vec<u8>,
+}
+
+impl EmbeddedFont {
+    /// A font from a file with extension `ext` (any case). `None` when the
+    /// extension is not a web font format browsers load.
+    pub fn from_extension(ext: &str, data: Vec<u8>) -> Option<EmbeddedFont> {
+        let (format, mime) = match ext.to_ascii_lowercase().as_str() {
+            "woff2" => ("woff2", "font/woff2"),
+            "woff" => ("woff", "font/woff"),
+            "ttf" => ("truetype", "font/ttf"),
+            "otf" => ("opentype", "font/otf"),
+            _ => return None,
+        };
+        Some(EmbeddedFont { format: format.to_string(), mime: mime.to_string(), data })
+    }
+}
+
+/// Rendering options shared by all writers. Writers ignore options that do
+/// not apply to their format.
+#[derive(Debug, Clone)]
+pub struct WriterOptions {
+    /// Class prefix for code block languages in HTML (`language-` by
+    /// default). An empty prefix emits the bare language name.
+    pub highlight_prefix: String,
+    /// How to render math in HTML.
+    pub math: MathMethod,
+    /// Render a trailing `— Author` blockquote paragraph as a citation.
+    pub cite_attribution: bool,
+    /// Drop `<!-- ... -->` comments from raw HTML.
+    pub strip_comments: bool,
+    /// Make HTML safe to embed from untrusted Markdown: drop raw HTML and
+    /// `on*` event-handler attributes, and replace `javascript:`,
+    /// `vbscript:` and non-image `data:` URLs with `#`. Front matter `css`
+    /// is left out of the stylesheet.
+    pub sanitize: bool,
+    /// Let Word auto-fit DOCX tables instead of fixed equal-width columns.
+    pub fit_tables: bool,
+    /// Append a "Tasks: 3/5 complete (60%)" line summarizing the
+    /// document's task-list items.
+    pub task_summary: bool,
+    /// Emit tab characters in code blocks as tabs (DOCX tab runs) instead
+    /// of expanding them to spaces.
+    pub preserve_tabs: bool,
+    /// Columns between tab stops when expanding tabs in code blocks.
+    pub tab_stop: usize,
+    /// Font file to embed in the HTML stylesheet so the output renders the
+    /// same without the font installed.
+    pub embed_font: Option<EmbeddedFont>,
+    /// Layout of the HTML source. `<pre>` content is never reformatted.
+    pub html_format: HtmlFormat,
+    /// Write non-ASCII characters in HTML as numeric entities (`&#160;`)
+    /// instead of raw UTF-8.
+    pub numeric_entities: bool,
+    /// Write HTML as pure ASCII: numeric entities as with `numeric_entities`,
+    /// plus escapes in stylesheets, scripts and comments.
+    pub ascii: bool,
+    /// Put the DOCX title, subtitle, author and date on a cover page of
+    /// their own, followed by a page break. Also set by `coverpage: true`.
+    pub cover_page: bool,
+    /// Leave out the visible title, subtitle, author and date block. The
+    /// metadata is still used elsewhere, such as the HTML `<title>`.
+    pub strip_metadata_block: bool,
+    /// Contents copied verbatim to the start of the body, in order: raw HTML
+    /// in HTML, plain paragraphs (one per non-blank line) in DOCX.
+    pub include_before_body: Vec<String>,
+    /// Contents copied verbatim to the end of the body, like
+    /// `include_before_body`.
+    pub include_after_body: Vec<String>,
+    /// Prefix for every id in the HTML output (headings, footnotes,
+    /// figures) and for the `#` links that point at them, so several
+    /// converted documents can share one page.
+    pub id_prefix: String,
+}
+
+impl Default for WriterOptions {
+    fn default() -> Self {
+        Self {
+            highlight_prefix: "language-".to_string(),
+            math: MathMethod::Tex,
+            cite_attribution: false,
+            strip_comments: false,
+            sanitize: false,
+            fit_tables: false,
+            task_summary: false,
+            preserve_tabs: false,
+            tab_stop: 4,
+            embed_font: None,
+            html_format: HtmlFormat::Compact,
+            numeric_entities: false,
+            ascii: false,
+            cover_page: false,
+            strip_metadata_block: false,
+            include_before_body: Vec::new(),
+            include_after_body: Vec::new(),
+            id_prefix: String::new(),
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use pandorust::ast::*;
+use pandorust::readers::asciidoc::read_asciidoc;
+
+#[test]
+fn test_asciidoc_header_and_headings() {
+    let adoc = "\
+= Project Notes
+Jane Doe <jane@example.com>
+:revdate: 2026-03-01
+
+== Overview
+
+Some *bold* and _italic_ text with `code`.
+
+=== Details
+
+* First
+* Second
+
+. One
+. Two
+
+[source,rust]
+----
+fn main() {}
+----
+";
+    let doc = read_asciidoc(adoc).unwrap();
+    assert_eq!(doc.meta.title(), Some("Project Notes"));
+    assert_eq!(doc.meta.author(), Some("Jane Doe"));
+    assert_eq!(doc.meta.date(), Some("2026-03-01"));
+
+    assert_eq!(
+        doc.blocks[0],
+        Block::Heading(Attr::empty(), 1, vec![Inline::Str("Overview".into())])
+    );
+    assert_eq!(
+        doc.blocks[1],
+        Block::Para(vec![
+            Inline::Str("Some ".into()),
+            Inline::Strong(vec![Inline::Str("bold".into())]),
+            Inline::Str(" and ".into()),
+            Inline::Emph(vec![Inline::Str("italic".into())]),
+            Inline::Str(" text with ".into()),
+            Inline::Code(Attr::empty(), "code".into()),
+            Inline::Str(".".into()),
+        ])
+    );
+    assert!(matches!(&doc.blocks[2], Block::Heading(_, 2, _)));
+    assert!(matches!(&doc.blocks[3], Block::BulletList(items) if items.len() == 2));
+    assert!(matches!(&doc.blocks[4], Block::OrderedList(_, items) if items.len() == 2));
+    match &doc.blocks[5] {
+        Block::CodeBlock(attr, code) => {
+            assert_eq!(attr.classes, vec!["rust"]);
+            assert_eq!(code, "fn main() {}\n");
+        }
+        other => panic!("Expected CodeBlock, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_asciidoc_table() {
+    let adoc = "\
+|===
+| Name | Value
+
+| foo | 42
+| bar
+| 7
+|===
+";
+    let doc = read_asciidoc(adoc).unwrap();
+    match &doc.blocks[0] {
+        Block::Table(table) => {
+            assert_eq!(table.col_specs.len(), 2);
+            assert_eq!(table.head.rows.len(), 1);
+            assert_eq!(table.bodies[0].body.len(), 2);
+            assert_eq!(
+                table.bodies[0].body[1].cells[1].content,
+                vec![Block::Plain(vec![Inline::Str("7".into())])]
+            );
+        }
+        other => panic!("Expected Table, got {:?}", other),
+    }
+
+    // Without a blank line after the first row there is no header
+    let doc = read_asciidoc("|===\n| a | b\n| c | d\n|===\n").unwrap();
+    match &doc.blocks[0] {
+        Block::Table(table) => {
+            assert!(table.head.rows.is_empty());
+            assert_eq!(table.bodies[0].body.len(), 2);
+        }
+        other => panic!("Expected Table, got {:?}", other),
+    }
+}
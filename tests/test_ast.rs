@@ -140,3 +140,105 @@ fn test_page_break_block() {
     let block = Block::PageBreak;
     assert!(matches!(block, Block::PageBreak));
 }
+
+#[test]
+fn test_meta_to_table() {
+    let mut entries = HashMap::new();
+    entries.insert("title".to_string(), MetaValue::String("Doc".to_string()));
+    entries.insert(
+        "tags".to_string(),
+        MetaValue::List(vec![
+            MetaValue::String("a".to_string()),
+            MetaValue::String("b".to_string()),
+        ]),
+    );
+    let mut nested = HashMap::new();
+    nested.insert("name".to_string(), MetaValue::String("Acme".to_string()));
+    nested.insert("draft".to_string(), MetaValue::Bool(true));
+    entries.insert("client".to_string(), MetaValue::Map(nested));
+
+    let table = Meta { entries }.to_table().unwrap();
+    assert_eq!(table.head.rows.len(), 1);
+    let rows = &table.bodies[0].body;
+    assert_eq!(rows.len(), 3);
+    // Sorted by key; nested values are flattened
+    let value = |row: &Row| match &row.cells[1].content[0] {
+        Block::Plain(inlines) => match &inlines[0] {
+            Inline::Str(s) => s.clone(),
+            other => panic!("unexpected inline {:?}", other),
+        },
+        other => panic!("unexpected block {:?}", other),
+    };
+    assert_eq!(value(&rows[0]), "draft: true, name: Acme");
+    assert_eq!(value(&rows[1]), "a, b");
+    assert_eq!(value(&rows[2]), "Doc");
+
+    assert!(Meta::default().to_table().is_none());
+}
+
+#[test]
+fn test_meta_unknown_keys() {
+    let mut entries = HashMap::new();
+    for key in ["title", "titel", "h2-space-after", "h9-space-after", "metadata", "fontsze"] {
+        entries.insert(key.to_string(), MetaValue::String("x".to_string()));
+    }
+    let meta = Meta { entries };
+    assert_eq!(meta.unknown_keys(), vec!["fontsze", "h9-space-after", "titel"]);
+}
+
+#[test]
+fn test_walk_reaches_note_in_nested_table_cell() {
+    // A note in a table cell, in a blockquote, in a list item
+    let note = Inline::Note("deep".into(), vec![Block::Para(vec![Inline::Str("deep".into())])]);
+    let cell = Cell {
+        attr: Attr::empty(),
+        align: Alignment::AlignDefault,
+        row_span: 1,
+        col_span: 1,
+        content: vec![Block::Plain(vec![Inline::Emph(vec![note])])],
+    };
+    let table = Table {
+        attr: Attr::empty(),
+        caption: Caption::default(),
+        col_specs: vec![ColSpec { align: Alignment::AlignDefault, width: ColWidth::Default }],
+        head: TableHead { attr: Attr::empty(), rows: vec![] },
+        bodies: vec![TableBody {
+            attr: Attr::empty(),
+            row_head_columns: 0,
+            head: vec![],
+            body: vec![Row { attr: Attr::empty(), cells: vec![cell] }],
+        }],
+        foot: TableFoot { attr: Attr::empty(), rows: vec![] },
+    };
+    let mut doc = Document {
+        meta: Meta::default(),
+        blocks: vec![Block::BulletList(vec![vec![Block::BlockQuote(vec![Block::Table(table)])]])],
+    };
+
+    let mut notes = 0;
+    let mut text = Vec::new();
+    doc.walk_inlines(|inline| match inline {
+        Inline::Note(..) => notes += 1,
+        Inline::Str(s) => text.push(s.as_str()),
+        _ => {}
+    });
+    assert_eq!(notes, 1);
+    assert_eq!(text, vec!["deep"]);
+
+    let mut tables = 0;
+    doc.walk_blocks(|block| tables += matches!(block, Block::Table(_)) as usize);
+    assert_eq!(tables, 1);
+
+    doc.walk_inlines_mut(|inline| {
+        if let Inline::Str(s) = inline {
+            *s = s.to_uppercase();
+        }
+    });
+    let mut text = Vec::new();
+    doc.walk_inlines(|inline| {
+        if let Inline::Str(s) = inline {
+            text.push(s.clone());
+        }
+    });
+    assert_eq!(text, vec!["DEEP"]);
+}
@@ -1,5 +1,6 @@
 use std::fs;
 use std::io::Write;
+use std::path::Path;
 use std::process::{Command, Stdio};
 use tempfile::TempDir;
 
@@ -8,6 +9,16 @@ fn pandorust_cmd() -> Command {
     Command::new(env!("CARGO_BIN_EXE_pandorust"))
 }
 
+/// The `word/document.xml` part of a DOCX file.
+fn document_xml(docx: &Path) -> String {
+    use std::io::Read;
+
+    let mut archive = zip::ZipArchive::new(fs::File::open(docx).unwrap()).unwrap();
+    let mut xml = String::new();
+    archive.by_name("word/document.xml").unwrap().read_to_string(&mut xml).unwrap();
+    xml
+}
+
 #[test]
 fn test_md_to_html_cli() {
     let tmp = TempDir::new().unwrap();
@@ -150,6 +161,25 @@ fn test_missing_input_file() {
     );
 }
 
+#[test]
+fn test_non_utf8_input_has_clear_error() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("photo.md");
+    let output = tmp.path().join("output.html");
+    fs::write(&input, b"\x89PNG\r\n\x1a\n\xff\xfe binary").unwrap();
+
+    let result = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(output.to_str().unwrap())
+        .output()
+        .expect("failed to execute pandorust");
+    assert!(!result.status.success());
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    assert!(stderr.contains("photo.md: input is not valid UTF-8 text"), "got: {}", stderr);
+    assert!(!output.exists());
+}
+
 #[test]
 fn test_list_formats() {
     let result = pandorust_cmd()
@@ -194,3 +224,996 @@ fn test_stdin_input() {
     assert!(html.contains("From Stdin"), "output should contain stdin content");
     assert!(html.contains("Piped content"), "output should contain piped paragraph");
 }
+
+#[test]
+fn test_highlight_prefix_option() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("input.md");
+    fs::write(&input, "```rust\nlet x = 1;\n```\n").unwrap();
+
+    let bare = tmp.path().join("bare.html");
+    let status = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(bare.to_str().unwrap())
+        .arg("--highlight-prefix=")
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success());
+    let html = fs::read_to_string(&bare).unwrap();
+    assert!(html.contains("<code class=\"rust\">"), "got: {}", html);
+
+    let custom = tmp.path().join("custom.html");
+    let status = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(custom.to_str().unwrap())
+        .arg("--highlight-prefix")
+        .arg("lang-")
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success());
+    let html = fs::read_to_string(&custom).unwrap();
+    assert!(html.contains("<code class=\"lang-rust\">"), "got: {}", html);
+}
+
+#[test]
+fn test_output_format_aliases_and_case() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("input.txt");
+    fs::write(&input, "# Alias\n").unwrap();
+
+    for (i, format) in ["HTML", "htm"].iter().enumerate() {
+        let output = tmp.path().join(format!("out{}.txt", i));
+        let status = pandorust_cmd()
+            .arg(input.to_str().unwrap())
+            .arg("-o")
+            .arg(output.to_str().unwrap())
+            .arg("-f")
+            .arg("GFM")
+            .arg("-t")
+            .arg(format)
+            .status()
+            .expect("failed to execute pandorust");
+        assert!(status.success(), "-t {} should succeed", format);
+        let html = fs::read_to_string(&output).unwrap();
+        assert!(html.contains("<h1"), "-t {} should produce HTML", format);
+    }
+
+    let docx = tmp.path().join("out.bin");
+    let status = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(docx.to_str().unwrap())
+        .arg("-f")
+        .arg("md")
+        .arg("-t")
+        .arg("Word")
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success(), "-t Word should succeed");
+    assert!(fs::read(&docx).unwrap().starts_with(b"PK"));
+}
+
+#[test]
+fn test_add_bom_option() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("input.md");
+    fs::write(&input, "# Café\n").unwrap();
+
+    let plain = tmp.path().join("plain.html");
+    let status = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(plain.to_str().unwrap())
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success());
+    let bytes = fs::read(&plain).unwrap();
+    assert!(bytes.starts_with(b"<!DOCTYPE html>"), "default output must not have a BOM");
+
+    let bom = tmp.path().join("bom.html");
+    let status = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(bom.to_str().unwrap())
+        .arg("--add-bom")
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success());
+    let bytes = fs::read(&bom).unwrap();
+    assert!(bytes.starts_with(&[0xEF, 0xBB, 0xBF]), "expected UTF-8 BOM");
+    assert_eq!(&bytes[3..], &fs::read(&plain).unwrap()[..]);
+}
+
+#[test]
+fn test_math_mathml_option() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("input.md");
+    fs::write(&input, "Square: $x^2$\n").unwrap();
+
+    let output = tmp.path().join("output.html");
+    let status = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(output.to_str().unwrap())
+        .arg("--math=mathml")
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success());
+    let html = fs::read_to_string(&output).unwrap();
+    assert!(html.contains("<math xmlns="), "got: {}", html);
+    assert!(html.contains("<msup><mi>x</mi><mn>2</mn></msup>"), "got: {}", html);
+
+    let default = tmp.path().join("default.html");
+    let status = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(default.to_str().unwrap())
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success());
+    let html = fs::read_to_string(&default).unwrap();
+    assert!(html.contains("\\(x^2\\)"), "got: {}", html);
+}
+
+#[test]
+fn test_show_metadata_table_option() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("input.md");
+    fs::write(
+        &input,
+        "---\ntitle: Report\nauthor: Jane\nproject: Apollo\n---\n\nBody text.\n",
+    )
+    .unwrap();
+
+    let output = tmp.path().join("output.html");
+    let status = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(output.to_str().unwrap())
+        .arg("--show-metadata-table")
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success());
+
+    let html = fs::read_to_string(&output).unwrap();
+    let table_start = html.find("<table>").expect("metadata table missing");
+    let table_end = html[table_start..].find("</table>").unwrap() + table_start;
+    let table = &html[table_start..table_end];
+    // Header row plus one row per key
+    assert_eq!(table.matches("<tr>").count(), 4, "got: {}", table);
+    assert!(table.contains("Apollo"));
+    assert!(table_end < html.find("Body text.").unwrap());
+}
+
+#[test]
+fn test_strip_comments_option() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("input.md");
+    fs::write(
+        &input,
+        "<!-- TODO: block note -->\n\nText <!-- inline note --> here.\n",
+    )
+    .unwrap();
+
+    let kept = tmp.path().join("kept.html");
+    let status = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(kept.to_str().unwrap())
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success());
+    let html = fs::read_to_string(&kept).unwrap();
+    assert!(html.contains("<!-- TODO: block note -->"));
+    assert!(html.contains("<!-- inline note -->"));
+
+    let stripped = tmp.path().join("stripped.html");
+    let status = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(stripped.to_str().unwrap())
+        .arg("--strip-comments")
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success());
+    let html = fs::read_to_string(&stripped).unwrap();
+    assert!(!html.contains("<!--"), "got: {}", html);
+    assert!(html.contains("Text  here."), "got: {}", html);
+}
+
+#[test]
+fn test_no_highlight_option() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("in.md");
+    fs::write(&input, "Say ==hi== now\n").unwrap();
+
+    let output = tmp.path().join("out.html");
+    let status = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(output.to_str().unwrap())
+        .arg("--no-highlight")
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success());
+    let html = fs::read_to_string(&output).unwrap();
+    assert!(html.contains("==hi=="), "got: {}", html);
+    assert!(!html.contains("<mark>"));
+}
+
+#[test]
+fn test_continue_lists_option() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("in.md");
+    fs::write(&input, "1. One\n2. Two\n\nA note.\n\n1. Three\n").unwrap();
+
+    let run = |out: &Path, continued: bool| {
+        let mut cmd = pandorust_cmd();
+        cmd.arg(input.to_str().unwrap()).arg("-o").arg(out.to_str().unwrap());
+        if continued {
+            cmd.arg("--continue-lists");
+        }
+        assert!(cmd.status().expect("failed to execute pandorust").success());
+    };
+
+    let plain = tmp.path().join("plain.html");
+    run(&plain, false);
+    assert!(!fs::read_to_string(&plain).unwrap().contains("start="));
+
+    let html = tmp.path().join("continued.html");
+    run(&html, true);
+    let html = fs::read_to_string(&html).unwrap();
+    assert!(html.contains("<ol start=\"3\">\n<li>Three</li>"), "got: {}", html);
+
+    let docx = tmp.path().join("continued.docx");
+    run(&docx, true);
+    let xml = document_xml(&docx);
+    assert!(xml.contains(">3.</w:t><w:tab />"), "got: {}", xml);
+    assert!(xml.contains(">Three</w:t>"), "got: {}", xml);
+}
+
+#[test]
+fn test_fit_tables_option() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("in.md");
+    fs::write(&input, "| A | B |\n|---|---|\n| 1 | 2 |\n").unwrap();
+
+    let table_xml = |name: &str, fit: bool| {
+        let output = tmp.path().join(name);
+        let mut cmd = pandorust_cmd();
+        cmd.arg(input.to_str().unwrap()).arg("-o").arg(output.to_str().unwrap());
+        if fit {
+            cmd.arg("--fit-tables");
+        }
+        assert!(cmd.status().expect("failed to execute pandorust").success());
+        document_xml(&output)
+    };
+
+    let fixed = table_xml("fixed.docx", false);
+    assert!(!fixed.contains("w:tblLayout w:type=\"autofit\""));
+    assert!(fixed.contains("w:type=\"dxa\""));
+
+    let fit = table_xml("fit.docx", true);
+    assert!(fit.contains("<w:tblLayout w:type=\"autofit\""), "got: {}", fit);
+    assert!(fit.contains("<w:tblW w:w=\"5000\" w:type=\"pct\""), "got: {}", fit);
+}
+
+#[test]
+fn test_strict_metadata_option() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("in.md");
+    let output = tmp.path().join("out.html");
+    fs::write(&input, "---\ntitel: X\n---\n\nBody.\n").unwrap();
+
+    let stderr = |strict: bool| {
+        let mut cmd = pandorust_cmd();
+        cmd.arg(input.to_str().unwrap()).arg("-o").arg(output.to_str().unwrap());
+        if strict {
+            cmd.arg("--strict-metadata");
+        }
+        let out = cmd.output().expect("failed to execute pandorust");
+        assert!(out.status.success());
+        String::from_utf8(out.stderr).unwrap()
+    };
+
+    assert!(stderr(true).contains("unknown front matter key 'titel'"));
+    assert!(stderr(false).is_empty());
+}
+
+#[test]
+fn test_dump_ast_option() {
+    let mut child = pandorust_cmd()
+        .arg("-")
+        .arg("-f")
+        .arg("md")
+        .arg("--dump-ast")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to spawn pandorust");
+
+    child.stdin.take().unwrap().write_all(b"# Intro\n").unwrap();
+    let out = child.wait_with_output().unwrap();
+    assert!(out.status.success(), "--dump-ast needs no output file");
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("Heading("), "got: {}", stdout);
+    assert!(stdout.contains("\"Intro\""), "got: {}", stdout);
+}
+
+#[test]
+fn test_smart_option() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("in.md");
+    fs::write(&input, "I don't know `it's` code.\n").unwrap();
+
+    let output = tmp.path().join("out.html");
+    let status = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(output.to_str().unwrap())
+        .arg("--smart")
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success());
+    let html = fs::read_to_string(&output).unwrap();
+    assert!(html.contains("don\u{2019}t"), "got: {}", html);
+    assert!(html.contains("<code>it&#39;s</code>"), "code spans are untouched");
+}
+
+#[test]
+fn test_require_alt_option() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("in.md");
+    let output = tmp.path().join("out.html");
+    fs::write(&input, "![](chart.png)\n\n![Logo](logo.png)\n").unwrap();
+
+    let stderr = |require: bool| {
+        let mut cmd = pandorust_cmd();
+        cmd.arg(input.to_str().unwrap()).arg("-o").arg(output.to_str().unwrap());
+        if require {
+            cmd.arg("--require-alt");
+        }
+        let out = cmd.output().expect("failed to execute pandorust");
+        assert!(out.status.success());
+        String::from_utf8(out.stderr).unwrap()
+    };
+
+    let warnings = stderr(true);
+    assert!(warnings.contains("image 'chart.png' has no alt text"), "got: {}", warnings);
+    assert!(!warnings.contains("logo.png"));
+    assert!(stderr(false).is_empty());
+
+    // Warnings are reported the same way when only dumping the AST
+    let out = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("--dump-ast")
+        .arg("--require-alt")
+        .output()
+        .expect("failed to execute pandorust");
+    assert!(out.status.success());
+    let warnings = String::from_utf8(out.stderr).unwrap();
+    assert_eq!(warnings, "Warning: image 'chart.png' has no alt text\n");
+}
+
+#[test]
+fn test_asciidoc_input_by_extension() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("notes.adoc");
+    let output = tmp.path().join("notes.html");
+    fs::write(&input, "= Notes\n\n== Intro\n\nHello *world*.\n").unwrap();
+
+    let status = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(output.to_str().unwrap())
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success());
+    let html = fs::read_to_string(&output).unwrap();
+    assert!(html.contains("<title>Notes</title>"), "got: {}", html);
+    assert!(html.contains("<strong>world</strong>"));
+}
+
+#[test]
+fn test_criticmarkup_option() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("in.md");
+    fs::write(&input, "Keep {++added++} and {--removed--}.\n").unwrap();
+
+    let convert = |name: &str, critic: bool| {
+        let output = tmp.path().join(name);
+        let mut cmd = pandorust_cmd();
+        cmd.arg(input.to_str().unwrap()).arg("-o").arg(output.to_str().unwrap());
+        if critic {
+            cmd.arg("--criticmarkup");
+        }
+        assert!(cmd.status().expect("failed to execute pandorust").success());
+        output
+    };
+
+    let html = fs::read_to_string(convert("on.html", true)).unwrap();
+    assert!(html.contains("<ins>added</ins>"), "got: {}", html);
+    assert!(html.contains("<del>removed</del>"), "got: {}", html);
+    let html = fs::read_to_string(convert("off.html", false)).unwrap();
+    assert!(html.contains("{++added++}"), "off by default: {}", html);
+
+    let docx = convert("on.docx", true);
+    let xml = document_xml(&docx);
+    let pos = xml.find(">added</w:t>").expect("insertion text missing");
+    let run = &xml[xml[..pos].rfind("<w:r>").unwrap()..pos];
+    assert!(run.contains("<w:u w:val=\"single\"") && run.contains("<w:color w:val=\"008000\""), "got: {}", run);
+}
+
+#[test]
+fn test_enable_includes() {
+    let tmp = TempDir::new().unwrap();
+    fs::create_dir(tmp.path().join("chapters")).unwrap();
+    fs::write(
+        tmp.path().join("main.md"),
+        "---\ntitle: Manual\n---\n\nIntro.\n\n!include chapters/one.md\n\nOutro.\n",
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join("chapters/one.md"),
+        "---\ntitle: Ignored\n---\n\n# Chapter One\n\n!include two.md\n",
+    )
+    .unwrap();
+    fs::write(tmp.path().join("chapters/two.md"), "Nested text.\n").unwrap();
+
+    let output = tmp.path().join("out.html");
+    let status = pandorust_cmd()
+        .arg(tmp.path().join("main.md").to_str().unwrap())
+        .arg("-o")
+        .arg(output.to_str().unwrap())
+        .arg("--enable-includes")
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success());
+    let html = fs::read_to_string(&output).unwrap();
+    assert!(html.contains("<title>Manual</title>"), "got: {}", html);
+    assert!(!html.contains("Ignored"));
+    assert!(html.contains("Chapter One</h1>"), "got: {}", html);
+    assert!(html.contains("<p>Nested text.</p>"), "got: {}", html);
+    let nested = html.find("Nested text.");
+    assert!(html.find("Intro.") < nested && nested < html.find("Outro."));
+
+    // Off by default: the directive stays text
+    let status = pandorust_cmd()
+        .arg(tmp.path().join("main.md").to_str().unwrap())
+        .arg("-o")
+        .arg(output.to_str().unwrap())
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success());
+    assert!(fs::read_to_string(&output).unwrap().contains("!include chapters/one.md"));
+}
+
+#[test]
+fn test_include_cycle_errors() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(tmp.path().join("a.md"), "A\n\n!include b.md\n").unwrap();
+    fs::write(tmp.path().join("b.md"), "B\n\n!include a.md\n").unwrap();
+
+    let result = pandorust_cmd()
+        .arg(tmp.path().join("a.md").to_str().unwrap())
+        .arg("-o")
+        .arg(tmp.path().join("out.html").to_str().unwrap())
+        .arg("--enable-includes")
+        .output()
+        .expect("failed to execute pandorust");
+    assert!(!result.status.success());
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    assert!(stderr.contains("include cycle"), "got: {}", stderr);
+    assert!(!tmp.path().join("out.html").exists());
+}
+
+#[test]
+fn test_emoji_option() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("in.md");
+    fs::write(&input, "Looks good :+1: but :notanemoji: and `:tada:`\n").unwrap();
+
+    let convert = |emoji: bool| {
+        let output = tmp.path().join("out.html");
+        let mut cmd = pandorust_cmd();
+        cmd.arg(input.to_str().unwrap()).arg("-o").arg(output.to_str().unwrap());
+        if emoji {
+            cmd.arg("--emoji");
+        }
+        assert!(cmd.status().expect("failed to execute pandorust").success());
+        fs::read_to_string(&output).unwrap()
+    };
+
+    let html = convert(true);
+    assert!(html.contains("Looks good \u{1F44D} but :notanemoji:"), "got: {}", html);
+    assert!(html.contains("<code>:tada:</code>"), "code spans are untouched: {}", html);
+    let html = convert(false);
+    assert!(html.contains("Looks good :+1:"), "off by default: {}", html);
+}
+
+#[test]
+fn test_preserve_tabs_option() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("in.md");
+    fs::write(&input, "```\nif x {\n\treturn;\n}\n```\n").unwrap();
+
+    let convert = |name: &str, args: &[&str]| {
+        let output = tmp.path().join(name);
+        let status = pandorust_cmd()
+            .arg(input.to_str().unwrap())
+            .arg("-o")
+            .arg(output.to_str().unwrap())
+            .args(args)
+            .status()
+            .expect("failed to execute pandorust");
+        assert!(status.success());
+        output
+    };
+
+    let html = fs::read_to_string(convert("default.html", &[])).unwrap();
+    assert!(html.contains("\n    return;"), "tabs expand to 4 spaces: {}", html);
+    let html = fs::read_to_string(convert("stop.html", &["--tab-stop", "2"])).unwrap();
+    assert!(html.contains("\n  return;"), "got: {}", html);
+    let html = fs::read_to_string(convert("tabs.html", &["--preserve-tabs"])).unwrap();
+    assert!(html.contains("\n\treturn;"), "got: {}", html);
+
+    let docx = convert("tabs.docx", &["--preserve-tabs"]);
+    let xml = document_xml(&docx);
+    assert!(xml.contains("<w:tab />"), "got: {}", xml);
+}
+
+#[test]
+fn test_markdown_extension_variants() {
+    let tmp = TempDir::new().unwrap();
+    for ext in ["markdown", "mkd", "mdown", "mkdn"] {
+        let input = tmp.path().join(format!("doc.{}", ext));
+        let output = tmp.path().join(format!("{}.html", ext));
+        fs::write(&input, "# Title\n\nBody *text*.\n").unwrap();
+
+        let status = pandorust_cmd()
+            .arg(input.to_str().unwrap())
+            .arg("-o")
+            .arg(output.to_str().unwrap())
+            .status()
+            .expect("failed to execute pandorust");
+        assert!(status.success(), ".{} should be read as Markdown", ext);
+        let html = fs::read_to_string(&output).unwrap();
+        assert!(html.contains("<h1 id=\"title\">Title</h1>"), "got: {}", html);
+    }
+}
+
+#[test]
+fn test_embed_fonts_option() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("in.md");
+    let font = tmp.path().join("x.woff2");
+    let output = tmp.path().join("out.html");
+    fs::write(&input, "---\nmainfont: Brand Sans\n---\n\nText\n").unwrap();
+    fs::write(&font, b"wOF2font").unwrap();
+
+    let status = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(output.to_str().unwrap())
+        .arg("--embed-fonts")
+        .arg("--font-file")
+        .arg(font.to_str().unwrap())
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success());
+    let html = fs::read_to_string(&output).unwrap();
+    assert!(
+        html.contains("@font-face { font-family: \"Brand Sans\"; src: url(\"data:font/woff2;base64,d09GMmZvbnQ=\") format(\"woff2\"); }"),
+        "got: {}",
+        html
+    );
+
+    // The flag needs a font file
+    let status = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(output.to_str().unwrap())
+        .arg("--embed-fonts")
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(!status.success());
+}
+
+#[test]
+fn test_embed_fonts_format_hints() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("in.md");
+    let output = tmp.path().join("out.html");
+    fs::write(&input, "Text\n").unwrap();
+
+    let embed = |name: &str| {
+        let font = tmp.path().join(name);
+        fs::write(&font, b"font").unwrap();
+        pandorust_cmd()
+            .arg(input.to_str().unwrap())
+            .arg("-o")
+            .arg(output.to_str().unwrap())
+            .arg("--embed-fonts")
+            .arg("--font-file")
+            .arg(font.to_str().unwrap())
+            .output()
+            .expect("failed to execute pandorust")
+    };
+
+    // The CSS hint is "truetype", not the file extension
+    assert!(embed("x.ttf").status.success());
+    let html = fs::read_to_string(&output).unwrap();
+    assert!(html.contains("url(\"data:font/ttf;base64,Zm9udA==\") format(\"truetype\")"), "got: {}", html);
+
+    assert!(embed("x.OTF").status.success());
+    let html = fs::read_to_string(&output).unwrap();
+    assert!(html.contains("url(\"data:font/otf;base64,Zm9udA==\") format(\"opentype\")"), "got: {}", html);
+
+    let result = embed("x.svg");
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("unsupported font file"));
+}
+
+#[test]
+fn test_resource_path_option() {
+    let tmp = TempDir::new().unwrap();
+    let docs = tmp.path().join("docs");
+    let assets = tmp.path().join("assets");
+    fs::create_dir(&docs).unwrap();
+    fs::create_dir(&assets).unwrap();
+    fs::write(assets.join("logo.png"), b"\x89PNG").unwrap();
+    fs::write(assets.join("part.md"), "Shared *part*.\n").unwrap();
+    let input = docs.join("in.md");
+    fs::write(
+        &input,
+        "![Logo](logo.png)\n\n![Chart](missing.png)\n\n![Remote](https://example.com/a.png)\n\n!include part.md\n",
+    )
+    .unwrap();
+
+    let output = tmp.path().join("out.html");
+    let result = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(output.to_str().unwrap())
+        .arg("--enable-includes")
+        .arg("--resource-path")
+        .arg(assets.to_str().unwrap())
+        .output()
+        .expect("failed to execute pandorust");
+    assert!(result.status.success(), "stderr: {}", String::from_utf8_lossy(&result.stderr));
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    assert!(stderr.contains("could not find image 'missing.png'"), "got: {}", stderr);
+    assert!(!stderr.contains("logo.png"), "found via resource path: {}", stderr);
+    assert!(!stderr.contains("example.com"), "remote images are not checked: {}", stderr);
+    let html = fs::read_to_string(&output).unwrap();
+    assert!(html.contains("<p>Shared <em>part</em>.</p>"), "got: {}", html);
+}
+
+#[test]
+fn test_multiple_outputs() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("in.md");
+    let html = tmp.path().join("out.html");
+    let docx = tmp.path().join("out.docx");
+    fs::write(&input, "# Title\n\nBody\n").unwrap();
+
+    let status = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(html.to_str().unwrap())
+        .arg("-o")
+        .arg(docx.to_str().unwrap())
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success());
+    assert!(fs::read_to_string(&html).unwrap().contains("Title</h1>"));
+    assert!(fs::read(&docx).unwrap().starts_with(b"PK"));
+
+    // Two outputs of the same format are rejected
+    let output = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(html.to_str().unwrap())
+        .arg("-o")
+        .arg(tmp.path().join("copy.htm").to_str().unwrap())
+        .output()
+        .expect("failed to execute pandorust");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("both html"));
+}
+
+#[test]
+fn test_commonmark_option() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("in.md");
+    fs::write(&input, "| A | B |\n|---|---|\n| 1 | 2 |\n").unwrap();
+
+    let convert = |name: &str, flags: &[&str]| {
+        let output = tmp.path().join(name);
+        let status = pandorust_cmd()
+            .arg(input.to_str().unwrap())
+            .arg("-o")
+            .arg(output.to_str().unwrap())
+            .args(flags)
+            .status()
+            .expect("failed to execute pandorust");
+        assert!(status.success());
+        fs::read_to_string(output).unwrap()
+    };
+
+    let html = convert("strict.html", &["--commonmark"]);
+    assert!(html.contains("<p>| A | B |"), "got: {}", html);
+    assert!(!html.contains("<table"));
+    let html = convert("from.html", &["-f", "commonmark"]);
+    assert!(!html.contains("<table"), "got: {}", html);
+    let html = convert("gfm.html", &["--gfm"]);
+    assert!(html.contains("<table"), "got: {}", html);
+}
+
+#[test]
+fn test_html_format_option() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("in.md");
+    fs::write(&input, "- one\n- two\n\n```\nkeep\n  this\n```\n").unwrap();
+
+    let convert = |name: &str, format: &str| {
+        let output = tmp.path().join(name);
+        let status = pandorust_cmd()
+            .arg(input.to_str().unwrap())
+            .arg("-o")
+            .arg(output.to_str().unwrap())
+            .arg(format!("--html-format={}", format))
+            .status()
+            .expect("failed to execute pandorust");
+        assert!(status.success());
+        fs::read_to_string(output).unwrap()
+    };
+
+    let html = convert("pretty.html", "pretty");
+    assert!(html.contains("\n  <body>\n    <ul>\n      <li>one</li>\n"), "got: {}", html);
+    assert!(html.contains("<pre><code>keep\n  this\n</code></pre>"), "got: {}", html);
+
+    let html = convert("min.html", "minify");
+    assert!(html.contains("<body><ul><li>one</li><li>two</li></ul><pre><code>keep\n  this\n</code></pre></body></html>"), "got: {}", html);
+    let body = &html[html.find("<body>").unwrap()..];
+    assert_eq!(body.matches('\n').count(), 2, "only the <pre> keeps newlines: {}", body);
+
+    let html = convert("compact.html", "compact");
+    assert!(html.contains("<ul>\n<li>one</li>\n"), "got: {}", html);
+}
+
+#[test]
+fn test_no_indented_code_option() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("in.md");
+    let output = tmp.path().join("out.html");
+    fs::write(&input, "Intro\n\n    Indented for readability\n").unwrap();
+
+    let status = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(output.to_str().unwrap())
+        .arg("--no-indented-code")
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success());
+    let html = fs::read_to_string(&output).unwrap();
+    assert!(html.contains("<p>Indented for readability</p>"), "got: {}", html);
+    assert!(!html.contains("<pre>"));
+}
+
+#[test]
+fn test_parse_raw_html_tables_option() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("in.md");
+    fs::write(
+        &input,
+        "Before\n\n<table>\n<tr><th>Name</th><th>Qty</th></tr>\n<tr><td>Apples</td><td><b>3</b></td></tr>\n</table>\n",
+    )
+    .unwrap();
+
+    let convert = |name: &str, parse: bool| {
+        let output = tmp.path().join(name);
+        let mut cmd = pandorust_cmd();
+        cmd.arg(input.to_str().unwrap()).arg("-o").arg(output.to_str().unwrap());
+        if parse {
+            cmd.arg("--parse-raw-html-tables");
+        }
+        assert!(cmd.status().expect("failed to execute pandorust").success());
+        document_xml(&output)
+    };
+
+    let xml = convert("raw.docx", false);
+    assert!(!xml.contains("<w:tbl>"), "raw HTML is dropped by default: {}", xml);
+
+    let xml = convert("parsed.docx", true);
+    assert!(xml.contains("<w:tbl>"), "got: {}", xml);
+    assert!(xml.contains(">Apples</w:t>"), "got: {}", xml);
+    assert!(xml.contains("<w:b />") && xml.contains(">3</w:t>"), "got: {}", xml);
+}
+
+#[test]
+fn test_help_lists_input_formats() {
+    let output = pandorust_cmd().arg("--help").output().expect("failed to execute pandorust");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Converts Markdown or AsciiDoc"), "got: {}", stdout);
+    assert!(stdout.contains("asciidoc (adoc, asc)"), "got: {}", stdout);
+}
+
+#[test]
+fn test_metadata_file() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("in.md");
+    let meta = tmp.path().join("meta.yaml");
+    let json = tmp.path().join("meta.json");
+    let output = tmp.path().join("out.html");
+    fs::write(&input, "Body text.\n").unwrap();
+    fs::write(&meta, "title: Shared Title\nauthor: Ada\n").unwrap();
+    fs::write(&json, "{\"title\": \"JSON Title\"}").unwrap();
+
+    let convert = |input: &Path, meta: &Path| {
+        let status = pandorust_cmd()
+            .arg(input.to_str().unwrap())
+            .arg("-o")
+            .arg(output.to_str().unwrap())
+            .arg("--metadata-file")
+            .arg(meta.to_str().unwrap())
+            .status()
+            .expect("failed to execute pandorust");
+        assert!(status.success());
+        fs::read_to_string(&output).unwrap()
+    };
+
+    let html = convert(&input, &meta);
+    assert!(html.contains("<title>Shared Title</title>"), "got: {}", html);
+    assert!(html.contains("Ada"), "got: {}", html);
+    let html = convert(&input, &json);
+    assert!(html.contains("<title>JSON Title</title>"), "got: {}", html);
+
+    // Front matter overrides the file
+    fs::write(&input, "---\ntitle: Own Title\n---\n\nBody text.\n").unwrap();
+    let html = convert(&input, &meta);
+    assert!(html.contains("<title>Own Title</title>"), "got: {}", html);
+    assert!(html.contains("Ada"), "other keys still come from the file: {}", html);
+
+    // Substitutions run once, whether or not a file is given
+    fs::write(&input, "---\nsubstitutions:\n  a: A\n  b: \"{{a}}\"\n---\n\nValue {{b}}.\n").unwrap();
+    let html = convert(&input, &meta);
+    assert!(html.contains("<p>Value {{a}}.</p>"), "got: {}", html);
+
+    // File substitutions apply to AsciiDoc input too
+    let adoc = tmp.path().join("in.adoc");
+    fs::write(&adoc, "= Doc\n\nMade by {{org}}.\n").unwrap();
+    fs::write(&meta, "substitutions:\n  org: Acme\n").unwrap();
+    let html = convert(&adoc, &meta);
+    assert!(html.contains("<p>Made by Acme.</p>"), "got: {}", html);
+}
+
+#[test]
+fn test_include_before_and_after_body() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("in.md");
+    let before = tmp.path().join("before.html");
+    let after = tmp.path().join("after.html");
+    let output = tmp.path().join("out.html");
+    fs::write(&input, "# First\n\nLast paragraph.\n").unwrap();
+    fs::write(&before, "<div class=\"banner\">Draft</div>").unwrap();
+    fs::write(&after, "<footer>CC BY 4.0</footer>\n").unwrap();
+
+    let status = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(output.to_str().unwrap())
+        .arg("--include-before-body")
+        .arg(before.to_str().unwrap())
+        .arg("--include-after-body")
+        .arg(after.to_str().unwrap())
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success());
+    let html = fs::read_to_string(&output).unwrap();
+    assert!(html.contains("<body>\n<div class=\"banner\">Draft</div>\n<h1"), "got: {}", html);
+    assert!(html.contains("<p>Last paragraph.</p>\n<footer>CC BY 4.0</footer>\n</body>"), "got: {}", html);
+}
+
+#[test]
+fn test_id_prefix_option() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("in.md");
+    let output = tmp.path().join("out.html");
+    fs::write(&input, "# Intro\n\nText[^1].\n\n[^1]: A note.\n").unwrap();
+
+    let status = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(output.to_str().unwrap())
+        .arg("--id-prefix")
+        .arg("doc1-")
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success());
+    let html = fs::read_to_string(&output).unwrap();
+    assert!(html.contains("id=\"doc1-intro\""), "got: {}", html);
+    assert!(html.contains("href=\"#doc1-fn1\""), "got: {}", html);
+    assert!(html.contains("<li id=\"doc1-fn1\">"), "got: {}", html);
+}
+
+#[test]
+fn test_strip_metadata_block_keeps_title_tag() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("in.md");
+    let output = tmp.path().join("out.html");
+    fs::write(&input, "---\ntitle: Report\nauthor: Ana\n---\n\nBody.\n").unwrap();
+
+    let status = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(output.to_str().unwrap())
+        .arg("--strip-metadata-block")
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success());
+    let html = fs::read_to_string(&output).unwrap();
+    assert!(html.contains("<title>Report</title>"), "got: {}", html);
+    assert!(!html.contains("<h1 class=\"title\">"), "got: {}", html);
+    assert!(!html.contains("<header>"), "got: {}", html);
+}
+
+#[test]
+fn test_github_alerts_option() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("in.md");
+    fs::write(&input, "> [!WARNING]\n> be careful\n").unwrap();
+
+    let output = tmp.path().join("out.html");
+    let status = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(output.to_str().unwrap())
+        .arg("--github-alerts")
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success());
+    let html = fs::read_to_string(&output).unwrap();
+    assert!(
+        html.contains("<div class=\"alert alert-warning\">\n<p class=\"alert-title\">Warning</p>\n<p>be careful</p>\n</div>"),
+        "got: {}",
+        html
+    );
+    assert!(!html.contains("[!WARNING]"));
+
+    let output = tmp.path().join("out.docx");
+    let status = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(output.to_str().unwrap())
+        .arg("--github-alerts")
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success());
+    let xml = document_xml(&output);
+    assert!(xml.contains("w:color=\"C27C0E\""), "got: {}", xml);
+    assert!(xml.contains(">Warning</w:t>"), "got: {}", xml);
+}
+
+#[test]
+fn test_sanitize_option() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path().join("in.md");
+    let output = tmp.path().join("out.html");
+    fs::write(&input, "<script>alert(1)</script>\n\n[x](javascript:alert(1))\n").unwrap();
+
+    let status = pandorust_cmd()
+        .arg(input.to_str().unwrap())
+        .arg("-o")
+        .arg(output.to_str().unwrap())
+        .arg("--sanitize")
+        .status()
+        .expect("failed to execute pandorust");
+    assert!(status.success());
+    let html = fs::read_to_string(&output).unwrap();
+    assert!(!html.contains("<script>"), "got: {}", html);
+    assert!(html.contains("<a href=\"#\">x</a>"), "got: {}", html);
+}
@@ -77,3 +77,517 @@ fn test_docx_respects_fontsize_meta() {
     assert!(content.contains("22") || content.contains("w:sz"),
         "DOCX should set font size from metadata");
 }
+
+/// Extract `word/document.xml` from DOCX bytes.
+fn document_xml(bytes: Vec<u8>) -> String {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+    let mut doc_xml = String::new();
+    archive.by_name("word/document.xml").unwrap().read_to_string(&mut doc_xml).unwrap();
+    doc_xml
+}
+
+#[test]
+fn test_docx_row_head_columns_are_bold() {
+    use pandorust::ast::*;
+
+    let cell = |text: &str| Cell {
+        attr: Attr::empty(),
+        align: Alignment::AlignDefault,
+        row_span: 1,
+        col_span: 1,
+        content: vec![Block::Plain(vec![Inline::Str(text.to_string())])],
+    };
+    let table = Table {
+        attr: Attr::empty(),
+        caption: Caption::default(),
+        col_specs: vec![
+            ColSpec { align: Alignment::AlignDefault, width: ColWidth::Default },
+            ColSpec { align: Alignment::AlignDefault, width: ColWidth::Default },
+        ],
+        head: TableHead { attr: Attr::empty(), rows: vec![] },
+        bodies: vec![TableBody {
+            attr: Attr::empty(),
+            row_head_columns: 1,
+            head: vec![],
+            body: vec![Row { attr: Attr::empty(), cells: vec![cell("RowLabel"), cell("PlainValue")] }],
+        }],
+        foot: TableFoot { attr: Attr::empty(), rows: vec![] },
+    };
+    let doc = Document { meta: Meta::default(), blocks: vec![Block::Table(table)] };
+    let xml = document_xml(write_docx(&doc).unwrap());
+
+    let run_props = |text: &str| {
+        let end = xml.find(text).unwrap();
+        let start = xml[..end].rfind("<w:r>").unwrap();
+        xml[start..end].to_string()
+    };
+    assert!(run_props("RowLabel").contains("<w:b"), "row-header cell run should be bold: {}", xml);
+    assert!(!run_props("PlainValue").contains("<w:b"), "regular body cell should not be bold: {}", xml);
+}
+
+#[test]
+fn test_docx_row_head_columns_count_spanned_columns() {
+    use pandorust::ast::*;
+
+    let cell = |text: &str, col_span: u32| Cell {
+        attr: Attr::empty(),
+        align: Alignment::AlignDefault,
+        row_span: 1,
+        col_span,
+        content: vec![Block::Plain(vec![Inline::Str(text.to_string())])],
+    };
+    let spec = ColSpec { align: Alignment::AlignDefault, width: ColWidth::Default };
+    let table = Table {
+        attr: Attr::empty(),
+        caption: Caption::default(),
+        col_specs: vec![spec.clone(), spec.clone(), spec],
+        head: TableHead { attr: Attr::empty(), rows: vec![] },
+        bodies: vec![TableBody {
+            attr: Attr::empty(),
+            row_head_columns: 2,
+            head: vec![],
+            // The label spans both row-header columns
+            body: vec![Row { attr: Attr::empty(), cells: vec![cell("WideLabel", 2), cell("PlainValue", 1)] }],
+        }],
+        foot: TableFoot { attr: Attr::empty(), rows: vec![] },
+    };
+    let doc = Document { meta: Meta::default(), blocks: vec![Block::Table(table)] };
+    let xml = document_xml(write_docx(&doc).unwrap());
+
+    let run_props = |text: &str| {
+        let end = xml.find(text).unwrap();
+        let start = xml[..end].rfind("<w:r>").unwrap();
+        xml[start..end].to_string()
+    };
+    assert!(run_props("WideLabel").contains("<w:b"), "spanning row-header cell should be bold: {}", xml);
+    assert!(!run_props("PlainValue").contains("<w:b"), "cell after the row-header columns should not be bold: {}", xml);
+}
+
+#[test]
+fn test_docx_abstract_is_italic_block() {
+    let md = "---\ntitle: Paper\nabstract: This is *important* work.\n---\n\nBody text.";
+    let doc = read_markdown(md).unwrap();
+    let xml = document_xml(write_docx(&doc).unwrap());
+    assert!(xml.contains(">Abstract</w:t>"), "abstract heading missing: {}", xml);
+    let pos = xml.find("This is ").unwrap();
+    let run_start = xml[..pos].rfind("<w:r>").unwrap();
+    assert!(xml[run_start..pos].contains("<w:i"), "abstract text should be italic");
+    assert!(pos < xml.find("Body text.").unwrap());
+}
+
+#[test]
+fn test_docx_definition_list_indents_each_definition() {
+    use pandorust::ast::*;
+
+    let para = |text: &str| vec![Block::Para(vec![Inline::Str(text.to_string())])];
+    let doc = Document {
+        meta: Meta::default(),
+        blocks: vec![Block::DefinitionList(vec![
+            (
+                vec![Inline::Str("Term".into())],
+                vec![para("First meaning"), para("Second meaning")],
+            ),
+            (vec![Inline::Str("Other".into())], vec![para("Only meaning")]),
+        ])],
+    };
+    let xml = document_xml(write_docx(&doc).unwrap());
+
+    for text in ["First meaning", "Second meaning", "Only meaning"] {
+        let pos = xml.find(text).unwrap_or_else(|| panic!("{} missing", text));
+        let props = xml[..pos].rfind("<w:pPr>").unwrap();
+        let para_xml = &xml[props..pos];
+        assert!(para_xml.contains("w:left=\"720\""), "{} not indented: {}", text, para_xml);
+        assert!(para_xml.contains("w:hanging=\"360\""), "{} lacks hanging indent", text);
+    }
+
+    let term = xml.find(">Term</w:t>").unwrap();
+    let term_props = xml[..term].rfind("<w:pPr>").unwrap();
+    assert!(xml[term_props..term].contains("<w:keepNext"));
+    assert!(xml[term_props..term].contains("<w:b"));
+}
+
+#[test]
+fn test_docx_output_is_deterministic() {
+    let md = "---\ntitle: Stable\n---\n\n# Heading\n\nText with **bold**.\n\n| A | B |\n|---|---|\n| 1 | 2 |";
+    let doc = read_markdown(md).unwrap();
+    let first = write_docx(&doc).unwrap();
+    let second = write_docx(&doc).unwrap();
+    assert_eq!(first, second, "identical input should give identical DOCX bytes");
+}
+
+#[test]
+fn test_docx_blockquote_attribution_right_aligned() {
+    use pandorust::writers::docx::write_docx_with;
+    use pandorust::writers::options::WriterOptions;
+
+    let doc = read_markdown("> quote\n>\n> \u{2014} Me").unwrap();
+    let options = WriterOptions { cite_attribution: true, ..Default::default() };
+    let xml = document_xml(write_docx_with(&doc, &options).unwrap());
+    let pos = xml.find(">Me</w:t>").expect("attribution text missing");
+    let props = xml[..pos].rfind("<w:pPr>").unwrap();
+    assert!(xml[props..pos].contains("<w:jc w:val=\"right\""), "got: {}", &xml[props..pos]);
+    assert!(xml[props..pos].contains("<w:i"));
+}
+
+#[test]
+fn test_docx_heading_spacing_from_metadata() {
+    let md = "---\nheading-space-before: 24pt\nh2-space-after: 3pt\n---\n\n# One\n\n## Two\n\nBody.";
+    let doc = read_markdown(md).unwrap();
+    let xml = document_xml(write_docx(&doc).unwrap());
+
+    let spacing_before = |text: &str| {
+        let pos = xml.find(text).unwrap();
+        let props = xml[..pos].rfind("<w:pPr>").unwrap();
+        xml[props..pos].to_string()
+    };
+    let h1 = spacing_before(">One</w:t>");
+    assert!(h1.contains("w:before=\"480\""), "got: {}", h1);
+    assert!(h1.contains("w:after=\"160\""), "default after kept: {}", h1);
+    let h2 = spacing_before(">Two</w:t>");
+    assert!(h2.contains("w:before=\"480\"") && h2.contains("w:after=\"60\""), "got: {}", h2);
+
+    // Unset keys keep the defaults
+    let plain = document_xml(write_docx(&read_markdown("# One").unwrap()).unwrap());
+    assert!(plain.contains("w:before=\"400\""));
+}
+
+#[test]
+fn test_docx_highlight_run() {
+    let doc = read_markdown("Say ==hi== now").unwrap();
+    let xml = document_xml(write_docx(&doc).unwrap());
+    let pos = xml.find(">hi</w:t>").expect("highlighted text missing");
+    let run = xml[..pos].rfind("<w:r>").unwrap();
+    assert!(xml[run..pos].contains("<w:highlight w:val=\"yellow\""), "got: {}", &xml[run..pos]);
+    let plain = xml.find("Say").unwrap();
+    let plain_run = xml[..plain].rfind("<w:r>").unwrap();
+    assert!(!xml[plain_run..plain].contains("w:highlight"));
+}
+
+#[test]
+fn test_docx_span_style_colors() {
+    use pandorust::ast::*;
+
+    let span = |attr: Attr, text: &str| Inline::Span(attr, vec![Inline::Str(text.to_string())]);
+    let doc = Document {
+        meta: Meta::default(),
+        blocks: vec![Block::Para(vec![
+            span(
+                Attr { attrs: vec![("style".into(), "background: cyan".into())], ..Attr::empty() },
+                "Shaded",
+            ),
+            span(Attr { classes: vec!["red".into()], ..Attr::empty() }, "Warning"),
+            span(Attr { classes: vec!["note".into()], ..Attr::empty() }, "Plain"),
+        ])],
+    };
+    let xml = document_xml(write_docx(&doc).unwrap());
+    let run_props = |text: &str| {
+        let end = xml.find(text).unwrap();
+        let start = xml[..end].rfind("<w:r>").unwrap();
+        xml[start..end].to_string()
+    };
+    assert!(run_props("Shaded").contains("<w:highlight w:val=\"cyan\""), "got: {}", xml);
+    assert!(run_props("Warning").contains("<w:color w:val=\"FF0000\""), "got: {}", xml);
+    let plain = run_props("Plain");
+    assert!(!plain.contains("w:highlight") && !plain.contains("w:color"));
+}
+
+#[test]
+fn test_docx_html_superscript_tag() {
+    let doc = read_markdown("E = mc<sup>2</sup>").unwrap();
+    let xml = document_xml(write_docx(&doc).unwrap());
+    let pos = xml.find(">2</w:t>").expect("superscript text missing");
+    let run = xml[..pos].rfind("<w:r>").unwrap();
+    assert!(xml[run..pos].contains("<w:vertAlign w:val=\"superscript\""), "got: {}", &xml[run..pos]);
+}
+
+#[test]
+fn test_docx_mainfont_and_monofont_metadata() {
+    let md = "---\nmainfont: Georgia\nmonofont: Consolas\n---\n\nBody with `code`.";
+    let xml = document_xml(write_docx(&read_markdown(md).unwrap()).unwrap());
+    assert!(xml.contains("<w:rFonts w:ascii=\"Georgia\""), "got: {}", xml);
+    assert!(xml.contains("<w:rFonts w:ascii=\"Consolas\""), "got: {}", xml);
+    assert!(!xml.contains("Calibri"));
+}
+
+#[test]
+fn test_docx_horizontal_rule_is_paragraph_border() {
+    let xml = document_xml(write_docx(&read_markdown("Above\n\n---\n\nBelow").unwrap()).unwrap());
+    let rule = xml.find("<w:pBdr>").expect("no paragraph border for the rule");
+    assert!(xml[rule..].starts_with("<w:pBdr><w:bottom w:val=\"single\""), "got: {}", &xml[rule..]);
+    assert!(!xml.contains("\u{2014}\u{2014}\u{2014}\u{2014}"));
+}
+
+#[test]
+fn test_docx_image_without_alt_does_not_show_url() {
+    let xml = document_xml(write_docx(&read_markdown("![](images/logo.png)").unwrap()).unwrap());
+    assert!(xml.contains(">[Image]</w:t>"), "got: {}", xml);
+    assert!(!xml.contains("images/logo.png"));
+}
+
+#[test]
+fn test_docx_task_summary() {
+    use pandorust::writers::docx::write_docx_with;
+    use pandorust::writers::options::WriterOptions;
+
+    let md = "- [x] one\n- [x] two\n- [ ] three\n- [x] four\n- [ ] five\n";
+    let doc = read_markdown(md).unwrap();
+    let options = WriterOptions {
+        task_summary: true,
+        ..Default::default()
+    };
+    let xml = document_xml(write_docx_with(&doc, &options).unwrap());
+    assert!(xml.contains(">Tasks: 3/5 complete (60%)</w:t>"), "got: {}", xml);
+    assert!(!document_xml(write_docx(&doc).unwrap()).contains("Tasks:"));
+}
+
+#[test]
+fn test_docx_display_math_is_centered_paragraph() {
+    let doc = read_markdown("Before\n\n$$E=mc^2$$\n\nAfter $x$ inline").unwrap();
+    let xml = document_xml(write_docx(&doc).unwrap());
+    let math = xml.find(">E=mc^2</w:t>").expect("math text missing");
+    let para = xml[..math].rfind("<w:pPr>").expect("no paragraph");
+    assert!(xml[para..math].contains("<w:jc w:val=\"center\""), "got: {}", &xml[para..math]);
+
+    // Inline math stays in a normal paragraph
+    let inline = xml.find(">x</w:t>").expect("inline math missing");
+    let para = xml[..inline].rfind("<w:pPr>").unwrap();
+    assert!(!xml[para..inline].contains("<w:jc w:val=\"center\""));
+}
+
+#[test]
+fn test_docx_nested_blockquote_and_list_indent_by_depth() {
+    let indent_before = |xml: &str, text: &str| -> String {
+        let pos = xml.find(text).unwrap_or_else(|| panic!("{} missing: {}", text, xml));
+        let ind = xml[..pos].rfind("<w:ind ").expect("no indent");
+        xml[ind..].split('>').next().unwrap().to_string()
+    };
+
+    let xml = document_xml(write_docx(&read_markdown("> Outer\n>\n> > Inner\n").unwrap()).unwrap());
+    assert!(indent_before(&xml, ">Outer<").contains("w:left=\"720\""), "got: {}", xml);
+    assert!(indent_before(&xml, ">Inner<").contains("w:left=\"1440\""), "got: {}", xml);
+
+    let xml = document_xml(write_docx(&read_markdown("- top\n  - child\n").unwrap()).unwrap());
+    assert!(indent_before(&xml, "top<").contains("w:left=\"720\""), "got: {}", xml);
+    assert!(indent_before(&xml, "child<").contains("w:left=\"1440\""), "got: {}", xml);
+}
+
+#[test]
+fn test_docx_list_marker_hangs_before_text() {
+    let xml = document_xml(write_docx(&read_markdown("- top
+  - child
+
+1. first
+").unwrap()).unwrap());
+    // The marker is its own run, followed by a tab to the text position
+    assert!(xml.contains(">\u{2022}</w:t><w:tab />"), "got: {}", xml);
+    assert!(xml.contains(">1.</w:t><w:tab />"), "got: {}", xml);
+    let indents: Vec<&str> = xml.match_indices("<w:ind ").map(|(i, _)| xml[i..].split('>').next().unwrap()).collect();
+    assert_eq!(
+        indents,
+        vec![
+            "<w:ind w:left=\"720\" w:hanging=\"480\" /",
+            "<w:ind w:left=\"1440\" w:hanging=\"480\" /",
+            "<w:ind w:left=\"720\" w:hanging=\"480\" /",
+        ]
+    );
+}
+
+#[test]
+fn test_docx_admonition_is_bordered_callout() {
+    let xml = document_xml(write_docx(&read_markdown("::: danger\nDo not run this.\n:::\n").unwrap()).unwrap());
+    let title = xml.find(">Danger</w:t>").expect("no admonition title");
+    let body = xml.find(">Do not run this.</w:t>").expect("no admonition text");
+    for pos in [title, body] {
+        let props = xml[..pos].rfind("<w:pPr>").unwrap();
+        assert!(xml[props..pos].contains("<w:left w:val=\"single\" w:sz=\"24\""), "got: {}", &xml[props..pos]);
+        assert!(xml[props..pos].contains("w:color=\"B42318\""), "got: {}", &xml[props..pos]);
+    }
+}
+
+#[test]
+fn test_docx_line_block_keeps_leading_indent() {
+    use pandorust::ast::*;
+
+    let line = |text: &str| vec![Inline::Str(text.to_string())];
+    let doc = Document {
+        meta: Meta::default(),
+        blocks: vec![Block::LineBlock(vec![line("The first line"), line("\u{a0}\u{a0}\u{a0}\u{a0}indented")])],
+    };
+    let xml = document_xml(write_docx(&doc).unwrap());
+    let props = |text: &str| {
+        let pos = xml.find(text).unwrap_or_else(|| panic!("{} missing: {}", text, xml));
+        let start = xml[..pos].rfind("<w:pPr>").unwrap();
+        xml[start..pos].to_string()
+    };
+    assert!(props(">The first line<").contains("w:left=\"0\""), "got: {}", xml);
+    assert!(props(">The first line<").contains("w:after=\"0\""), "got: {}", xml);
+    // Four spaces at the 12pt default, half an em each
+    assert!(props(">indented<").contains("w:left=\"480\""), "got: {}", xml);
+}
+
+#[test]
+fn test_docx_code_caption_above_listing() {
+    let md = "```rust {caption=\"Listing 1: main.rs\"}\nfn main() {}\n```\n";
+    let xml = document_xml(write_docx(&read_markdown(md).unwrap()).unwrap());
+    let caption = xml.find(">Listing 1: main.rs</w:t>").expect("no caption");
+    let code = xml.find(">fn main() {}</w:t>").expect("no code");
+    assert!(caption < code);
+    let run = &xml[xml[..caption].rfind("<w:r>").unwrap()..caption];
+    assert!(run.contains("<w:i />"), "got: {}", run);
+}
+
+#[test]
+fn test_docx_table_grid_covers_widest_row() {
+    use pandorust::ast::*;
+
+    let cell = |text: &str| Cell {
+        attr: Attr::empty(),
+        align: Alignment::AlignDefault,
+        row_span: 1,
+        col_span: 1,
+        content: vec![Block::Plain(vec![Inline::Str(text.to_string())])],
+    };
+    let row = |cells: Vec<Cell>| Row { attr: Attr::empty(), cells };
+    let table = Table {
+        attr: Attr::empty(),
+        caption: Caption::default(),
+        // Fewer column specs than the body row has cells
+        col_specs: vec![
+            ColSpec { align: Alignment::AlignDefault, width: ColWidth::Default },
+            ColSpec { align: Alignment::AlignDefault, width: ColWidth::Default },
+        ],
+        head: TableHead { attr: Attr::empty(), rows: vec![row(vec![cell("A"), cell("B")])] },
+        bodies: vec![TableBody {
+            attr: Attr::empty(),
+            row_head_columns: 0,
+            head: vec![],
+            body: vec![row(vec![cell("1"), cell("2"), cell("3")])],
+        }],
+        foot: TableFoot { attr: Attr::empty(), rows: vec![] },
+    };
+    let doc = Document { meta: Meta::default(), blocks: vec![Block::Table(table)] };
+    let xml = document_xml(write_docx(&doc).unwrap());
+    assert_eq!(xml.matches("<w:gridCol ").count(), 3, "got: {}", xml);
+    assert!(xml.contains(">3</w:t>"));
+}
+
+#[test]
+fn test_docx_nested_table_in_cell() {
+    let md = "+-------------+-----+\n| Outer       | B   |\n+=============+=====+\n| | a | b |   | x   |\n| |---|---|   |     |\n| | 1 | 2 |   |     |\n+-------------+-----+\n";
+    let xml = document_xml(write_docx(&read_markdown(md).unwrap()).unwrap());
+    assert_eq!(xml.matches("<w:tbl>").count(), 2, "got: {}", xml);
+    // The inner table sits in a cell, sized to its column less padding
+    let inner = xml.match_indices("<w:tbl>").nth(1).unwrap().0;
+    assert!(xml[..inner].ends_with("</w:tcPr>"), "got: {}", xml);
+    assert!(xml[inner..].contains("<w:tblW w:w=\"4260\" w:type=\"dxa\" />"), "got: {}", &xml[inner..]);
+    // and the cell still ends with a paragraph
+    assert!(xml[inner..].contains("</w:tbl><w:p"), "got: {}", &xml[inner..]);
+}
+
+#[test]
+fn test_docx_list_of_figures() {
+    use pandorust::ast::*;
+
+    let text = |s: &str| vec![Inline::Str(s.to_string())];
+    let mut meta = Meta::default();
+    meta.entries.insert("lof".to_string(), MetaValue::Bool(true));
+    let figure = Block::Figure(
+        Attr::empty(),
+        Caption { short: Some(text("Layout")), long: vec![Block::Para(text("The full layout diagram"))] },
+        vec![Block::Para(text("figure body"))],
+    );
+    let xml = document_xml(write_docx(&Document { meta, blocks: vec![figure] }).unwrap());
+    let title = xml.find(">List of Figures</w:t>").expect("no list title");
+    let entry = xml.find(">Layout</w:t>").expect("no short caption entry");
+    assert!(title < entry && entry < xml.find(">figure body</w:t>").unwrap(), "got: {}", xml);
+    assert!(xml[title..entry].contains(">1. </w:t>"), "got: {}", xml);
+}
+
+#[test]
+fn test_docx_drops_raw_content() {
+    let md = "Before <span>inline</span> after.\n\n```{=latex}\n\\clearpage\n```\n";
+    let xml = document_xml(write_docx(&read_markdown(md).unwrap()).unwrap());
+    assert!(!xml.contains("span&gt;") && !xml.contains("<span>"), "got: {}", xml);
+    assert!(!xml.contains("clearpage"), "got: {}", xml);
+    assert!(xml.contains(">inline</w:t>"), "got: {}", xml);
+}
+
+#[test]
+fn test_only_format_divs() {
+    let md = "::: {.only-html}\nWeb only.\n:::\n\n::: {.only-docx}\nPrint only.\n:::\n\n::: aside\nEverywhere.\n:::\n";
+    let xml = document_xml(write_docx(&read_markdown(md).unwrap()).unwrap());
+    assert!(!xml.contains("Web only."), "got: {}", xml);
+    assert!(xml.contains("Print only."), "got: {}", xml);
+    assert!(xml.contains("Everywhere."), "got: {}", xml);
+}
+
+#[test]
+fn test_header_cell_follows_column_alignment() {
+    let md = "| Name | Qty |\n|------|:---:|\n| a | 1 |\n";
+    let xml = document_xml(write_docx(&read_markdown(md).unwrap()).unwrap());
+    let qty = xml.find(">Qty</w:t>").expect("no header cell");
+    let para = xml[..qty].rfind("<w:p ").unwrap();
+    assert!(xml[para..qty].contains("<w:jc w:val=\"center\" />"), "got: {}", &xml[para..qty]);
+    let name = xml.find(">Name</w:t>").unwrap();
+    let para = xml[..name].rfind("<w:p ").unwrap();
+    assert!(!xml[para..name].contains("<w:jc "), "got: {}", &xml[para..name]);
+}
+
+#[test]
+fn test_bold_italic_run() {
+    for md in ["A ***both***.", "A **_both_**.", "# A *both*"] {
+        let xml = document_xml(write_docx(&read_markdown(md).unwrap()).unwrap());
+        let text = xml.find(">both</w:t>").expect("no run");
+        let run = &xml[xml[..text].rfind("<w:r>").unwrap()..text];
+        assert!(run.contains("<w:b />") && run.contains("<w:i />"), "{}: got {}", md, run);
+    }
+}
+
+#[test]
+fn test_strip_metadata_block() {
+    use pandorust::writers::docx::write_docx_with;
+    use pandorust::writers::options::WriterOptions;
+
+    let doc = read_markdown("---\ntitle: Report\nauthor: Ana\n---\n\nBody.\n").unwrap();
+    let options = WriterOptions { strip_metadata_block: true, ..Default::default() };
+    let xml = document_xml(write_docx_with(&doc, &options).unwrap());
+    assert!(!xml.contains("Report"), "got: {}", xml);
+    assert!(!xml.contains("Author: Ana"), "got: {}", xml);
+    assert!(xml.contains("Body."));
+}
+
+#[test]
+fn test_docx_linestretch() {
+    let md = "---\nlinestretch: 2.0\n---\n\n# Head\n\nBody text.\n\n- item\n";
+    let xml = document_xml(write_docx(&read_markdown(md).unwrap()).unwrap());
+    let spacing = |text: &str| {
+        let pos = xml.find(text).unwrap();
+        let props = xml[..pos].rfind("<w:pPr>").unwrap();
+        xml[props..pos].to_string()
+    };
+    for text in [">Body text.</w:t>", ">item</w:t>", ">Head</w:t>"] {
+        assert!(spacing(text).contains("w:line=\"480\""), "{}: got {}", text, spacing(text));
+    }
+
+    // Without it the built-in spacing stays
+    let xml = document_xml(write_docx(&read_markdown("Body text.").unwrap()).unwrap());
+    assert!(xml.contains("w:line=\"300\""), "got: {}", xml);
+}
+
+#[test]
+fn test_cover_page() {
+    let md = "---\ntitle: Report\ndate: 2026-01-01\ncoverpage: true\n---\n\nBody.\n";
+    let xml = document_xml(write_docx(&read_markdown(md).unwrap()).unwrap());
+    let title = xml.find(">Report</w:t>").unwrap();
+    let run = xml[..title].rfind("<w:r>").unwrap();
+    assert!(xml[run..title].contains("<w:sz w:val=\"72\" />"), "got: {}", &xml[run..title]);
+
+    // The page break comes after the date and before the body
+    let date = xml.find(">2026-01-01</w:t>").unwrap();
+    let body = xml.find(">Body.</w:t>").unwrap();
+    let page_break = xml.find("<w:br w:type=\"page\" />").expect("no page break");
+    assert!(date < page_break && page_break < body, "got: {}", xml);
+
+    let xml = document_xml(write_docx(&read_markdown("---\ntitle: Report\n---\n\nBody.\n").unwrap()).unwrap());
+    assert!(!xml.contains("w:type=\"page\""), "got: {}", xml);
+}
@@ -22,6 +22,30 @@ fn test_simple_grid_table() {
     }
 }
 
+#[test]
+fn test_grid_table_with_multibyte_text() {
+    let md = "\
++--------+--------+
+| Café   | 東京     |
++========+========+
+| naïve  | 日本語    |
++--------+--------+";
+    let doc = read_markdown(md).unwrap();
+    let Block::Table(table) = &doc.blocks[0] else {
+        panic!("Expected Table, got {:?}", doc.blocks[0]);
+    };
+    let text = |cell: &Cell| match cell.content.as_slice() {
+        [Block::Para(inlines)] | [Block::Plain(inlines)] => inlines.clone(),
+        other => panic!("unexpected cell content {:?}", other),
+    };
+    let head = &table.head.rows[0].cells;
+    assert_eq!(text(&head[0]), vec![Inline::Str("Café".into())]);
+    assert_eq!(text(&head[1]), vec![Inline::Str("東京".into())]);
+    let body = &table.bodies[0].body[0].cells;
+    assert_eq!(text(&body[0]), vec![Inline::Str("naïve".into())]);
+    assert_eq!(text(&body[1]), vec![Inline::Str("日本語".into())]);
+}
+
 #[test]
 fn test_grid_table_with_header_content() {
     let md = "\
@@ -103,8 +127,9 @@ fn test_grid_table_no_header_separator() {
     let doc = read_markdown(md).unwrap();
     match &doc.blocks[0] {
         Block::Table(table) => {
-            // First row becomes header in GFM
-            assert_eq!(table.head.rows.len(), 1);
+            // Without a separator there is no header, only body rows
+            assert!(table.head.rows.is_empty());
+            assert_eq!(table.bodies[0].body.len(), 2);
         }
         other => panic!("Expected Table, got {:?}", other),
     }
@@ -180,3 +205,133 @@ fn extract_text(blocks: &[Block]) -> String {
         .collect::<Vec<_>>()
         .join(" ")
 }
+
+#[test]
+fn test_simple_table() {
+    let md = "\
+Name    Value
+------  ------
+foo     42
+bar     7";
+    let doc = read_markdown(md).unwrap();
+    match &doc.blocks[0] {
+        Block::Table(table) => {
+            assert_eq!(table.head.rows.len(), 1);
+            assert_eq!(table.head.rows[0].cells.len(), 2);
+            assert_eq!(table.bodies[0].body.len(), 2);
+            let text = extract_text(&table.bodies[0].body[0].cells[1].content);
+            assert_eq!(text, "42");
+        }
+        other => panic!("Expected Table, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_simple_table_no_header() {
+    let md = "\
+-----  -----
+A      B
+1      2
+-----  -----";
+    let doc = read_markdown(md).unwrap();
+    match &doc.blocks[0] {
+        Block::Table(table) => {
+            assert_eq!(table.head.rows[0].cells.len(), 2);
+            assert_eq!(table.bodies[0].body.len(), 1);
+        }
+        other => panic!("Expected Table, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_fenced_code_keeps_div_and_grid_markers() {
+    let md = "\
+```
+::: {.x}
++---+
+| a |
++---+
+:::
+```";
+    let doc = read_markdown(md).unwrap();
+    match &doc.blocks[0] {
+        Block::CodeBlock(_, code) => {
+            assert_eq!(code, "::: {.x}\n+---+\n| a |\n+---+\n:::\n");
+        }
+        other => panic!("Expected CodeBlock, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_grid_table_multi_paragraph_cell() {
+    use pandorust::writers::docx::write_docx;
+    use pandorust::writers::html::write_html;
+    use std::io::Read;
+
+    let md = "\
++-------+-------------------+
+| Key   | Notes             |
++=======+===================+
+| a     | First paragraph.  |
+|       |                   |
+|       | Second paragraph. |
++-------+-------------------+";
+    let doc = read_markdown(md).unwrap();
+    match &doc.blocks[0] {
+        Block::Table(table) => {
+            let content = &table.bodies[0].body[0].cells[1].content;
+            assert_eq!(content.len(), 2, "got: {:?}", content);
+            assert!(matches!(&table.bodies[0].body[0].cells[0].content[..], [Block::Plain(_)]));
+        }
+        other => panic!("Expected Table, got {:?}", other),
+    }
+
+    let html = write_html(&doc);
+    assert!(
+        html.contains("<td><p>First paragraph.</p>\n<p>Second paragraph.</p>\n</td>"),
+        "got: {}",
+        html
+    );
+
+    let bytes = write_docx(&doc).unwrap();
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+    let mut xml = String::new();
+    archive
+        .by_name("word/document.xml")
+        .unwrap()
+        .read_to_string(&mut xml)
+        .unwrap();
+    let first = xml.find("First paragraph.").unwrap();
+    let second = xml.find("Second paragraph.").unwrap();
+    assert!(xml[first..second].contains("</w:p>"), "paragraphs should be separate");
+    assert!(!xml[first..second].contains("</w:tc>"), "both paragraphs in one cell");
+}
+
+#[test]
+fn test_grid_table_cell_definition_list() {
+    use pandorust::writers::html::write_html;
+
+    let md = "\
++---------+--------------------+
+| Section | Details            |
++=========+====================+
+| Terms   | Owner              |
+|         | : Finance team     |
+|         |                    |
+|         | Budget             |
+|         | : RM 12,000        |
++---------+--------------------+";
+    let doc = read_markdown(md).unwrap();
+    match &doc.blocks[0] {
+        Block::Table(table) => match &table.bodies[0].body[0].cells[1].content[..] {
+            [Block::DefinitionList(items)] => {
+                assert_eq!(items.len(), 2);
+                assert_eq!(items[0].0, vec![Inline::Str("Owner".into())]);
+            }
+            other => panic!("Expected DefinitionList in cell, got {:?}", other),
+        },
+        other => panic!("Expected Table, got {:?}", other),
+    }
+    let html = write_html(&doc);
+    assert!(html.contains("<td><dl>\n<dt>Owner</dt>"), "got: {}", html);
+}
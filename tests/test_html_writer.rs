@@ -5,7 +5,7 @@ use pandorust::writers::html::write_html;
 fn test_heading_to_html() {
     let doc = read_markdown("# Hello").unwrap();
     let html = write_html(&doc);
-    assert!(html.contains("<h1>Hello</h1>"));
+    assert!(html.contains("<h1 id=\"hello\">Hello</h1>"));
 }
 
 #[test]
@@ -78,3 +78,538 @@ fn test_html_respects_fontsize_meta() {
     let html = write_html(&doc);
     assert!(html.contains("11pt"), "HTML should respect fontsize from metadata, got: {}", &html[..500.min(html.len())]);
 }
+
+#[test]
+fn test_abstract_in_html() {
+    let md = "---\ntitle: Paper\nabstract: This is *important* work.\n---\n\nBody text.";
+    let doc = read_markdown(md).unwrap();
+    let html = write_html(&doc);
+    assert!(html.contains("<div class=\"abstract\">\n<h2>Abstract</h2>"), "got: {}", html);
+    assert!(html.contains("<em>important</em>"), "abstract should be parsed as Markdown");
+    let abstract_pos = html.find("class=\"abstract\"").unwrap();
+    let header_pos = html.find("<header>").unwrap();
+    let body_pos = html.find("Body text.").unwrap();
+    assert!(header_pos < abstract_pos && abstract_pos < body_pos);
+}
+
+#[test]
+fn test_wrap_code_block_to_html() {
+    let doc = read_markdown("```python {.wrap}\nprint('a very long line')\n```").unwrap();
+    let html = write_html(&doc);
+    assert!(
+        html.contains("<pre class=\"wrap\"><code class=\"language-python\">"),
+        "got: {}",
+        html
+    );
+    assert!(html.contains("pre.wrap { white-space: pre-wrap;"));
+
+    let doc = read_markdown("``` {.wrap}\nplain\n```").unwrap();
+    let html = write_html(&doc);
+    assert!(html.contains("<pre class=\"wrap\"><code>plain"), "got: {}", html);
+}
+
+#[test]
+fn test_blockquote_attribution_as_cite() {
+    use pandorust::writers::html::write_html_with;
+    use pandorust::writers::options::WriterOptions;
+
+    let doc = read_markdown("> quote\n>\n> \u{2014} Me").unwrap();
+    let plain = write_html(&doc);
+    assert!(!plain.contains("<cite>"), "attribution is opt-in");
+
+    let options = WriterOptions { cite_attribution: true, ..Default::default() };
+    let html = write_html_with(&doc, &options);
+    assert!(html.contains("<cite>Me</cite>"), "got: {}", html);
+    assert!(html.contains("<p>quote</p>"));
+    assert!(!html.contains("<p>\u{2014} Me</p>"));
+
+    let doc_dashes = read_markdown("> quote\n>\n> -- Someone Else").unwrap();
+    assert!(write_html_with(&doc_dashes, &options).contains("<cite>Someone Else</cite>"));
+}
+
+#[test]
+fn test_highlight_renders_mark() {
+    let doc = read_markdown("Say ==hi== now").unwrap();
+    let html = write_html(&doc);
+    assert!(html.contains("<p>Say <mark>hi</mark> now</p>"), "got: {}", html);
+}
+
+#[test]
+fn test_quotes_follow_document_language() {
+    use pandorust::ast::*;
+
+    let quoted = |lang: Option<&str>| {
+        let mut meta = Meta::default();
+        if let Some(lang) = lang {
+            meta.entries.insert("lang".to_string(), MetaValue::String(lang.to_string()));
+        }
+        let doc = Document {
+            meta,
+            blocks: vec![Block::Para(vec![Inline::Quoted(
+                QuoteType::DoubleQuote,
+                vec![Inline::Str("Hallo".into())],
+            )])],
+        };
+        write_html(&doc)
+    };
+
+    let de = quoted(Some("de"));
+    assert!(de.contains("<p>&#8222;Hallo&#8220;</p>"), "got: {}", de);
+    assert!(de.contains("<html lang=\"de\">"));
+    assert!(quoted(Some("fr-CA")).contains("&#171;&#160;Hallo&#160;&#187;"));
+    let en = quoted(None);
+    assert!(en.contains("<p>&#8220;Hallo&#8221;</p>"), "got: {}", en);
+    assert!(en.contains("<html>\n"));
+}
+
+#[test]
+fn test_mainfont_and_monofont_metadata() {
+    let doc = read_markdown("---\nmainfont: Georgia\nmonofont: Fira Mono\n---\n\nText").unwrap();
+    let html = write_html(&doc);
+    assert!(html.contains("body { font-family: \"Georgia\","), "got: {}", html);
+    assert!(html.contains("code { font-family: \"Fira Mono\", monospace; }"));
+
+    let html = write_html(&read_markdown("Text").unwrap());
+    assert!(html.contains("body { font-family: \"Calibri\","));
+}
+
+#[test]
+fn test_figure_has_role_and_linked_caption() {
+    use pandorust::ast::*;
+
+    let image = Inline::Image(
+        Attr::empty(),
+        vec![],
+        Target { url: "chart.png".into(), title: String::new() },
+    );
+    let doc = Document {
+        meta: Meta::default(),
+        blocks: vec![Block::Figure(
+            Attr::empty(),
+            Caption { short: None, long: vec![Block::Plain(vec![Inline::Str("Sales".into())])] },
+            vec![Block::Plain(vec![image])],
+        )],
+    };
+    let html = write_html(&doc);
+    assert!(
+        html.contains("<figure id=\"figure-1\" role=\"figure\" aria-labelledby=\"figure-1-caption\">"),
+        "got: {}",
+        html
+    );
+    assert!(html.contains("<figcaption id=\"figure-1-caption\">Sales</figcaption>"));
+    assert!(html.contains("<img src=\"chart.png\" alt=\"Sales\">"), "caption fills empty alt: {}", html);
+}
+
+#[test]
+fn test_image_without_alt_has_empty_alt() {
+    let html = write_html(&read_markdown("![](logo.png \"Company\")").unwrap());
+    assert!(html.contains("<img src=\"logo.png\" alt=\"\" title=\"Company\">"), "got: {}", html);
+    let html = write_html(&read_markdown("![](logo.png)").unwrap());
+    assert!(html.contains("<img src=\"logo.png\" alt=\"\">"), "got: {}", html);
+}
+
+#[test]
+fn test_blockquote_inside_list_item() {
+    let html = write_html(&read_markdown("- > quoted\n- plain").unwrap());
+    assert!(
+        html.contains("<li><blockquote>\n<p>quoted</p>\n</blockquote>\n</li>"),
+        "got: {}",
+        html
+    );
+    assert!(html.contains("<li>plain</li>"));
+}
+
+#[test]
+fn test_nested_blockquotes() {
+    let html = write_html(&read_markdown("> outer\n>\n> > inner\n\nafter").unwrap());
+    assert!(
+        html.contains("<blockquote>\n<p>outer</p>\n<blockquote>\n<p>inner</p>\n</blockquote>\n</blockquote>\n<p>after</p>"),
+        "got: {}",
+        html
+    );
+    assert_eq!(html.matches("<blockquote>").count(), html.matches("</blockquote>").count());
+}
+
+#[test]
+fn test_writer_options_struct() {
+    use pandorust::writers::html::write_html_with;
+    use pandorust::writers::options::{MathMethod, WriterOptions};
+
+    let doc = read_markdown("```rust\nfn f() {}\n```\n\n$x^2$ <!-- note -->").unwrap();
+    let options = WriterOptions {
+        highlight_prefix: "lang-".to_string(),
+        math: MathMethod::MathMl,
+        strip_comments: true,
+        ..Default::default()
+    };
+    let html = write_html_with(&doc, &options);
+    assert!(html.contains("<code class=\"lang-rust\">"), "got: {}", html);
+    assert!(html.contains("<math"), "got: {}", html);
+    assert!(!html.contains("<!-- note -->"));
+
+    // Defaults match write_html
+    assert_eq!(write_html_with(&doc, &WriterOptions::default()), write_html(&doc));
+}
+
+#[test]
+fn test_task_list_checkboxes_and_summary() {
+    use pandorust::writers::html::write_html_with;
+    use pandorust::writers::options::WriterOptions;
+
+    let md = "- [x] one\n- [x] two\n- [ ] three\n- [x] four\n- [ ] five\n";
+    let doc = read_markdown(md).unwrap();
+    assert_eq!(doc.task_counts(), (3, 5));
+
+    let html = write_html(&doc);
+    assert!(html.contains("<li><span class=\"task checked\">\u{2612}</span> one</li>"), "got: {}", html);
+    assert!(html.contains("<li><span class=\"task\">\u{2610}</span> three</li>"), "got: {}", html);
+    assert!(!html.contains("task-summary\">"));
+
+    let options = WriterOptions {
+        task_summary: true,
+        ..Default::default()
+    };
+    let html = write_html_with(&doc, &options);
+    assert!(
+        html.contains("<p class=\"task-summary\">Tasks: 3/5 complete (60%)</p>\n</body>"),
+        "got: {}",
+        html
+    );
+}
+
+#[test]
+fn test_plain_block_has_no_paragraph_wrapper() {
+    use pandorust::ast::*;
+
+    let text = |s: &str| vec![Inline::Str(s.into())];
+    let doc = Document {
+        meta: Meta::default(),
+        blocks: vec![
+            Block::Plain(text("bare")),
+            Block::Para(text("wrapped")),
+            Block::BulletList(vec![vec![Block::Para(text("loose"))]]),
+        ],
+    };
+    let html = write_html(&doc);
+    assert!(html.contains("<body>\nbare\n<p>wrapped</p>"), "got: {}", html);
+    assert!(html.contains("<li><p>loose</p>\n</li>"), "got: {}", html);
+
+    // Tight Markdown lists hold Plain items; loose ones keep paragraphs
+    let html = write_html(&read_markdown("- a\n- b\n\n1. x\n\n2. y\n").unwrap());
+    assert!(html.contains("<li>a</li>"), "got: {}", html);
+    assert!(html.contains("<li><p>x</p>\n</li>"), "got: {}", html);
+}
+
+#[test]
+fn test_footnote_in_table_cell_is_numbered() {
+    let md = "Intro[^a].\n\n| Key | Value |\n|-----|-------|\n| x | y[^cell] |\n\n[^a]: First.\n[^cell]: From the cell.\n";
+    let html = write_html(&read_markdown(md).unwrap());
+    assert!(
+        html.contains("<td>y<sup class=\"footnote-ref\"><a href=\"#fn2\" id=\"fnref2\">2</a></sup></td>"),
+        "got: {}",
+        html
+    );
+    assert_eq!(html.matches("From the cell.").count(), 1, "got: {}", html);
+    let section = html.find("<section class=\"footnotes\">").expect("no footnotes section");
+    assert!(html[section..].contains("<li id=\"fn2\"><p>From the cell.</p>"), "got: {}", html);
+}
+
+#[test]
+fn test_identically_worded_footnotes_stay_distinct() {
+    let md = "One[^a] and two[^b].\n\n[^a]: See above.\n[^b]: See above.\n";
+    let html = write_html(&read_markdown(md).unwrap());
+    assert!(html.contains("<a href=\"#fn1\" id=\"fnref1\">1</a>"), "got: {}", html);
+    assert!(html.contains("<a href=\"#fn2\" id=\"fnref2\">2</a>"), "got: {}", html);
+    assert_eq!(html.matches("See above.").count(), 2, "got: {}", html);
+    assert!(!html.contains("fnref1-2"), "got: {}", html);
+}
+
+#[test]
+fn test_heading_ids_are_unique_slugs() {
+    let md = "# Getting Started!\n\n## Getting started\n\n## 1. Setup & Install\n\n## ???\n";
+    let html = write_html(&read_markdown(md).unwrap());
+    assert!(html.contains("<h1 id=\"getting-started\">"), "got: {}", html);
+    assert!(html.contains("<h2 id=\"getting-started-1\">"), "got: {}", html);
+    assert!(html.contains("<h2 id=\"setup-install\">"), "got: {}", html);
+    assert!(html.contains("<h2 id=\"section\">"), "got: {}", html);
+}
+
+#[test]
+fn test_id_prefix_keeps_anchors_and_links_in_sync() {
+    use pandorust::ast::*;
+    use pandorust::writers::html::write_html_with;
+    use pandorust::writers::options::WriterOptions;
+
+    let md = "# Intro\n\nSee [intro](#intro) and [site](https://example.com)[^a].\n\n[^a]: Note.\n";
+    let mut doc = read_markdown(md).unwrap();
+    doc.blocks.push(Block::Figure(
+        Attr::empty(),
+        Caption { short: None, long: vec![Block::Plain(vec![Inline::Str("Sales".into())])] },
+        vec![],
+    ));
+    let options = WriterOptions { id_prefix: "doc1-".into(), ..Default::default() };
+    let html = write_html_with(&doc, &options);
+    assert!(html.contains("<h1 id=\"doc1-intro\">Intro</h1>"), "got: {}", html);
+    assert!(html.contains("<a href=\"#doc1-intro\">intro</a>"), "got: {}", html);
+    assert!(html.contains("<a href=\"https://example.com\">site</a>"), "got: {}", html);
+    assert!(html.contains("<a href=\"#doc1-fn1\" id=\"doc1-fnref1\">1</a>"), "got: {}", html);
+    assert!(html.contains("<li id=\"doc1-fn1\">"), "got: {}", html);
+    assert!(html.contains("<a href=\"#doc1-fnref1\" class=\"footnote-back\">"), "got: {}", html);
+    assert!(html.contains("<figure id=\"doc1-figure-1\" role=\"figure\" aria-labelledby=\"doc1-figure-1-caption\">"), "got: {}", html);
+    assert!(html.contains("<figcaption id=\"doc1-figure-1-caption\">"), "got: {}", html);
+}
+
+#[test]
+fn test_footnote_referenced_twice_has_two_backrefs() {
+    let md = "One[^n] and two[^n].\n\n[^n]: Shared.\n";
+    let html = write_html(&read_markdown(md).unwrap());
+    assert!(html.contains("<a href=\"#fn1\" id=\"fnref1\">1</a>"), "got: {}", html);
+    assert!(html.contains("<a href=\"#fn1\" id=\"fnref1-2\">1</a>"), "got: {}", html);
+    assert_eq!(html.matches("Shared.").count(), 1, "got: {}", html);
+    assert!(
+        html.contains("<a href=\"#fnref1\" class=\"footnote-back\">&#8617;</a> <a href=\"#fnref1-2\" class=\"footnote-back\">&#8617;</a></li>"),
+        "got: {}",
+        html
+    );
+}
+
+#[test]
+fn test_admonition_div_has_title() {
+    let html = write_html(&read_markdown("::: warning\ntext\n:::\n\n::: sidebar\nother\n:::\n").unwrap());
+    assert!(
+        html.contains("<div class=\"admonition warning\">\n<p class=\"admonition-title\">Warning</p>\n<p>text</p>\n</div>"),
+        "got: {}",
+        html
+    );
+    // Other keywords are plain divs
+    assert!(html.contains("<div class=\"sidebar\">\n<p>other</p>\n</div>"), "got: {}", html);
+}
+
+#[test]
+fn test_semantic_div_classes() {
+    let md = "::: {.aside}\nA note.\n:::\n\n::: nav\n- [Intro](#intro)\n:::\n\n::: {.section}\nFirst.\n:::\n\n::: {.box}\nPlain.\n:::\n";
+    let html = write_html(&read_markdown(md).unwrap());
+    assert!(html.contains("<aside class=\"aside\">\n<p>A note.</p>\n</aside>"), "got: {}", html);
+    assert!(html.contains("<nav class=\"nav\">\n<ul>"), "got: {}", html);
+    assert!(html.contains("<section class=\"section\">\n<p>First.</p>\n</section>"), "got: {}", html);
+    assert!(html.contains("<div class=\"box\">"), "got: {}", html);
+}
+
+#[test]
+fn test_css_metadata_appended_to_style() {
+    let html = write_html(&read_markdown("---\ncss: \"p { color: red; }\"\n---\n\nText\n").unwrap());
+    let style = &html[html.find("<style>").unwrap()..html.find("</style>").unwrap()];
+    assert!(style.ends_with("p { color: red; }\n"), "got: {}", style);
+
+    let md = "---\ncss:\n  - \"h1 { margin: 0; }\"\n  - \"a::after { content: '</STYLE><script>'; }\"\n---\n\nText\n";
+    let html = write_html(&read_markdown(md).unwrap());
+    assert!(html.contains("h1 { margin: 0; }\na::after { content: '<\\/STYLE><script>'; }\n</style>"), "got: {}", html);
+    assert_eq!(html.matches("</style>").count(), 1);
+}
+
+#[test]
+fn test_code_fence_attributes_on_pre() {
+    let md = "```python {#demo .executable data-runnable=true}\nprint(1)\n```\n";
+    let html = write_html(&read_markdown(md).unwrap());
+    assert!(
+        html.contains("<pre id=\"demo\" class=\"executable\" data-runnable=\"true\"><code class=\"language-python\">"),
+        "got: {}",
+        html
+    );
+}
+
+#[test]
+fn test_code_caption_makes_listing_figure() {
+    let md = "```rust {caption=\"Listing 1: main.rs\"}\nfn main() {}\n```\n";
+    let html = write_html(&read_markdown(md).unwrap());
+    assert!(
+        html.contains("<figure class=\"listing\">\n<figcaption>Listing 1: main.rs</figcaption>\n<pre><code class=\"language-rust\">fn main() {}\n</code></pre>\n</figure>"),
+        "got: {}",
+        html
+    );
+
+    let html = write_html(&read_markdown("```rust\nfn main() {}\n```\n").unwrap());
+    assert!(!html.contains("<figure"), "got: {}", html);
+}
+
+#[test]
+fn test_numeric_entities_option() {
+    use pandorust::writers::html::write_html_with;
+    use pandorust::writers::options::WriterOptions;
+
+    let doc = read_markdown("---\nmainfont: Café Sans\n---\n\n10\u{a0}km, co\u{ad}operate [é](https://e.com \"Café\")\n").unwrap();
+    let options = WriterOptions { numeric_entities: true, ..Default::default() };
+    let html = write_html_with(&doc, &options);
+    assert!(html.contains("<p>10&#160;km, co&#173;operate <a href=\"https://e.com\" title=\"Caf&#233;\">&#233;</a></p>"), "got: {}", html);
+    // The stylesheet keeps raw UTF-8, where entities would not be decoded
+    assert!(html.contains("font-family: \"Café Sans\""), "got: {}", html);
+
+    let html = write_html(&doc);
+    assert!(html.contains("10\u{a0}km"), "raw UTF-8 by default");
+}
+
+#[test]
+fn test_ascii_option() {
+    use pandorust::writers::html::write_html_with;
+    use pandorust::writers::options::WriterOptions;
+
+    let doc = read_markdown("---\nmainfont: Café Sans\n---\n\ncafé — \u{201c}quote\u{201d} <!-- né -->\n").unwrap();
+    let options = WriterOptions { ascii: true, ..Default::default() };
+    let html = write_html_with(&doc, &options);
+    assert!(html.is_ascii(), "got: {}", html);
+    assert!(html.contains("<p>caf&#233; &#8212; &#8220;quote&#8221; <!-- n&#233; --></p>"), "got: {}", html);
+    assert!(html.contains("font-family: \"Caf\\E9  Sans\""), "got: {}", html);
+}
+
+#[test]
+fn test_tight_and_loose_ordered_lists() {
+    use pandorust::ast::*;
+
+    // Tight: no item wraps its text, even next to a code block
+    let html = write_html(&read_markdown("1. one\n2. two\n   ```\n   code\n   ```\n3. three\n").unwrap());
+    assert!(html.contains("<li>one</li>\n<li>two\n<pre><code>code\n</code></pre>\n</li>\n<li>three</li>"), "got: {}", html);
+
+    // Loose: every item wraps its text, including Plain items
+    let text = |s: &str| vec![Inline::Str(s.into())];
+    let doc = Document {
+        meta: Meta::default(),
+        blocks: vec![Block::OrderedList(
+            ListAttrs::default(),
+            vec![
+                vec![Block::Para(text("one"))],
+                vec![Block::Plain(text("two")), Block::CodeBlock(Attr::empty(), "code\n".into())],
+                vec![Block::Plain(text("three"))],
+            ],
+        )],
+    };
+    let html = write_html(&doc);
+    assert!(html.contains("<li><p>one</p>\n</li>\n<li><p>two</p>\n<pre><code>code\n</code></pre>\n</li>\n<li><p>three</p>\n</li>"), "got: {}", html);
+}
+
+#[test]
+fn test_nested_table_in_grid_cell() {
+    let md = "+-------------+-----+\n| Outer       | B   |\n+=============+=====+\n| | a | b |   | x   |\n| |---|---|   |     |\n| | 1 | 2 |   |     |\n+-------------+-----+\n";
+    let html = write_html(&read_markdown(md).unwrap());
+    assert!(
+        html.contains("<tr><td><table>\n<thead>\n<tr><th>a</th><th>b</th></tr>\n</thead>\n<tbody>\n<tr><td>1</td><td>2</td></tr>\n</tbody>\n</table>\n</td><td>x</td></tr>"),
+        "got: {}",
+        html
+    );
+}
+
+#[test]
+fn test_list_of_tables_uses_short_caption() {
+    use pandorust::ast::*;
+
+    let text = |s: &str| vec![Inline::Str(s.to_string())];
+    let mut meta = Meta::default();
+    meta.entries.insert("lot".to_string(), MetaValue::Bool(true));
+    let table = Table {
+        attr: Attr::empty(),
+        caption: Caption {
+            short: Some(text("Sales")),
+            long: vec![Block::Plain(text("Quarterly sales by region, in thousands"))],
+        },
+        col_specs: vec![ColSpec { align: Alignment::AlignDefault, width: ColWidth::Default }],
+        head: TableHead { attr: Attr::empty(), rows: vec![] },
+        bodies: vec![],
+        foot: TableFoot { attr: Attr::empty(), rows: vec![] },
+    };
+    let doc = Document { meta, blocks: vec![Block::Table(table)] };
+    let html = write_html(&doc);
+    assert!(
+        html.contains("<nav class=\"lot\">\n<h2>List of Tables</h2>\n<ol>\n<li>Sales</li>\n</ol>\n</nav>"),
+        "got: {}",
+        html
+    );
+    assert!(html.contains("<table>\n<caption>Quarterly sales by region, in thousands</caption>"), "got: {}", html);
+
+    // Without the flag there is no list
+    let doc = Document { meta: Meta::default(), blocks: doc.blocks };
+    assert!(!write_html(&doc).contains("List of Tables"));
+}
+
+#[test]
+fn test_raw_content_only_for_html() {
+    let md = "```{=latex}\n\\clearpage\n```\n\n```{=html5}\n<hr class=\"x\">\n```\n";
+    let html = write_html(&read_markdown(md).unwrap());
+    assert!(!html.contains("clearpage"), "LaTeX is dropped: {}", html);
+    assert!(html.contains("<hr class=\"x\">\n"), "got: {}", html);
+}
+
+#[test]
+fn test_only_format_divs() {
+    let md = "::: {.only-html}\nWeb only.\n:::\n\n::: {.only-docx}\nPrint only.\n:::\n\n::: aside\nEverywhere.\n:::\n";
+    let html = write_html(&read_markdown(md).unwrap());
+    assert!(html.contains("Web only."), "got: {}", html);
+    assert!(!html.contains("Print only."), "got: {}", html);
+    assert!(html.contains("Everywhere."), "got: {}", html);
+}
+
+#[test]
+fn test_bold_italic_nesting() {
+    let html = write_html(&read_markdown("A ***both*** and **_x_**.").unwrap());
+    assert!(html.contains("<em><strong>both</strong></em>"), "got: {}", html);
+    assert!(html.contains("<strong><em>x</em></strong>"), "got: {}", html);
+}
+
+#[test]
+fn test_inline_code_language_class() {
+    let html = write_html(&read_markdown("Write `let x = 1`{.rust} or `plain`.").unwrap());
+    assert!(html.contains("<code class=\"language-rust\">let x = 1</code> or <code>plain</code>."), "got: {}", html);
+}
+
+#[test]
+fn test_sanitize_option() {
+    use pandorust::writers::html::write_html_with;
+    use pandorust::writers::options::WriterOptions;
+
+    let md = "<script>alert(1)</script>\n\n\
+              [click](javascript:alert(1)) [ok](https://example.com) <b onclick=\"x()\">b</b>\n\n\
+              ![pic](JavaScript:alert(1)) `x`{onclick=\"x()\" data-src=javascript:y title=t}\n";
+    let doc = read_markdown(md).unwrap();
+    let options = WriterOptions { sanitize: true, ..Default::default() };
+    let html = write_html_with(&doc, &options);
+    assert!(!html.contains("<script>"), "got: {}", html);
+    assert!(!html.contains("onclick"), "got: {}", html);
+    assert!(!html.to_lowercase().contains("javascript:"), "got: {}", html);
+    assert!(html.contains("<a href=\"#\">click</a>"), "got: {}", html);
+    assert!(html.contains("<a href=\"https://example.com\">ok</a>"), "got: {}", html);
+    assert!(html.contains("<img src=\"#\" alt=\"pic\">"), "got: {}", html);
+    assert!(html.contains("<code title=\"t\">x</code>"), "got: {}", html);
+
+    // Without it raw HTML passes through
+    assert!(write_html(&doc).contains("<script>alert(1)</script>"));
+
+    // Front matter CSS is untrusted too
+    let doc = read_markdown("---\ncss: \"body { background: url(https://track.example/x.gif) }\"\n---\n\nText\n").unwrap();
+    let html = write_html_with(&doc, &options);
+    assert!(!html.contains("track.example"), "got: {}", html);
+    assert!(write_html(&doc).contains("track.example"));
+
+    // A quoted key with a space cannot smuggle in an event handler
+    let doc = read_markdown("```{\"x onclick\"=alert(1) data-lang=rs}\ncode\n```\n").unwrap();
+    let html = write_html_with(&doc, &options);
+    assert!(!html.contains("onclick"), "got: {}", html);
+    assert!(html.contains("<pre data-lang=\"rs\">"), "got: {}", html);
+    let doc = pandorust::ast::Document {
+        meta: Default::default(),
+        blocks: vec![pandorust::ast::Block::CodeBlock(
+            pandorust::ast::Attr {
+                id: String::new(),
+                classes: vec![],
+                attrs: vec![("x onclick".into(), "alert(1)".into())],
+            },
+            "code".into(),
+        )],
+    };
+    let html = write_html_with(&doc, &options);
+    assert!(html.contains("<pre><code>code</code></pre>"), "got: {}", html);
+}
+
+#[test]
+fn test_backslash_escapes_stay_literal() {
+    let html = write_html(&read_markdown("\\*not italic\\* and \\_x\\_ and \\[y\\]").unwrap());
+    assert!(html.contains("<p>*not italic* and _x_ and [y]</p>"), "got: {}", html);
+    assert!(!html.contains("<em>"));
+}
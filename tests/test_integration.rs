@@ -147,3 +147,61 @@ fn test_full_pipeline_no_metadata() {
     let docx_bytes = write_docx(&doc).unwrap();
     assert_eq!(&docx_bytes[0..2], b"PK", "DOCX should be a valid zip");
 }
+
+#[test]
+fn test_empty_and_front_matter_only_inputs() {
+    for input in ["", "   \n\n\t\n", "---\n---\n", "---\ntitle: Only Meta\n---\n"] {
+        let doc = read_markdown(input)
+            .unwrap_or_else(|e| panic!("{:?} failed to parse: {}", input, e));
+        assert!(doc.blocks.is_empty(), "{:?} gave blocks {:?}", input, doc.blocks);
+
+        let html = write_html(&doc);
+        assert!(html.starts_with("<!DOCTYPE html>"), "{:?}", input);
+        assert!(html.contains("<body>") && html.trim_end().ends_with("</html>"));
+
+        let bytes = write_docx(&doc).unwrap();
+        assert_eq!(&bytes[0..2], b"PK", "{:?} should give a zip", input);
+    }
+
+    let doc = read_markdown("---\ntitle: Only Meta\n---\n").unwrap();
+    assert_eq!(doc.meta.title(), Some("Only Meta"));
+}
+
+#[test]
+fn test_leading_rule_is_not_front_matter() {
+    let doc = read_markdown("----\n\nText\n\n----\n").unwrap();
+    assert!(doc.meta.entries.is_empty());
+    assert_eq!(doc.blocks.len(), 3, "got: {:?}", doc.blocks);
+}
+
+#[test]
+fn test_reference_link_title_in_html() {
+    let md = "See [the docs][docs] or [docs].\n\n[docs]: https://example.com/docs \"Project docs\"\n";
+    let html = write_html(&read_markdown(md).unwrap());
+    assert_eq!(
+        html.matches("<a href=\"https://example.com/docs\" title=\"Project docs\">").count(),
+        2,
+        "got: {}",
+        html
+    );
+}
+
+#[test]
+fn test_date_format_in_title_block() {
+    use std::io::Read;
+
+    let md = "---\ntitle: Report\ndate: 2026-01-01\ndate-format: \"%B %e, %Y\"\n---\n\nBody.\n";
+    let doc = read_markdown(md).unwrap();
+    let html = write_html(&doc);
+    assert!(html.contains("<p class=\"date\">January 1, 2026</p>"), "got: {}", html);
+
+    let bytes = write_docx(&doc).unwrap();
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+    let mut xml = String::new();
+    archive.by_name("word/document.xml").unwrap().read_to_string(&mut xml).unwrap();
+    assert!(xml.contains(">January 1, 2026</w:t>"), "got: {}", xml);
+
+    // A date that is not ISO is shown as written
+    let doc = read_markdown("---\ndate: Spring 2026\ndate-format: \"%Y\"\n---\n").unwrap();
+    assert!(write_html(&doc).contains("<p class=\"date\">Spring 2026</p>"));
+}
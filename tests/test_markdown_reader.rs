@@ -92,6 +92,25 @@ fn test_parse_link() {
     }
 }
 
+#[test]
+fn test_reference_link_keeps_title() {
+    let md = "[full][ref], [ref][] and [ref]\n\n[ref]: https://example.com \"Ref Title\"\n";
+    let doc = read_markdown(md).unwrap();
+    let Block::Para(inlines) = &doc.blocks[0] else {
+        panic!("Expected Para, got {:?}", doc.blocks[0]);
+    };
+    let titles: Vec<&str> = inlines
+        .iter()
+        .filter_map(|i| match i {
+            Inline::Link(_, _, target) if target.url == "https://example.com" => {
+                Some(target.title.as_str())
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(titles, vec!["Ref Title"; 3]);
+}
+
 #[test]
 fn test_parse_horizontal_rule() {
     let md = "Above\n\n---\n\nBelow";
@@ -137,3 +156,343 @@ fn test_parse_no_front_matter() {
     assert_eq!(doc.meta.title(), None);
     assert_eq!(doc.blocks.len(), 1);
 }
+
+#[test]
+fn test_parse_dollar_math() {
+    let doc = read_markdown("Area is $\\pi r^2$ and\n\n$$E = mc^2$$").unwrap();
+    match &doc.blocks[0] {
+        Block::Para(inlines) => {
+            assert!(inlines.contains(&Inline::Math(MathType::InlineMath, "\\pi r^2".into())));
+        }
+        other => panic!("Expected Para with Math, got {:?}", other),
+    }
+    match &doc.blocks[1] {
+        Block::Para(inlines) => {
+            assert!(inlines.contains(&Inline::Math(MathType::DisplayMath, "E = mc^2".into())));
+        }
+        other => panic!("Expected Para with display Math, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_fence_attributes() {
+    let doc = read_markdown("```rust {#main .wrap startFrom=\"10\"}\nfn main() {}\n```").unwrap();
+    match &doc.blocks[0] {
+        Block::CodeBlock(attr, code) => {
+            assert_eq!(attr.id, "main");
+            assert_eq!(attr.classes, vec!["rust", "wrap"]);
+            assert_eq!(attr.attrs, vec![("startFrom".to_string(), "10".to_string())]);
+            assert_eq!(code, "fn main() {}\n");
+        }
+        other => panic!("Expected CodeBlock, got {:?}", other),
+    }
+
+    let doc = read_markdown("```{.haskell}\nx\n```").unwrap();
+    match &doc.blocks[0] {
+        Block::CodeBlock(attr, _) => assert_eq!(attr.classes, vec!["haskell"]),
+        other => panic!("Expected CodeBlock, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_definition_list() {
+    let doc = read_markdown("Term\n\n: First\n\n: Second\n\nOther\n: Third\n").unwrap();
+    match &doc.blocks[0] {
+        Block::DefinitionList(items) => {
+            assert_eq!(items.len(), 2);
+            assert_eq!(items[0].0, vec![Inline::Str("Term".into())]);
+            assert_eq!(items[0].1.len(), 2, "both definitions belong to Term");
+            assert_eq!(items[1].0, vec![Inline::Str("Other".into())]);
+        }
+        other => panic!("Expected DefinitionList, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_highlight_mark() {
+    let doc = read_markdown("Some ==hi *there*== and a == b.").unwrap();
+    match &doc.blocks[0] {
+        Block::Para(inlines) => {
+            let span = inlines
+                .iter()
+                .find(|i| matches!(i, Inline::Span(..)))
+                .unwrap_or_else(|| panic!("no mark span: {:?}", inlines));
+            match span {
+                Inline::Span(attr, content) => {
+                    assert_eq!(attr.classes, vec!["mark"]);
+                    assert_eq!(content[0], Inline::Str("hi ".into()));
+                    assert_eq!(content[1], Inline::Emph(vec![Inline::Str("there".into())]));
+                }
+                _ => unreachable!(),
+            }
+            assert_eq!(inlines.last(), Some(&Inline::Str(" and a == b.".into())));
+        }
+        other => panic!("Expected Para, got {:?}", other),
+    }
+
+    use pandorust::readers::markdown::read_markdown_with;
+    use pandorust::readers::options::ReaderOptions;
+    let options = ReaderOptions { highlight: false, ..Default::default() };
+    let doc = read_markdown_with("==hi==", &options).unwrap();
+    assert_eq!(doc.blocks[0], Block::Para(vec![Inline::Str("==hi==".into())]));
+}
+
+#[test]
+fn test_parse_inline_html_tags_as_semantic_inlines() {
+    let doc = read_markdown("E = mc<sup>2</sup>, H<sub>2</sub>O, <kbd>Ctrl</kbd> and <span>x</span>").unwrap();
+    match &doc.blocks[0] {
+        Block::Para(inlines) => {
+            assert!(inlines.contains(&Inline::Superscript(vec![Inline::Str("2".into())])), "got: {:?}", inlines);
+            assert!(inlines.contains(&Inline::Subscript(vec![Inline::Str("2".into())])));
+            assert!(inlines.iter().any(|i| matches!(i, Inline::Code(attr, s) if s == "Ctrl" && attr.classes == ["kbd"])));
+            assert!(
+                inlines.contains(&Inline::RawInline(Format("html".into()), "<span>".into())),
+                "unknown tags stay raw"
+            );
+        }
+        other => panic!("Expected Para, got {:?}", other),
+    }
+
+    // An unmatched tag is left alone
+    let doc = read_markdown("a <sup>b").unwrap();
+    match &doc.blocks[0] {
+        Block::Para(inlines) => assert!(inlines.iter().any(|i| matches!(i, Inline::RawInline(..)))),
+        other => panic!("Expected Para, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_front_matter_variants() {
+    for md in [
+        "---\r\ntitle: Crlf\r\n---\r\n\r\nBody\r\n",
+        "---\ntitle: Dots\n...\n\nBody\n",
+        "\n\n---\ntitle: Leading\n---\n\nBody\n",
+    ] {
+        let doc = read_markdown(md).unwrap();
+        assert!(doc.meta.title().is_some(), "no title for {:?}", md);
+        assert_eq!(doc.blocks.len(), 1, "front matter leaked into body for {:?}: {:?}", md, doc.blocks);
+        match &doc.blocks[0] {
+            Block::Para(inlines) => assert_eq!(inlines, &vec![Inline::Str("Body".into())]),
+            other => panic!("Expected Para, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn test_footnote_reference_holds_definition() {
+    let doc = read_markdown("Claim[^1].\n\n[^1]: Source.\n").unwrap();
+    assert_eq!(doc.blocks.len(), 1, "definition is not a body block: {:?}", doc.blocks);
+    match &doc.blocks[0] {
+        Block::Para(inlines) => assert!(
+            inlines.contains(&Inline::Note("1".into(), vec![Block::Para(vec![Inline::Str("Source.".into())])])),
+            "got: {:?}",
+            inlines
+        ),
+        other => panic!("expected Para, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_substitutions_from_metadata() {
+    let md = "---\nsubstitutions:\n  client: Acme Corp\n  \"{{year}}\": 2026\n---\n\nPrepared for **{{client}}** in {{year}}. Keep `{{client}}` and {{unknown}}.\n";
+    let doc = read_markdown(md).unwrap();
+    match &doc.blocks[0] {
+        Block::Para(inlines) => {
+            assert_eq!(inlines[0], Inline::Str("Prepared for ".into()));
+            assert_eq!(inlines[1], Inline::Strong(vec![Inline::Str("Acme Corp".into())]));
+            assert_eq!(inlines[2], Inline::Str(" in 2026. Keep ".into()));
+            assert_eq!(inlines[3], Inline::Code(Attr::empty(), "{{client}}".into()));
+            assert_eq!(inlines[4], Inline::Str(" and {{unknown}}.".into()));
+        }
+        other => panic!("expected Para, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_keyword_fenced_div_becomes_div() {
+    let doc = read_markdown("::: warning\ntext\n:::\n\n::: {custom-style=\"X\"}\nplain\n:::\n").unwrap();
+    match &doc.blocks[0] {
+        Block::Div(attr, inner) => {
+            assert_eq!(attr.classes, vec!["warning".to_string()]);
+            assert_eq!(inner, &vec![Block::Para(vec![Inline::Str("text".into())])]);
+        }
+        other => panic!("expected Div, got {:?}", other),
+    }
+    // Attribute-brace divs are still unwrapped
+    assert_eq!(doc.blocks[1], Block::Para(vec![Inline::Str("plain".into())]));
+}
+
+#[test]
+fn test_commonmark_mode_leaves_extensions_literal() {
+    use pandorust::readers::markdown::read_markdown_with;
+    use pandorust::readers::options::ReaderOptions;
+
+    let md = "| A | B |\n|---|---|\n| 1 | 2 |\n\n~~gone~~\n\n- [x] done\n";
+    let options = ReaderOptions { commonmark: true, ..Default::default() };
+    let doc = read_markdown_with(md, &options).unwrap();
+    assert!(matches!(&doc.blocks[0], Block::Para(inlines) if matches!(&inlines[0], Inline::Str(s) if s == "| A | B |")), "got: {:?}", doc.blocks[0]);
+    assert_eq!(doc.blocks[1], Block::Para(vec![Inline::Str("~~gone~~".into())]));
+    assert_eq!(doc.blocks[2], Block::BulletList(vec![vec![Block::Plain(vec![Inline::Str("[x] done".into())])]]));
+
+    let doc = read_markdown(md).unwrap();
+    assert!(matches!(doc.blocks[0], Block::Table(_)));
+    assert!(matches!(&doc.blocks[1], Block::Para(inlines) if matches!(inlines[0], Inline::Strikeout(_))));
+    assert_eq!(
+        doc.blocks[2],
+        Block::BulletList(vec![vec![Block::Plain(vec![Inline::task_checkbox(true), Inline::Space, Inline::Str("done".into())])]])
+    );
+}
+
+#[test]
+fn test_task_items_come_from_list_syntax_only() {
+    // A ballot box typed by hand is text, not a checkbox
+    let doc = read_markdown("- [x]\n- \u{2612} typed by hand\n- [ ] *open* item\n").unwrap();
+    assert_eq!(doc.task_counts(), (1, 2), "got: {:?}", doc.blocks);
+    let Block::BulletList(items) = &doc.blocks[0] else {
+        panic!("Expected BulletList, got {:?}", doc.blocks[0]);
+    };
+    assert_eq!(items[0], vec![Block::Plain(vec![Inline::task_checkbox(true)])]);
+    assert!(matches!(&items[2][0], Block::Plain(inlines) if inlines[0] == Inline::task_checkbox(false) && matches!(inlines[2], Inline::Emph(_))));
+}
+
+#[test]
+fn test_backslash_space_is_nonbreaking() {
+    let doc = read_markdown("See Fig.\\ 1, not a\\\\ b or `c\\ d`.\n\n    Fig.\\ 2\n").unwrap();
+    let Block::Para(inlines) = &doc.blocks[0] else {
+        panic!("Expected Para, got {:?}", doc.blocks[0]);
+    };
+    assert_eq!(inlines[0], Inline::Str("See Fig.\u{a0}1, not a\\ b or ".into()));
+    assert!(inlines.iter().any(|i| matches!(i, Inline::Code(_, s) if s == "c\\ d")));
+    assert_eq!(doc.blocks[1], Block::CodeBlock(Attr::empty(), "Fig.\\ 2\n".into()));
+
+    // Front matter, raw HTML and multi-line code spans keep the backslash;
+    // list continuation paragraphs convert it
+    let md = "---\nnote: a\\ b\n---\n\n<div>x\\ y</div>\n\nSee `one\ntwo\\ three`.\n\n- item\n\n    Fig.\\ 3\n";
+    let doc = read_markdown(md).unwrap();
+    assert_eq!(doc.meta.get_str("note"), Some("a\\ b"));
+    assert_eq!(doc.blocks[0], Block::RawBlock(Format("html".into()), "<div>x\\ y</div>\n".into()));
+    assert!(matches!(&doc.blocks[1], Block::Para(inlines) if inlines.iter().any(|i| matches!(i, Inline::Code(_, s) if s == "one two\\ three"))), "got: {:?}", doc.blocks[1]);
+    let Block::BulletList(items) = &doc.blocks[2] else {
+        panic!("Expected BulletList, got {:?}", doc.blocks[2]);
+    };
+    assert_eq!(items[0][1], Block::Para(vec![Inline::Str("Fig.\u{a0}3".into())]));
+}
+
+#[test]
+fn test_raw_attribute_fence_is_raw_block() {
+    let doc = read_markdown("```{=latex}\n\\clearpage\n```\n\n```{=html}\n<hr class=\"x\">\n```\n").unwrap();
+    assert_eq!(doc.blocks[0], Block::RawBlock(Format("latex".into()), "\\clearpage\n".into()));
+    assert_eq!(doc.blocks[1], Block::RawBlock(Format("html".into()), "<hr class=\"x\">\n".into()));
+}
+
+#[test]
+fn test_indented_code_can_be_disabled() {
+    use pandorust::readers::markdown::read_markdown_with;
+    use pandorust::readers::options::ReaderOptions;
+
+    let md = "Intro\n\n    Indented for *looks*\n    over two lines\n\n```\nfenced\n```\n";
+    assert!(matches!(read_markdown(md).unwrap().blocks[1], Block::CodeBlock(..)));
+
+    let options = ReaderOptions { indented_code: false, ..Default::default() };
+    let doc = read_markdown_with(md, &options).unwrap();
+    assert_eq!(doc.blocks.len(), 3, "got: {:?}", doc.blocks);
+    let Block::Para(inlines) = &doc.blocks[1] else {
+        panic!("Expected Para, got {:?}", doc.blocks[1]);
+    };
+    assert_eq!(inlines[0], Inline::Str("Indented for ".into()));
+    assert!(matches!(inlines[1], Inline::Emph(_)));
+    assert!(matches!(doc.blocks[2], Block::CodeBlock(_, ref code) if code == "fenced\n"));
+}
+
+#[test]
+fn test_github_alerts() {
+    use pandorust::readers::markdown::read_markdown_with;
+    use pandorust::readers::options::ReaderOptions;
+
+    let md = "> [!WARNING]\n> be careful\n";
+    assert!(matches!(read_markdown(md).unwrap().blocks[0], Block::BlockQuote(_)));
+
+    let options = ReaderOptions { github_alerts: true, ..Default::default() };
+    let doc = read_markdown_with(md, &options).unwrap();
+    let Block::Div(attr, blocks) = &doc.blocks[0] else {
+        panic!("Expected Div, got {:?}", doc.blocks[0]);
+    };
+    assert_eq!(attr.classes, vec!["alert", "alert-warning"]);
+    assert_eq!(blocks, &vec![Block::Para(vec![Inline::Str("be careful".into())])]);
+}
+
+#[test]
+fn test_inline_code_attributes() {
+    let doc = read_markdown("Run `cargo`{#cmd .sh} now and `{x}`").unwrap();
+    let Block::Para(inlines) = &doc.blocks[0] else {
+        panic!("Expected Para, got {:?}", doc.blocks[0]);
+    };
+    let code = inlines.iter().position(|i| matches!(i, Inline::Code(..))).unwrap();
+    let Inline::Code(attr, text) = &inlines[code] else { unreachable!() };
+    assert_eq!(text, "cargo");
+    assert_eq!(attr.id, "cmd");
+    assert_eq!(attr.classes, vec!["sh"]);
+    assert_eq!(inlines[code + 1], Inline::Str(" now and ".into()));
+    // Braces inside code are just code
+    assert_eq!(inlines.last(), Some(&Inline::Code(Attr::empty(), "{x}".into())));
+}
+
+#[test]
+fn test_inline_code_braces_that_are_not_attributes_stay_text() {
+    let doc = read_markdown("A `HashMap`{K, V} and `f`{x} here").unwrap();
+    let Block::Para(inlines) = &doc.blocks[0] else {
+        panic!("Expected Para, got {:?}", doc.blocks[0]);
+    };
+    assert!(inlines.iter().all(|i| !matches!(i, Inline::Code(attr, _) if *attr != Attr::empty())), "got: {:?}", inlines);
+    let text: String = inlines
+        .iter()
+        .map(|i| match i {
+            Inline::Str(s) => s.clone(),
+            Inline::Code(_, c) => format!("`{}`", c),
+            Inline::Space => " ".into(),
+            _ => String::new(),
+        })
+        .collect();
+    assert_eq!(text, "A `HashMap`{K, V} and `f`{x} here");
+}
+
+#[test]
+fn test_backslash_escapes_are_text() {
+    let doc = read_markdown("\\*x\\*").unwrap();
+    let Block::Para(inlines) = &doc.blocks[0] else {
+        panic!("Expected Para, got {:?}", doc.blocks[0]);
+    };
+    let text: String = inlines
+        .iter()
+        .map(|i| match i {
+            Inline::Str(s) => s.as_str(),
+            other => panic!("unexpected {:?}", other),
+        })
+        .collect();
+    assert_eq!(text, "*x*");
+}
+
+#[test]
+fn test_parse_raw_html_tables() {
+    use pandorust::readers::markdown::read_markdown_with;
+    use pandorust::readers::options::ReaderOptions;
+
+    let md = "<table>\n<caption>Stock</caption>\n<thead><tr><th>Item</th><th align=\"right\">Qty</th></tr></thead>\n\
+              <tbody><tr><td>*Apples*</td><td align=\"right\">3</td></tr>\n\
+              <tr><td colspan=\"2\">Sold out</td></tr></tbody>\n</table>\n";
+    assert!(matches!(read_markdown(md).unwrap().blocks[0], Block::RawBlock(..)));
+
+    let options = ReaderOptions { html_tables: true, ..Default::default() };
+    let doc = read_markdown_with(md, &options).unwrap();
+    let Block::Table(table) = &doc.blocks[0] else {
+        panic!("Expected Table, got {:?}", doc.blocks[0]);
+    };
+    assert_eq!(table.caption.long, vec![Block::Plain(vec![Inline::Str("Stock".into())])]);
+    assert_eq!(table.col_specs.len(), 2);
+    assert_eq!(table.col_specs[1].align, Alignment::AlignRight);
+    assert_eq!(table.head.rows.len(), 1);
+    let body = &table.bodies[0].body;
+    assert_eq!(body.len(), 2);
+    assert!(matches!(&body[0].cells[0].content[..], [Block::Plain(inlines)] if matches!(inlines[0], Inline::Emph(_))));
+    assert_eq!(body[1].cells[0].col_span, 2);
+}